@@ -0,0 +1,462 @@
+extern crate rand;
+
+use rand::{Rng, FromEntropy};
+use rand::rngs::StdRng;
+
+use super::matrix::Matrix;
+
+// A stack of 2D feature maps, one height x width Matrix per channel
+pub type FeatureMaps = Vec<Matrix>;
+
+fn output_length(input: usize, kernel: usize, stride: usize, padding: usize, dilation: usize) -> usize {
+    let span = dilation * (kernel - 1) + 1;
+    if input + 2 * padding < span {
+        panic!("Kernel is larger than the padded input!");
+    }
+    return (input + 2 * padding - span) / stride + 1;
+}
+
+// Height and width shared by every channel
+fn map_size(maps: &FeatureMaps) -> (usize, usize) {
+    if maps.is_empty() {
+        panic!("Feature maps must have at least one channel!");
+    }
+    let size = maps[0].size();
+    if maps.iter().any(|map| map.size() != size) {
+        panic!("Incompatible Matrix Dimensions!");
+    }
+    return size;
+}
+
+fn zero_maps(channels: usize, height: usize, width: usize) -> FeatureMaps {
+    return (0..channels).map(|_| {
+        let mut map = Matrix::new();
+        map.zero_fill(height, width);
+        map
+    }).collect();
+}
+
+// 2D convolution (strictly, cross-correlation as in most libraries) over
+// multi-channel inputs. Patches are unrolled into the columns of a matrix so
+// the whole layer is one matrix product per sample.
+pub struct Conv2D {
+    in_channels: usize,
+    out_channels: usize,
+    kernel_size: usize,
+    stride: usize,
+    padding: usize,
+    dilation: usize,
+    // out_channels x (in_channels * kernel_size * kernel_size)
+    weights: Matrix,
+    biases: Vec<f64>,
+    weight_gradients: Matrix,
+    bias_gradients: Vec<f64>,
+    // Unrolled patches and sizes from the last forward pass
+    columns: Matrix,
+    input_size: (usize, usize),
+    output_size: (usize, usize),
+}
+
+impl Conv2D {
+    pub fn new(in_channels: usize, out_channels: usize, kernel_size: usize) -> Conv2D {
+        if in_channels == 0 || out_channels == 0 || kernel_size == 0 {
+            panic!("Channels and kernel size must be at least 1!");
+        }
+        let fan_in = in_channels * kernel_size * kernel_size;
+        let mut weights = Matrix::new();
+        weights.zero_fill(out_channels, fan_in);
+        let mut weight_gradients = Matrix::new();
+        weight_gradients.zero_fill(out_channels, fan_in);
+        let mut conv = Conv2D {
+            in_channels,
+            out_channels,
+            kernel_size,
+            stride: 1,
+            padding: 0,
+            dilation: 1,
+            weights,
+            biases: vec![0.0; out_channels],
+            weight_gradients,
+            bias_gradients: vec![0.0; out_channels],
+            columns: Matrix::new(),
+            input_size: (0, 0),
+            output_size: (0, 0),
+        };
+        conv.initialize(&mut StdRng::from_entropy());
+        return conv;
+    }
+
+    // Redraws the weights uniformly in +-1 / sqrt(fan_in) from `rng` and
+    // zeroes the biases
+    pub fn initialize(&mut self, rng: &mut StdRng) {
+        let (out_channels, fan_in) = self.weights.size();
+        let bound = 1.0 / (fan_in as f64).sqrt();
+        let data: Vec<f64> = (0..(out_channels * fan_in)).map(|_| rng.gen_range(-bound, bound)).collect();
+        self.weights = Matrix::from_vec(&data, out_channels, fan_in);
+        self.biases = vec![0.0; out_channels];
+    }
+
+    pub fn set_stride(&mut self, stride: usize) {
+        if stride == 0 {
+            panic!("Stride must be at least 1!");
+        }
+        self.stride = stride;
+    }
+
+    // Zeros added on every side of the input
+    pub fn set_padding(&mut self, padding: usize) {
+        self.padding = padding;
+    }
+
+    // Spacing between kernel taps; 1 is a dense kernel
+    pub fn set_dilation(&mut self, dilation: usize) {
+        if dilation == 0 {
+            panic!("Dilation must be at least 1!");
+        }
+        self.dilation = dilation;
+    }
+
+    // One row per output channel, laid out as (in_channel, kernel_row, kernel_col)
+    pub fn get_weights(&self) -> &Matrix {
+        return &self.weights;
+    }
+
+    pub fn set_weights(&mut self, weights: Matrix) {
+        if weights.size() != self.weights.size() {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        self.weights = weights;
+    }
+
+    pub fn get_biases(&self) -> &Vec<f64> {
+        return &self.biases;
+    }
+
+    pub fn set_biases(&mut self, biases: Vec<f64>) {
+        if biases.len() != self.out_channels {
+            panic!("Incompatible Vector Dimensions!");
+        }
+        self.biases = biases;
+    }
+
+    // Gradients accumulated by `backward` since the last `update`
+    pub fn get_weight_gradients(&self) -> &Matrix {
+        return &self.weight_gradients;
+    }
+
+    pub fn get_bias_gradients(&self) -> &Vec<f64> {
+        return &self.bias_gradients;
+    }
+
+    // Height and width of the output for an input of the given size
+    pub fn output_size(&self, height: usize, width: usize) -> (usize, usize) {
+        return (
+            output_length(height, self.kernel_size, self.stride, self.padding, self.dilation),
+            output_length(width, self.kernel_size, self.stride, self.padding, self.dilation),
+        );
+    }
+
+    // Input position read by a kernel tap, or None when it falls in the padding
+    fn source(&self, output: usize, tap: usize, length: usize) -> Option<usize> {
+        let position = (output * self.stride + tap * self.dilation) as isize - self.padding as isize;
+        if position < 0 || position >= length as isize {
+            return None;
+        }
+        return Some(position as usize);
+    }
+
+    pub fn forward(&mut self, inputs: &FeatureMaps) -> FeatureMaps {
+        if inputs.len() != self.in_channels {
+            panic!("Incompatible number of channels!");
+        }
+        let (height, width) = map_size(inputs);
+        let (out_height, out_width) = self.output_size(height, width);
+        let k = self.kernel_size;
+
+        let mut columns = Matrix::new();
+        columns.zero_fill(self.in_channels * k * k, out_height * out_width);
+        for (c, map) in inputs.iter().enumerate() {
+            for ki in 0..k {
+                for kj in 0..k {
+                    let row = c * k * k + ki * k + kj;
+                    for oi in 0..out_height {
+                        let i = match self.source(oi, ki, height) {
+                            Some(i) => i,
+                            None => continue,
+                        };
+                        for oj in 0..out_width {
+                            if let Some(j) = self.source(oj, kj, width) {
+                                columns.set_at_index(row, oi * out_width + oj, map.get_at_index(i, j));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let outputs = self.weights.dot_prod(&columns);
+        self.columns = columns;
+        self.input_size = (height, width);
+        self.output_size = (out_height, out_width);
+        return (0..self.out_channels).map(|o| {
+            let bias = self.biases[o];
+            let data: Vec<f64> = outputs.get_row(o).iter().map(|x| x + bias).collect();
+            Matrix::from_vec(&data, out_height, out_width)
+        }).collect();
+    }
+
+    // Accumulates parameter gradients for the last forward pass and returns
+    // the gradient with respect to its inputs
+    pub fn backward(&mut self, output_gradients: &FeatureMaps) -> FeatureMaps {
+        if self.columns.size().0 == 0 {
+            panic!("Backward called before forward!");
+        }
+        if output_gradients.len() != self.out_channels || map_size(output_gradients) != self.output_size {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        let (height, width) = self.input_size;
+        let (out_height, out_width) = self.output_size;
+        let k = self.kernel_size;
+
+        let data: Vec<f64> = output_gradients.iter().flat_map(|map| map.as_vec()).collect();
+        let gradients = Matrix::from_vec(&data, self.out_channels, out_height * out_width);
+        self.weight_gradients = self.weight_gradients.add(&gradients.dot_prod(&self.columns.transpose()));
+        for o in 0..self.out_channels {
+            self.bias_gradients[o] += gradients.get_row(o).iter().sum::<f64>();
+        }
+
+        // Scatter the patch gradients back onto the input positions they came from
+        let column_gradients = self.weights.transpose().dot_prod(&gradients);
+        let mut result = zero_maps(self.in_channels, height, width);
+        for (c, map) in result.iter_mut().enumerate() {
+            for ki in 0..k {
+                for kj in 0..k {
+                    let row = c * k * k + ki * k + kj;
+                    for oi in 0..out_height {
+                        let i = match self.source(oi, ki, height) {
+                            Some(i) => i,
+                            None => continue,
+                        };
+                        for oj in 0..out_width {
+                            if let Some(j) = self.source(oj, kj, width) {
+                                let value = map.get_at_index(i, j) + column_gradients.get_at_index(row, oi * out_width + oj);
+                                map.set_at_index(i, j, value);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        return result;
+    }
+
+    // Gradient descent step with the accumulated gradients, which are then reset
+    pub fn update(&mut self, learning_rate: f64) {
+        let mut step = self.weight_gradients.clone();
+        step.map(|g| g * learning_rate);
+        self.weights = self.weights.subtract(&step);
+        for o in 0..self.out_channels {
+            self.biases[o] -= learning_rate * self.bias_gradients[o];
+        }
+        self.weight_gradients.map(|_| 0.0);
+        self.bias_gradients = vec![0.0; self.out_channels];
+    }
+}
+
+// Windows of a pooling layer over one map: (output row, output col) -> the
+// input positions it covers
+fn pool_windows(height: usize, width: usize, size: usize, stride: usize) -> (usize, usize, Vec<Vec<(usize, usize)>>) {
+    let out_height = output_length(height, size, stride, 0, 1);
+    let out_width = output_length(width, size, stride, 0, 1);
+    let mut windows: Vec<Vec<(usize, usize)>> = Vec::new();
+    for oi in 0..out_height {
+        for oj in 0..out_width {
+            let mut window: Vec<(usize, usize)> = Vec::new();
+            for di in 0..size {
+                for dj in 0..size {
+                    window.push((oi * stride + di, oj * stride + dj));
+                }
+            }
+            windows.push(window);
+        }
+    }
+    return (out_height, out_width, windows);
+}
+
+// Keeps the largest value of each window; gradients flow only to that value
+pub struct MaxPool2D {
+    size: usize,
+    stride: usize,
+    input_size: (usize, usize),
+    output_size: (usize, usize),
+    // Position of the winning input for every output, per channel
+    winners: Vec<Vec<(usize, usize)>>,
+}
+
+impl MaxPool2D {
+    // Non-overlapping size x size windows
+    pub fn new(size: usize) -> MaxPool2D {
+        if size == 0 {
+            panic!("Pool size must be at least 1!");
+        }
+        MaxPool2D {
+            size,
+            stride: size,
+            input_size: (0, 0),
+            output_size: (0, 0),
+            winners: Vec::new(),
+        }
+    }
+
+    pub fn set_stride(&mut self, stride: usize) {
+        if stride == 0 {
+            panic!("Stride must be at least 1!");
+        }
+        self.stride = stride;
+    }
+
+    pub fn forward(&mut self, inputs: &FeatureMaps) -> FeatureMaps {
+        let (height, width) = map_size(inputs);
+        let (out_height, out_width, windows) = pool_windows(height, width, self.size, self.stride);
+        self.winners = Vec::new();
+        let mut result: FeatureMaps = Vec::new();
+        for map in inputs {
+            let mut winners: Vec<(usize, usize)> = Vec::new();
+            for window in &windows {
+                let mut best = window[0];
+                for &(i, j) in window {
+                    if map.get_at_index(i, j) > map.get_at_index(best.0, best.1) {
+                        best = (i, j);
+                    }
+                }
+                winners.push(best);
+            }
+            let data: Vec<f64> = winners.iter().map(|&(i, j)| map.get_at_index(i, j)).collect();
+            result.push(Matrix::from_vec(&data, out_height, out_width));
+            self.winners.push(winners);
+        }
+        self.input_size = (height, width);
+        self.output_size = (out_height, out_width);
+        return result;
+    }
+
+    pub fn backward(&mut self, output_gradients: &FeatureMaps) -> FeatureMaps {
+        if output_gradients.len() != self.winners.len() || map_size(output_gradients) != self.output_size {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        let (height, width) = self.input_size;
+        let mut result = zero_maps(output_gradients.len(), height, width);
+        for (c, gradients) in output_gradients.iter().enumerate() {
+            for (index, gradient) in gradients.as_vec().iter().enumerate() {
+                let (i, j) = self.winners[c][index];
+                let value = result[c].get_at_index(i, j) + gradient;
+                result[c].set_at_index(i, j, value);
+            }
+        }
+        return result;
+    }
+}
+
+// Averages each window; gradients are shared equally across it
+pub struct AvgPool2D {
+    size: usize,
+    stride: usize,
+    channels: usize,
+    input_size: (usize, usize),
+    output_size: (usize, usize),
+}
+
+impl AvgPool2D {
+    pub fn new(size: usize) -> AvgPool2D {
+        if size == 0 {
+            panic!("Pool size must be at least 1!");
+        }
+        AvgPool2D {
+            size,
+            stride: size,
+            channels: 0,
+            input_size: (0, 0),
+            output_size: (0, 0),
+        }
+    }
+
+    pub fn set_stride(&mut self, stride: usize) {
+        if stride == 0 {
+            panic!("Stride must be at least 1!");
+        }
+        self.stride = stride;
+    }
+
+    pub fn forward(&mut self, inputs: &FeatureMaps) -> FeatureMaps {
+        let (height, width) = map_size(inputs);
+        let (out_height, out_width, windows) = pool_windows(height, width, self.size, self.stride);
+        let area = (self.size * self.size) as f64;
+        self.channels = inputs.len();
+        self.input_size = (height, width);
+        self.output_size = (out_height, out_width);
+        return inputs.iter().map(|map| {
+            let data: Vec<f64> = windows.iter()
+                .map(|window| window.iter().map(|&(i, j)| map.get_at_index(i, j)).sum::<f64>() / area)
+                .collect();
+            Matrix::from_vec(&data, out_height, out_width)
+        }).collect();
+    }
+
+    pub fn backward(&mut self, output_gradients: &FeatureMaps) -> FeatureMaps {
+        if output_gradients.len() != self.channels || map_size(output_gradients) != self.output_size {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        let (height, width) = self.input_size;
+        let (_, _, windows) = pool_windows(height, width, self.size, self.stride);
+        let area = (self.size * self.size) as f64;
+        let mut result = zero_maps(self.channels, height, width);
+        for (c, gradients) in output_gradients.iter().enumerate() {
+            for (window, gradient) in windows.iter().zip(gradients.as_vec()) {
+                for &(i, j) in window {
+                    let value = result[c].get_at_index(i, j) + gradient / area;
+                    result[c].set_at_index(i, j, value);
+                }
+            }
+        }
+        return result;
+    }
+}
+
+// Unrolls feature maps channel by channel, row by row into a single vector,
+// e.g. to feed a dense layer
+pub struct Flatten {
+    channels: usize,
+    input_size: (usize, usize),
+}
+
+impl Flatten {
+    pub fn new() -> Flatten {
+        Flatten {
+            channels: 0,
+            input_size: (0, 0),
+        }
+    }
+
+    pub fn forward(&mut self, inputs: &FeatureMaps) -> Vec<f64> {
+        self.input_size = map_size(inputs);
+        self.channels = inputs.len();
+        return inputs.iter().flat_map(|map| map.as_vec()).collect();
+    }
+
+    pub fn backward(&mut self, output_gradients: &Vec<f64>) -> FeatureMaps {
+        if self.channels == 0 {
+            panic!("Backward called before forward!");
+        }
+        let (height, width) = self.input_size;
+        if output_gradients.len() != self.channels * height * width {
+            panic!("Incompatible Vector Dimensions!");
+        }
+        return output_gradients.chunks(height * width)
+            .map(|chunk| Matrix::from_vec(&chunk.to_vec(), height, width))
+            .collect();
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_convolution.rs"]
+mod test;
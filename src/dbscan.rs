@@ -0,0 +1,81 @@
+use super::matrix::Matrix;
+use super::math::euclidean;
+
+// Density-based clustering: points with at least `min_points` neighbors
+// (themselves included) within `eps` are core points, clusters grow from core
+// points through their neighborhoods, and anything unreachable is noise.
+pub struct DBSCAN {
+    eps: f64,
+    min_points: usize,
+    labels: Vec<Option<usize>>,
+    core_points: Vec<usize>,
+}
+
+impl DBSCAN {
+    pub fn new(eps: f64, min_points: usize) -> DBSCAN {
+        if eps <= 0.0 {
+            panic!("eps must be positive!");
+        }
+        DBSCAN {
+            eps,
+            min_points,
+            labels: Vec::new(),
+            core_points: Vec::new(),
+        }
+    }
+
+    // Cluster of every training point, `None` for noise
+    pub fn get_labels(&self) -> &Vec<Option<usize>> {
+        return &self.labels;
+    }
+
+    pub fn get_core_points(&self) -> &Vec<usize> {
+        return &self.core_points;
+    }
+
+    pub fn get_num_clusters(&self) -> usize {
+        return self.labels.iter().filter_map(|&label| label).max().map_or(0, |max| max + 1);
+    }
+
+    pub fn fit(&mut self, inputs: &Matrix) {
+        let points = inputs.get_rows();
+        let n = points.len();
+        let neighborhoods: Vec<Vec<usize>> = (0..n)
+            .map(|i| (0..n).filter(|&j| euclidean(&points[i], &points[j]) <= self.eps).collect())
+            .collect();
+        let is_core: Vec<bool> = neighborhoods.iter().map(|hood| hood.len() >= self.min_points).collect();
+
+        self.labels = vec![None; n];
+        self.core_points = (0..n).filter(|&i| is_core[i]).collect();
+        let mut cluster = 0;
+        for start in 0..n {
+            if !is_core[start] || self.labels[start].is_some() {
+                continue;
+            }
+            self.labels[start] = Some(cluster);
+            let mut frontier = vec![start];
+            while let Some(point) = frontier.pop() {
+                // Border points join the cluster but do not extend it
+                if !is_core[point] {
+                    continue;
+                }
+                for &neighbor in &neighborhoods[point] {
+                    if self.labels[neighbor].is_none() {
+                        self.labels[neighbor] = Some(cluster);
+                        frontier.push(neighbor);
+                    }
+                }
+            }
+            cluster += 1;
+        }
+    }
+
+    pub fn fit_predict(&mut self, inputs: &Matrix) -> Vec<Option<usize>> {
+        self.fit(inputs);
+        return self.labels.clone();
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_dbscan.rs"]
+mod test;
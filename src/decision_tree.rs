@@ -0,0 +1,471 @@
+extern crate rand;
+
+use super::matrix::Matrix;
+use super::math::argmax;
+
+pub enum Criterion {
+    Gini,
+    Entropy,
+    MSE,
+}
+
+enum Node {
+    // Class probabilities for classification, a single mean for regression
+    Leaf {
+        value: Vec<f64>,
+    },
+    // Rows with x[feature] <= threshold go left
+    Split {
+        feature: usize,
+        threshold: f64,
+        left: usize,
+        right: usize,
+    },
+}
+
+enum Targets<'a> {
+    Classes(&'a Vec<usize>, usize),
+    Values(&'a Vec<f64>),
+}
+
+// Running statistics of the targets on one side of a split
+#[derive(Clone)]
+enum Stats {
+    Classes(Vec<f64>, f64),
+    Values(f64, f64, f64),
+}
+
+impl Stats {
+    fn empty(targets: &Targets) -> Stats {
+        match targets {
+            Targets::Classes(_, num_classes) => Stats::Classes(vec![0.0; *num_classes], 0.0),
+            Targets::Values(_) => Stats::Values(0.0, 0.0, 0.0),
+        }
+    }
+
+    fn add(&mut self, targets: &Targets, index: usize, sign: f64) {
+        match (self, targets) {
+            (Stats::Classes(counts, total), Targets::Classes(labels, _)) => {
+                counts[labels[index]] += sign;
+                *total += sign;
+            },
+            (Stats::Values(sum, sum_squares, total), Targets::Values(values)) => {
+                *sum += sign * values[index];
+                *sum_squares += sign * values[index] * values[index];
+                *total += sign;
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    fn count(&self) -> f64 {
+        match self {
+            Stats::Classes(_, total) => *total,
+            Stats::Values(_, _, total) => *total,
+        }
+    }
+
+    fn impurity(&self, criterion: &Criterion) -> f64 {
+        match (self, criterion) {
+            (Stats::Classes(counts, total), Criterion::Gini) => {
+                return 1.0 - counts.iter().map(|c| (c / total) * (c / total)).sum::<f64>();
+            },
+            (Stats::Classes(counts, total), Criterion::Entropy) => {
+                return -counts.iter()
+                    .filter(|&&c| c > 0.0)
+                    .map(|c| (c / total) * (c / total).log2())
+                    .sum::<f64>();
+            },
+            (Stats::Values(sum, sum_squares, total), Criterion::MSE) => {
+                let mean = sum / total;
+                return (sum_squares / total - mean * mean).max(0.0);
+            },
+            _ => panic!("Criterion does not match the tree type!"),
+        }
+    }
+
+    fn leaf_value(&self) -> Vec<f64> {
+        match self {
+            Stats::Classes(counts, total) => counts.iter().map(|c| c / total).collect(),
+            Stats::Values(sum, _, total) => vec![sum / total],
+        }
+    }
+}
+
+struct Tree {
+    criterion: Criterion,
+    max_depth: Option<usize>,
+    min_samples_split: usize,
+    min_samples_leaf: usize,
+    max_features: Option<usize>,
+    num_features: usize,
+    nodes: Vec<Node>,
+    importances: Vec<f64>,
+}
+
+impl Tree {
+    fn new(criterion: Criterion) -> Tree {
+        Tree {
+            criterion,
+            max_depth: None,
+            min_samples_split: 2,
+            min_samples_leaf: 1,
+            max_features: None,
+            num_features: 0,
+            nodes: Vec::new(),
+            importances: Vec::new(),
+        }
+    }
+
+    fn fit(&mut self, inputs: &Matrix, targets: &Targets, indices: Vec<usize>) {
+        let rows = inputs.get_rows();
+        self.num_features = inputs.size().1;
+        self.nodes = Vec::new();
+        self.importances = vec![0.0; self.num_features];
+
+        self.grow(&rows, targets, indices, 0);
+
+        let total: f64 = self.importances.iter().sum();
+        if total > 0.0 {
+            for importance in self.importances.iter_mut() {
+                *importance /= total;
+            }
+        }
+    }
+
+    // Builds the subtree for `indices` and returns its node index
+    fn grow(&mut self, rows: &Vec<Vec<f64>>, targets: &Targets, indices: Vec<usize>, depth: usize) -> usize {
+        let mut stats = Stats::empty(targets);
+        for &i in &indices {
+            stats.add(targets, i, 1.0);
+        }
+        let impurity = stats.impurity(&self.criterion);
+
+        let below_max_depth = match self.max_depth {
+            Some(max) => depth < max,
+            None => true,
+        };
+        let can_split = indices.len() >= self.min_samples_split
+            && indices.len() >= 2 * self.min_samples_leaf
+            && impurity > 1e-12
+            && below_max_depth;
+
+        let split = if can_split { self.best_split(rows, targets, &indices, &stats) } else { None };
+        match split {
+            None => {
+                self.nodes.push(Node::Leaf { value: stats.leaf_value() });
+                return self.nodes.len() - 1;
+            },
+            Some((feature, threshold, child_impurity)) => {
+                self.importances[feature] += indices.len() as f64 * impurity - child_impurity;
+
+                let (left, right): (Vec<usize>, Vec<usize>) = indices.iter()
+                    .partition(|&&i| rows[i][feature] <= threshold);
+
+                // Reserve this node's slot before growing the children
+                let node = self.nodes.len();
+                self.nodes.push(Node::Leaf { value: Vec::new() });
+                let left = self.grow(rows, targets, left, depth + 1);
+                let right = self.grow(rows, targets, right, depth + 1);
+                self.nodes[node] = Node::Split { feature, threshold, left, right };
+                return node;
+            },
+        }
+    }
+
+    // (feature, threshold, sample-weighted impurity of the children)
+    fn best_split(&self, rows: &Vec<Vec<f64>>, targets: &Targets, indices: &Vec<usize>, stats: &Stats) -> Option<(usize, f64, f64)> {
+        let features: Vec<usize> = match self.max_features {
+            Some(max) if max < self.num_features => {
+                let mut rng = rand::thread_rng();
+                rand::seq::index::sample(&mut rng, self.num_features, max).into_vec()
+            },
+            _ => (0..self.num_features).collect(),
+        };
+
+        let total = indices.len();
+        let mut best: Option<(usize, f64, f64)> = None;
+        for feature in features {
+            let mut sorted = indices.clone();
+            sorted.sort_by(|&a, &b| rows[a][feature].partial_cmp(&rows[b][feature]).unwrap());
+
+            let mut left = Stats::empty(targets);
+            let mut right = stats.clone();
+            for position in 0..(total - 1) {
+                left.add(targets, sorted[position], 1.0);
+                right.add(targets, sorted[position], -1.0);
+
+                let value = rows[sorted[position]][feature];
+                let next = rows[sorted[position + 1]][feature];
+                if value == next {
+                    continue;
+                }
+                let left_count = position + 1;
+                if left_count < self.min_samples_leaf || total - left_count < self.min_samples_leaf {
+                    continue;
+                }
+
+                let child_impurity = left.count() * left.impurity(&self.criterion)
+                    + right.count() * right.impurity(&self.criterion);
+                let is_better = match best {
+                    Some((_, _, current)) => child_impurity < current - 1e-12,
+                    None => true,
+                };
+                if is_better {
+                    best = Some((feature, (value + next) / 2.0, child_impurity));
+                }
+            }
+        }
+        return best;
+    }
+
+    fn leaf_index(&self, row: &Vec<f64>) -> usize {
+        if self.nodes.is_empty() {
+            panic!("Model has not been fitted!");
+        }
+        if row.len() != self.num_features {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        let mut node = 0;
+        loop {
+            match &self.nodes[node] {
+                Node::Leaf { .. } => return node,
+                Node::Split { feature, threshold, left, right } => {
+                    node = if row[*feature] <= *threshold { *left } else { *right };
+                },
+            }
+        }
+    }
+
+    fn set_leaf_value(&mut self, node: usize, new_value: Vec<f64>) {
+        match &mut self.nodes[node] {
+            Node::Leaf { value } => *value = new_value,
+            Node::Split { .. } => panic!("Node is not a leaf!"),
+        }
+    }
+
+    fn leaf_value(&self, row: &Vec<f64>) -> &Vec<f64> {
+        match &self.nodes[self.leaf_index(row)] {
+            Node::Leaf { value } => value,
+            Node::Split { .. } => unreachable!(),
+        }
+    }
+
+    fn depth(&self, node: usize) -> usize {
+        match &self.nodes[node] {
+            Node::Leaf { .. } => 0,
+            Node::Split { left, right, .. } => 1 + self.depth(*left).max(self.depth(*right)),
+        }
+    }
+
+    fn max_depth_reached(&self) -> usize {
+        if self.nodes.is_empty() {
+            panic!("Model has not been fitted!");
+        }
+        return self.depth(0);
+    }
+
+    fn num_leaves(&self) -> usize {
+        return self.nodes.iter().filter(|node| matches!(node, Node::Leaf { .. })).count();
+    }
+
+    fn to_text<F>(&self, describe_leaf: F) -> String
+        where F: Fn(&Vec<f64>) -> String {
+        if self.nodes.is_empty() {
+            panic!("Model has not been fitted!");
+        }
+        let mut result = String::new();
+        self.write_node(0, 0, &describe_leaf, &mut result);
+        return result;
+    }
+
+    fn write_node<F>(&self, node: usize, depth: usize, describe_leaf: &F, result: &mut String)
+        where F: Fn(&Vec<f64>) -> String {
+        let indent = "|   ".repeat(depth);
+        match &self.nodes[node] {
+            Node::Leaf { value } => {
+                result.push_str(&format!("{}|--- {}\n", indent, describe_leaf(value)));
+            },
+            Node::Split { feature, threshold, left, right } => {
+                result.push_str(&format!("{}|--- feature_{} <= {:.2}\n", indent, feature, threshold));
+                self.write_node(*left, depth + 1, describe_leaf, result);
+                result.push_str(&format!("{}|--- feature_{} >  {:.2}\n", indent, feature, threshold));
+                self.write_node(*right, depth + 1, describe_leaf, result);
+            },
+        }
+    }
+}
+
+pub struct DecisionTreeClassifier {
+    num_classes: usize,
+    tree: Tree,
+}
+
+impl DecisionTreeClassifier {
+    pub fn new(num_classes: usize) -> DecisionTreeClassifier {
+        DecisionTreeClassifier {
+            num_classes,
+            tree: Tree::new(Criterion::Gini),
+        }
+    }
+
+    pub fn set_criterion(&mut self, criterion: Criterion) {
+        if let Criterion::MSE = criterion {
+            panic!("Classification trees support Gini or entropy only!");
+        }
+        self.tree.criterion = criterion;
+    }
+
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.tree.max_depth = Some(max_depth);
+    }
+
+    pub fn set_min_samples_split(&mut self, min_samples_split: usize) {
+        self.tree.min_samples_split = min_samples_split;
+    }
+
+    pub fn set_min_samples_leaf(&mut self, min_samples_leaf: usize) {
+        self.tree.min_samples_leaf = min_samples_leaf.max(1);
+    }
+
+    // Number of features drawn at random as split candidates at each node
+    pub fn set_max_features(&mut self, max_features: usize) {
+        self.tree.max_features = Some(max_features.max(1));
+    }
+
+    pub fn fit(&mut self, inputs: &Matrix, labels: &Vec<usize>) {
+        self.fit_samples(inputs, labels, (0..labels.len()).collect());
+    }
+
+    // Fits on the given rows only; repeated indices act as sample weights
+    pub fn fit_samples(&mut self, inputs: &Matrix, labels: &Vec<usize>, indices: Vec<usize>) {
+        if inputs.size().0 != labels.len() {
+            panic!("Inputs and labels must have the same number of rows!");
+        }
+        if indices.is_empty() {
+            panic!("Cannot fit a tree without samples!");
+        }
+        if labels.iter().any(|&label| label >= self.num_classes) {
+            panic!("Label out of range!");
+        }
+        self.tree.fit(inputs, &Targets::Classes(labels, self.num_classes), indices);
+    }
+
+    pub fn predict_proba(&self, inputs: &Matrix) -> Matrix {
+        let rows = inputs.size().0;
+        let mut data: Vec<f64> = Vec::new();
+        for i in 0..rows {
+            data.extend(self.tree.leaf_value(&inputs.get_row(i)));
+        }
+        return Matrix::from_vec(&data, rows, self.num_classes);
+    }
+
+    pub fn predict(&self, inputs: &Matrix) -> Vec<usize> {
+        let mut result: Vec<usize> = Vec::new();
+        for i in 0..inputs.size().0 {
+            result.push(argmax(self.tree.leaf_value(&inputs.get_row(i))));
+        }
+        return result;
+    }
+
+    // Index of the leaf each row lands in
+    pub fn apply(&self, inputs: &Matrix) -> Vec<usize> {
+        return (0..inputs.size().0).map(|i| self.tree.leaf_index(&inputs.get_row(i))).collect();
+    }
+
+    // Total impurity decrease contributed by each feature, summing to one
+    pub fn feature_importances(&self) -> &Vec<f64> {
+        return &self.tree.importances;
+    }
+
+    pub fn get_depth(&self) -> usize {
+        return self.tree.max_depth_reached();
+    }
+
+    pub fn get_num_leaves(&self) -> usize {
+        return self.tree.num_leaves();
+    }
+
+    pub fn to_text(&self) -> String {
+        return self.tree.to_text(|value| format!("class: {}", argmax(value)));
+    }
+}
+
+pub struct DecisionTreeRegressor {
+    tree: Tree,
+}
+
+impl DecisionTreeRegressor {
+    pub fn new() -> DecisionTreeRegressor {
+        DecisionTreeRegressor {
+            tree: Tree::new(Criterion::MSE),
+        }
+    }
+
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.tree.max_depth = Some(max_depth);
+    }
+
+    pub fn set_min_samples_split(&mut self, min_samples_split: usize) {
+        self.tree.min_samples_split = min_samples_split;
+    }
+
+    pub fn set_min_samples_leaf(&mut self, min_samples_leaf: usize) {
+        self.tree.min_samples_leaf = min_samples_leaf.max(1);
+    }
+
+    // Number of features drawn at random as split candidates at each node
+    pub fn set_max_features(&mut self, max_features: usize) {
+        self.tree.max_features = Some(max_features.max(1));
+    }
+
+    pub fn fit(&mut self, inputs: &Matrix, targets: &Vec<f64>) {
+        self.fit_samples(inputs, targets, (0..targets.len()).collect());
+    }
+
+    // Fits on the given rows only; repeated indices act as sample weights
+    pub fn fit_samples(&mut self, inputs: &Matrix, targets: &Vec<f64>, indices: Vec<usize>) {
+        if inputs.size().0 != targets.len() {
+            panic!("Inputs and targets must have the same number of rows!");
+        }
+        if indices.is_empty() {
+            panic!("Cannot fit a tree without samples!");
+        }
+        self.tree.fit(inputs, &Targets::Values(targets), indices);
+    }
+
+    pub fn predict(&self, inputs: &Matrix) -> Vec<f64> {
+        return (0..inputs.size().0).map(|i| self.tree.leaf_value(&inputs.get_row(i))[0]).collect();
+    }
+
+    // Overrides the prediction of a leaf returned by `apply`
+    pub fn set_leaf_value(&mut self, leaf: usize, value: f64) {
+        self.tree.set_leaf_value(leaf, vec![value]);
+    }
+
+    // Index of the leaf each row lands in
+    pub fn apply(&self, inputs: &Matrix) -> Vec<usize> {
+        return (0..inputs.size().0).map(|i| self.tree.leaf_index(&inputs.get_row(i))).collect();
+    }
+
+    // Total impurity decrease contributed by each feature, summing to one
+    pub fn feature_importances(&self) -> &Vec<f64> {
+        return &self.tree.importances;
+    }
+
+    pub fn get_depth(&self) -> usize {
+        return self.tree.max_depth_reached();
+    }
+
+    pub fn get_num_leaves(&self) -> usize {
+        return self.tree.num_leaves();
+    }
+
+    pub fn to_text(&self) -> String {
+        return self.tree.to_text(|value| format!("value: {:.2}", value[0]));
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_decision_tree.rs"]
+mod test;
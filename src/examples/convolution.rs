@@ -0,0 +1,154 @@
+extern crate ml_from_scratch;
+extern crate rand;
+
+use rand::Rng;
+
+use ml_from_scratch::math::softmax;
+use ml_from_scratch::matrix::Matrix;
+use ml_from_scratch::convolution::{FeatureMaps, Conv2D, MaxPool2D, Flatten};
+
+const SIZE: usize = 28;
+const SHAPES: [&str; 4] = ["vertical bar", "horizontal bar", "diagonal", "square"];
+
+// A 28x28 grayscale image of a randomly placed shape with pixel noise
+fn draw<R: Rng>(shape: usize, rng: &mut R) -> Matrix {
+    let mut image = Matrix::new();
+    image.zero_fill(SIZE, SIZE);
+    let (x, y) = (rng.gen_range(2, 14), rng.gen_range(2, 14));
+    let length = rng.gen_range(8, 12);
+    for t in 0..length {
+        match shape {
+            0 => image.set_at_index(y + t, x, 1.0),
+            1 => image.set_at_index(y, x + t, 1.0),
+            2 => image.set_at_index(y + t, x + t, 1.0),
+            _ => {
+                image.set_at_index(y, x + t, 1.0);
+                image.set_at_index(y + length, x + t, 1.0);
+                image.set_at_index(y + t, x, 1.0);
+                image.set_at_index(y + t, x + length, 1.0);
+            },
+        }
+    }
+    let mut noise: Vec<f64> = Vec::new();
+    for _ in 0..(SIZE * SIZE) {
+        noise.push(rng.gen_range(-0.1, 0.1));
+    }
+    return image.add(&Matrix::from_vec(&noise, SIZE, SIZE));
+}
+
+// conv 3x3 (4 filters) -> ReLU -> 2x2 max pool -> 2x2 max pool -> flatten
+// -> dense softmax
+struct SmallCNN {
+    conv: Conv2D,
+    first_pool: MaxPool2D,
+    second_pool: MaxPool2D,
+    flatten: Flatten,
+    relu_outputs: FeatureMaps,
+    // One row of weights and a bias per class
+    weights: Vec<Vec<f64>>,
+    biases: Vec<f64>,
+}
+
+impl SmallCNN {
+    fn new<R: Rng>(rng: &mut R) -> SmallCNN {
+        let mut conv = Conv2D::new(1, 4, 3);
+        conv.set_padding(1);
+        let num_features = 4 * 7 * 7;
+        SmallCNN {
+            conv,
+            first_pool: MaxPool2D::new(2),
+            second_pool: MaxPool2D::new(2),
+            flatten: Flatten::new(),
+            relu_outputs: Vec::new(),
+            weights: (0..SHAPES.len()).map(|_| (0..num_features).map(|_| rng.gen_range(-0.05, 0.05)).collect()).collect(),
+            biases: vec![0.0; SHAPES.len()],
+        }
+    }
+
+    // Returns the flattened features and the class probabilities
+    fn forward(&mut self, image: &Matrix) -> (Vec<f64>, Vec<f64>) {
+        let mut maps = self.conv.forward(&vec![image.clone()]);
+        for map in maps.iter_mut() {
+            map.map(|x| x.max(0.0));
+        }
+        let pooled = self.second_pool.forward(&self.first_pool.forward(&maps));
+        self.relu_outputs = maps;
+        let features = self.flatten.forward(&pooled);
+        let scores: Vec<f64> = self.weights.iter().zip(&self.biases)
+            .map(|(row, bias)| bias + row.iter().zip(&features).map(|(w, x)| w * x).sum::<f64>())
+            .collect();
+        return (features, softmax(&scores));
+    }
+
+    // One step of stochastic gradient descent on the cross-entropy loss
+    fn train_step(&mut self, image: &Matrix, label: usize, learning_rate: f64) -> f64 {
+        let (features, probabilities) = self.forward(image);
+        let score_gradients: Vec<f64> = probabilities.iter().enumerate()
+            .map(|(c, p)| if c == label { p - 1.0 } else { *p })
+            .collect();
+
+        let mut feature_gradients = vec![0.0; features.len()];
+        for (c, gradient) in score_gradients.iter().enumerate() {
+            for (k, feature) in features.iter().enumerate() {
+                feature_gradients[k] += gradient * self.weights[c][k];
+                self.weights[c][k] -= learning_rate * gradient * feature;
+            }
+            self.biases[c] -= learning_rate * gradient;
+        }
+
+        let pooled_gradients = self.flatten.backward(&feature_gradients);
+        let mut map_gradients = self.first_pool.backward(&self.second_pool.backward(&pooled_gradients));
+        for (gradients, outputs) in map_gradients.iter_mut().zip(&self.relu_outputs) {
+            let mask = outputs.as_vec();
+            gradients.map_with_index(|g, i| if mask[i] > 0.0 { g } else { 0.0 });
+        }
+        self.conv.backward(&map_gradients);
+        self.conv.update(learning_rate);
+        return -probabilities[label].ln();
+    }
+
+    fn predict(&mut self, image: &Matrix) -> usize {
+        let (_, probabilities) = self.forward(image);
+        let mut best = 0;
+        for (c, p) in probabilities.iter().enumerate() {
+            if *p > probabilities[best] {
+                best = c;
+            }
+        }
+        return best;
+    }
+}
+
+pub fn small_cnn_on_shapes() {
+    println!("=== Training a Small CNN on 28x28 Images of Shapes ===");
+
+    let mut rng = rand::thread_rng();
+    let make_data = |count: usize, rng: &mut rand::rngs::ThreadRng| -> Vec<(Matrix, usize)> {
+        return (0..count).map(|i| (draw(i % SHAPES.len(), rng), i % SHAPES.len())).collect();
+    };
+    let train = make_data(400, &mut rng);
+    let test = make_data(100, &mut rng);
+    println!("Classes: {}", SHAPES.join(", "));
+    println!("Training images: {}, test images: {}", train.len(), test.len());
+
+    let mut cnn = SmallCNN::new(&mut rng);
+    let learning_rate = 0.01;
+    let epochs = 5;
+    println!("\nTraining (learning rate: {}, epochs: {})...", learning_rate, epochs);
+    for epoch in 0..epochs {
+        let mut loss = 0.0;
+        for (image, label) in &train {
+            loss += cnn.train_step(image, *label, learning_rate);
+        }
+        let correct = test.iter().filter(|(image, label)| cnn.predict(image) == *label).count();
+        println!("Epoch {}: average loss {:.4}, test accuracy {:.1}%",
+                 epoch + 1, loss / train.len() as f64, 100.0 * correct as f64 / test.len() as f64);
+    }
+
+    println!("\nLearned 3x3 filters:");
+    let filters = cnn.conv.get_weights();
+    for f in 0..filters.size().0 {
+        let row = Matrix::from_vec(&filters.get_row(f), 3, 3);
+        println!("  {}", row.to_string_fmt(2));
+    }
+}
@@ -1 +1,11 @@
-pub mod neural_network;
\ No newline at end of file
+pub mod neural_network;
+
+pub mod perceptron;
+
+pub mod reinforcement_learning;
+
+pub mod convolution;
+
+pub mod recurrent;
+
+pub mod transformer;
\ No newline at end of file
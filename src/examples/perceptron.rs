@@ -0,0 +1,60 @@
+extern crate ml_from_scratch;
+
+use ml_from_scratch::matrix::Matrix;
+use ml_from_scratch::perceptron::Perceptron;
+use ml_from_scratch::neural_network::{TrainingData, NeuralNetwork};
+
+pub fn perceptron_vs_neural_network_on_xor() {
+    println!("=== Perceptron vs Neural Network on the XOR Gate ===");
+
+    let inputs = Matrix::from_str("[[1, 1], [1, 0], [0, 1], [0, 0]]");
+    let names = ["T T", "T F", "F T", "F F"];
+    let and_labels = vec![1, 0, 0, 0];
+    let xor_labels = vec![0, 1, 1, 0];
+
+    // A single threshold unit handles AND...
+    let mut perceptron = Perceptron::new(2);
+    perceptron.fit(&inputs, &and_labels);
+    println!("\nPerceptron on AND: converged after {} epochs", perceptron.get_errors_per_epoch().len());
+
+    // ...but no line separates the XOR classes, so training never settles
+    let max_epochs = 1000;
+    perceptron.set_max_epochs(max_epochs);
+    perceptron.fit(&inputs, &xor_labels);
+    println!("Perceptron on XOR: converged = {}, misclassified in last epoch = {}",
+             perceptron.has_converged(), perceptron.get_errors_per_epoch().last().unwrap());
+
+    let predictions = perceptron.predict(&inputs);
+    for i in 0..names.len() {
+        println!("{} -> {} (expected {})", names[i], predictions[i], xor_labels[i]);
+    }
+
+    // A multi-layer network learns the same function. NeuralNetwork has no
+    // bias terms and square layers, so a constant third input acts as the
+    // bias and the third output is simply held at 0.5.
+    let mut nn = NeuralNetwork::new(3, 3);
+    nn.set_weights(&vec![
+        Matrix::from_str("[[0.5,-0.4,0.1],[-0.3,0.6,-0.2],[0.2,0.1,-0.5]]"),
+        Matrix::from_str("[[-0.4,0.3,0.2],[0.1,-0.5,0.4],[0.3,0.2,-0.1]]"),
+        Matrix::from_str("[[0.2,-0.3,0.1],[-0.1,0.4,-0.2],[0.3,-0.2,0.5]]"),
+    ]);
+
+    let mut data: TrainingData = Vec::new();
+    for (i, &label) in xor_labels.iter().enumerate() {
+        let mut input = inputs.get_row(i);
+        input.push(1.0);
+        let target = if label == 1 { vec![1.0, 0.0, 0.5] } else { vec![0.0, 1.0, 0.5] };
+        data.push((input, target));
+    }
+
+    let learning_rate = 0.5;
+    let epochs = 100000;
+    println!("\nTraining Neural Network (learning rate: {}, epochs: {})...", learning_rate, epochs);
+    nn.train(&data, learning_rate, epochs);
+
+    for i in 0..names.len() {
+        let outputs = nn.execute(&data[i].0);
+        let predicted = if outputs.get_at_index(0, 0) > outputs.get_at_index(1, 0) { 1 } else { 0 };
+        println!("{} -> {} (expected {}), outputs: {}", names[i], predicted, xor_labels[i], outputs.to_string_fmt(5));
+    }
+}
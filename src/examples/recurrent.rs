@@ -0,0 +1,64 @@
+extern crate ml_from_scratch;
+extern crate rand;
+
+use rand::Rng;
+
+use ml_from_scratch::loss::Loss;
+use ml_from_scratch::recurrent::{SequenceData, Cell, RecurrentNetwork};
+
+const BITS: usize = 8;
+
+// Bits of a number, least significant first
+fn to_bits(number: u32) -> Vec<f64> {
+    return (0..BITS).map(|i| ((number >> i) & 1) as f64).collect();
+}
+
+fn from_bits(bits: &[f64]) -> u32 {
+    return bits.iter().enumerate().map(|(i, b)| if *b > 0.5 { 1 << i } else { 0 }).sum();
+}
+
+// Each step reads one bit of both numbers and outputs that bit of the sum,
+// so the network has to remember the carry
+fn addition_data<R: Rng>(count: usize, rng: &mut R) -> SequenceData {
+    return (0..count).map(|_| {
+        let (a, b) = (rng.gen_range(0, 128), rng.gen_range(0, 128));
+        let (a_bits, b_bits, sum_bits) = (to_bits(a), to_bits(b), to_bits(a + b));
+        let inputs: Vec<Vec<f64>> = (0..BITS).map(|i| vec![a_bits[i], b_bits[i]]).collect();
+        let targets: Vec<Vec<f64>> = sum_bits.iter().map(|bit| vec![*bit]).collect();
+        (inputs, targets)
+    }).collect();
+}
+
+pub fn recurrent_networks_on_binary_addition() {
+    println!("=== RNN, LSTM and GRU Learning to Add Binary Numbers ===");
+
+    let mut rng = rand::thread_rng();
+    let train = addition_data(256, &mut rng);
+    let test = addition_data(100, &mut rng);
+    println!("Adding pairs of 7-bit numbers one bit per step, least significant bit first");
+    println!("Training sums: {}, test sums: {}", train.len(), test.len());
+
+    for (name, cell) in [("RNN", Cell::RNN), ("LSTM", Cell::LSTM), ("GRU", Cell::GRU)] {
+        let mut network = RecurrentNetwork::new(cell, 2, 8, 1);
+        network.set_loss(Loss::MeanSquaredError);
+        network.set_batch_size(8);
+        let losses = network.train(&train, 1.0, 100);
+
+        let mut correct = 0;
+        for (inputs, targets) in &test {
+            let outputs: Vec<f64> = network.predict(inputs).iter().map(|o| o[0]).collect();
+            let expected: Vec<f64> = targets.iter().map(|t| t[0]).collect();
+            if from_bits(&outputs) == from_bits(&expected) {
+                correct += 1;
+            }
+        }
+        println!("\n{}: loss {:.4} after the first epoch, {:.4} after the last", name, losses[0], losses[losses.len() - 1]);
+        println!("{}: {} of {} test sums exactly right", name, correct, test.len());
+
+        let (inputs, _) = &test[0];
+        let a = from_bits(&inputs.iter().map(|step| step[0]).collect::<Vec<f64>>());
+        let b = from_bits(&inputs.iter().map(|step| step[1]).collect::<Vec<f64>>());
+        let sum = from_bits(&network.predict(inputs).iter().map(|o| o[0]).collect::<Vec<f64>>());
+        println!("{}: {} + {} = {}", name, a, b, sum);
+    }
+}
@@ -0,0 +1,79 @@
+extern crate ml_from_scratch;
+
+extern crate rand;
+
+use rand::Rng;
+
+use ml_from_scratch::rl::{Environment, DiscreteEnvironment, GridWorld, FrozenLake, CartPole, QLearning, SARSA, DQN};
+
+pub fn q_learning_and_sarsa() {
+    println!("=== Tabular Q-Learning and SARSA ===");
+
+    // A 6x4 maze with a wall that forces a detour
+    let mut grid = GridWorld::new(6, 4);
+    for y in 0..3 {
+        grid.add_wall(3, y);
+    }
+    let episodes = 500;
+
+    println!("\nTraining Q-Learning and SARSA on a grid world for {} episodes...", episodes);
+    let mut q_learning = QLearning::new(grid.num_states(), grid.num_actions());
+    q_learning.set_learning_rate(0.5);
+    let returns = q_learning.train(&mut grid, episodes);
+    println!("Q-Learning: first episode return {}, last episode return {}", returns[0], returns[episodes - 1]);
+    println!("Greedy return: {}", q_learning.evaluate(&mut grid, 1));
+    print!("{}", grid.render_policy(&q_learning.policy()));
+
+    let mut sarsa = SARSA::new(grid.num_states(), grid.num_actions());
+    sarsa.set_learning_rate(0.5);
+    let returns = sarsa.train(&mut grid, episodes);
+    println!("\nSARSA: first episode return {}, last episode return {}", returns[0], returns[episodes - 1]);
+    println!("Greedy return: {}", sarsa.evaluate(&mut grid, 1));
+    print!("{}", grid.render_policy(&sarsa.policy()));
+
+    // On slippery ice the best policy only reaches the goal part of the time
+    let mut lake = FrozenLake::new();
+    lake.set_slippery(true);
+    let episodes = 20000;
+    println!("\nTraining Q-Learning on a slippery frozen lake for {} episodes...", episodes);
+    let mut agent = QLearning::new(lake.num_states(), lake.num_actions());
+    agent.set_learning_rate(0.1);
+    agent.set_discount(0.99);
+    agent.train(&mut lake, episodes);
+    println!("Success rate of the greedy policy: {:.1}%", 100.0 * agent.evaluate(&mut lake, 1000));
+    print!("{}", lake.render_policy(&agent.policy()));
+}
+
+pub fn dqn_on_cart_pole() {
+    println!("=== Deep Q-Network balancing a Cart Pole ===");
+
+    let mut environment = CartPole::new();
+    environment.set_max_steps(200);
+
+    let mut random_total = 0.0;
+    let mut rng = rand::thread_rng();
+    for _ in 0..100 {
+        environment.reset();
+        loop {
+            let (_, reward, terminated, truncated) = environment.step(rng.gen_range(0, 2));
+            random_total += reward;
+            if terminated || truncated {
+                break;
+            }
+        }
+    }
+    println!("Random policy keeps the pole up for {:.1} steps on average", random_total / 100.0);
+
+    let mut agent = DQN::new(4, 2, 2);
+    agent.set_learning_rate(0.5);
+    agent.set_value_scale(50.0);
+    agent.set_discount(0.95);
+    let episodes = 300;
+    println!("\nTraining a DQN for {} episodes...", episodes);
+    let returns = agent.train(&mut environment, episodes);
+    for (block, chunk) in returns.chunks(50).enumerate() {
+        let average = chunk.iter().sum::<f64>() / chunk.len() as f64;
+        println!("Episodes {:>3}-{:>3}: average return {:.1}", block * 50 + 1, block * 50 + chunk.len(), average);
+    }
+    println!("\nGreedy policy keeps the pole up for {:.1} steps on average", agent.evaluate(&mut environment, 100));
+}
@@ -0,0 +1,145 @@
+extern crate ml_from_scratch;
+extern crate rand;
+
+use rand::Rng;
+
+use ml_from_scratch::layers::{Layer, LayerNorm};
+use ml_from_scratch::loss::Loss;
+use ml_from_scratch::math::softmax;
+use ml_from_scratch::matrix::Matrix;
+use ml_from_scratch::transformer::{positional_encoding, Embedding, TransformerBlock};
+
+const TEXT: &str = include_str!("data/nursery_rhymes.txt");
+const CONTEXT: usize = 32;
+const D_MODEL: usize = 32;
+
+// Token embedding + positional encoding -> two causal transformer blocks ->
+// layer norm -> dense projection to a score per character
+struct CharModel {
+    embedding: Embedding,
+    blocks: Vec<TransformerBlock>,
+    norm: LayerNorm,
+    // D_MODEL x vocab_size
+    weights: Matrix,
+    biases: Matrix,
+}
+
+impl CharModel {
+    fn new<R: Rng>(vocab_size: usize, rng: &mut R) -> CharModel {
+        let blocks = (0..2).map(|_| {
+            let mut block = TransformerBlock::new(D_MODEL, 4, 4 * D_MODEL);
+            block.set_causal(true);
+            block
+        }).collect();
+        let data: Vec<f64> = (0..(D_MODEL * vocab_size)).map(|_| rng.gen_range(-0.1, 0.1)).collect();
+        CharModel {
+            embedding: Embedding::new(vocab_size, D_MODEL),
+            blocks,
+            norm: LayerNorm::new(D_MODEL),
+            weights: Matrix::from_vec(&data, D_MODEL, vocab_size),
+            biases: Matrix::from_vec(&vec![0.0; vocab_size], 1, vocab_size),
+        }
+    }
+
+    // Returns the final features and the scores for the next character at
+    // every position
+    fn forward(&mut self, tokens: &Vec<usize>) -> (Matrix, Matrix) {
+        let mut features = self.embedding.forward(tokens).add(&positional_encoding(tokens.len(), D_MODEL));
+        for block in self.blocks.iter_mut() {
+            features = block.forward(&features);
+        }
+        let features = self.norm.forward(&features);
+        let scores = features.dot_prod(&self.weights).add_row(&self.biases);
+        return (features, scores);
+    }
+
+    fn train_step(&mut self, tokens: &Vec<usize>, targets: &Matrix, learning_rate: f64) -> f64 {
+        let (features, scores) = self.forward(tokens);
+        let loss = Loss::CrossEntropy.compute(&scores, targets);
+        let gradients = Loss::CrossEntropy.gradient(&scores, targets);
+
+        let mut feature_gradients = gradients.dot_prod(&self.weights.transpose());
+        let mut weight_step = features.transpose().dot_prod(&gradients);
+        weight_step.map(|g| g * learning_rate);
+        let mut bias_step = gradients.sum_rows();
+        bias_step.map(|g| g * learning_rate);
+        self.weights = self.weights.subtract(&weight_step);
+        self.biases = self.biases.subtract(&bias_step);
+
+        feature_gradients = self.norm.backward(&feature_gradients);
+        for block in self.blocks.iter_mut().rev() {
+            feature_gradients = block.backward(&feature_gradients);
+        }
+        self.embedding.backward(&feature_gradients);
+
+        self.norm.update(learning_rate);
+        for block in self.blocks.iter_mut() {
+            block.update(learning_rate);
+        }
+        self.embedding.update(learning_rate);
+        return loss;
+    }
+
+    // Samples characters one at a time from the softmax of the last scores
+    fn generate<R: Rng>(&mut self, prompt: &[usize], length: usize, temperature: f64, rng: &mut R) -> Vec<usize> {
+        let mut tokens = prompt.to_vec();
+        for _ in 0..length {
+            let start = if tokens.len() > CONTEXT { tokens.len() - CONTEXT } else { 0 };
+            let (_, scores) = self.forward(&tokens[start..].to_vec());
+            let last: Vec<f64> = scores.get_row(scores.size().0 - 1).iter().map(|s| s / temperature).collect();
+            let probabilities = softmax(&last);
+            let mut threshold = rng.gen_range(0.0, 1.0);
+            let mut next = probabilities.len() - 1;
+            for (token, p) in probabilities.iter().enumerate() {
+                if threshold < *p {
+                    next = token;
+                    break;
+                }
+                threshold -= p;
+            }
+            tokens.push(next);
+        }
+        return tokens;
+    }
+}
+
+pub fn tiny_language_model() {
+    println!("=== Character-Level Transformer Language Model on Nursery Rhymes ===");
+
+    let mut vocab: Vec<char> = TEXT.chars().collect();
+    vocab.sort();
+    vocab.dedup();
+    let encode = |text: &str| -> Vec<usize> { text.chars().map(|c| vocab.binary_search(&c).unwrap()).collect() };
+    let decode = |tokens: &Vec<usize>| -> String { tokens.iter().map(|&t| vocab[t]).collect() };
+    let data = encode(TEXT);
+    println!("Text length: {} characters, vocabulary: {} characters", data.len(), vocab.len());
+    println!("Context: {} characters, model size: {}, 2 blocks of 4 heads", CONTEXT, D_MODEL);
+
+    let mut rng = rand::thread_rng();
+    let mut model = CharModel::new(vocab.len(), &mut rng);
+    let (steps, report_every, learning_rate) = (3000, 500, 0.3);
+    println!("\nTraining on random {} character windows (learning rate: {})...", CONTEXT, learning_rate);
+    let mut total = 0.0;
+    let mut since_report = 0;
+    for step in 0..steps {
+        let start = rng.gen_range(0, data.len() - CONTEXT);
+        let tokens = data[start..(start + CONTEXT)].to_vec();
+        let mut targets = Matrix::new();
+        targets.zero_fill(CONTEXT, vocab.len());
+        for t in 0..CONTEXT {
+            targets.set_at_index(t, data[start + t + 1], 1.0);
+        }
+        total += model.train_step(&tokens, &targets, learning_rate);
+        since_report += 1;
+        if since_report == report_every {
+            println!("Step {}: average cross-entropy {:.4}", step + 1, total / report_every as f64);
+            total = 0.0;
+            since_report = 0;
+        }
+    }
+
+    for prompt in &["Twinkle, ", "Jack and ", "The "] {
+        let tokens = model.generate(&encode(prompt), 80, 0.5, &mut rng);
+        println!("\nPrompt {:?}:\n{}", prompt, decode(&tokens));
+    }
+}
@@ -0,0 +1,299 @@
+use std::f64::consts::PI;
+
+use super::k_means::KMeans;
+use super::math::{log_sum_exp, argmax};
+use super::matrix::Matrix;
+
+pub enum CovarianceType {
+    // A full covariance matrix per component
+    Full,
+    // Independent variances per feature and component
+    Diagonal,
+    // A single variance per component
+    Spherical,
+}
+
+// A Gaussian prepared for repeated density evaluation
+struct Component {
+    mean: Vec<f64>,
+    // Cholesky factor of the covariance
+    lower: Matrix,
+    log_normalizer: f64,
+}
+
+impl Component {
+    fn new(mean: &Vec<f64>, covariance: &Matrix) -> Component {
+        let lower = covariance.cholesky();
+        let dims = mean.len();
+        let log_det: f64 = (0..dims).map(|d| 2.0 * lower.get_at_index(d, d).ln()).sum();
+        Component {
+            mean: mean.clone(),
+            lower,
+            log_normalizer: -0.5 * (dims as f64 * (2.0 * PI).ln() + log_det),
+        }
+    }
+
+    #[allow(clippy::needless_range_loop)]
+    fn log_density(&self, point: &Vec<f64>) -> f64 {
+        // Solve L y = x - mean by forward substitution, so |y|^2 is the
+        // squared Mahalanobis distance
+        let dims = self.mean.len();
+        let mut y = vec![0.0; dims];
+        for i in 0..dims {
+            let mut sum = point[i] - self.mean[i];
+            for k in 0..i {
+                sum -= self.lower.get_at_index(i, k) * y[k];
+            }
+            y[i] = sum / self.lower.get_at_index(i, i);
+        }
+        let distance: f64 = y.iter().map(|v| v * v).sum();
+        return self.log_normalizer - 0.5 * distance;
+    }
+}
+
+// Mixture of Gaussians fitted by expectation-maximization, starting from a
+// k-means clustering of the data
+pub struct GaussianMixture {
+    num_components: usize,
+    covariance_type: CovarianceType,
+    max_iterations: usize,
+    tolerance: f64,
+    // Added to the diagonal of every covariance to keep it positive definite
+    reg_covar: f64,
+    weights: Vec<f64>,
+    means: Vec<Vec<f64>>,
+    covariances: Vec<Matrix>,
+    log_likelihoods: Vec<f64>,
+    converged: bool,
+}
+
+impl GaussianMixture {
+    pub fn new(num_components: usize) -> GaussianMixture {
+        if num_components == 0 {
+            panic!("At least one component is required!");
+        }
+        GaussianMixture {
+            num_components,
+            covariance_type: CovarianceType::Full,
+            max_iterations: 100,
+            tolerance: 1e-3,
+            reg_covar: 1e-6,
+            weights: Vec::new(),
+            means: Vec::new(),
+            covariances: Vec::new(),
+            log_likelihoods: Vec::new(),
+            converged: false,
+        }
+    }
+
+    pub fn set_covariance_type(&mut self, covariance_type: CovarianceType) {
+        self.covariance_type = covariance_type;
+    }
+
+    pub fn set_max_iterations(&mut self, max_iterations: usize) {
+        self.max_iterations = max_iterations;
+    }
+
+    // Stop once the average log-likelihood per sample improves by less than this
+    pub fn set_tolerance(&mut self, tolerance: f64) {
+        self.tolerance = tolerance;
+    }
+
+    pub fn set_reg_covar(&mut self, reg_covar: f64) {
+        if reg_covar < 0.0 {
+            panic!("Covariance regularization must not be negative!");
+        }
+        self.reg_covar = reg_covar;
+    }
+
+    // Mixing proportions, summing to one
+    pub fn get_weights(&self) -> &Vec<f64> {
+        return &self.weights;
+    }
+
+    // One component mean per row
+    pub fn get_means(&self) -> Matrix {
+        return Matrix::from_rows(&self.means);
+    }
+
+    // Covariance of each component as a full matrix, whatever the
+    // covariance type
+    pub fn get_covariances(&self) -> &Vec<Matrix> {
+        return &self.covariances;
+    }
+
+    // Total log-likelihood of the training data after each E-step
+    pub fn get_log_likelihoods(&self) -> &Vec<f64> {
+        return &self.log_likelihoods;
+    }
+
+    pub fn get_num_iterations(&self) -> usize {
+        return self.log_likelihoods.len();
+    }
+
+    pub fn has_converged(&self) -> bool {
+        return self.converged;
+    }
+
+    // Free parameters of the fitted model, as counted by BIC and AIC
+    pub fn get_num_parameters(&self) -> usize {
+        if self.means.is_empty() {
+            panic!("Model has not been fitted!");
+        }
+        let k = self.num_components;
+        let dims = self.means[0].len();
+        let covariance_parameters = match self.covariance_type {
+            CovarianceType::Full => k * dims * (dims + 1) / 2,
+            CovarianceType::Diagonal => k * dims,
+            CovarianceType::Spherical => k,
+        };
+        return covariance_parameters + k * dims + k - 1;
+    }
+
+    pub fn fit(&mut self, inputs: &Matrix) {
+        let points = inputs.get_rows();
+        if points.len() < self.num_components {
+            panic!("Fewer samples than components!");
+        }
+
+        // Hard k-means assignments serve as the first responsibilities
+        let mut kmeans = KMeans::new(self.num_components);
+        kmeans.fit(inputs);
+        let responsibilities: Vec<Vec<f64>> = kmeans.predict(inputs).iter().map(|&label| {
+            let mut row = vec![0.0; self.num_components];
+            row[label] = 1.0;
+            row
+        }).collect();
+
+        self.maximization_step(&points, &responsibilities);
+
+        self.log_likelihoods = Vec::new();
+        self.converged = false;
+        let mut previous = f64::NEG_INFINITY;
+        for _ in 0..self.max_iterations {
+            let (responsibilities, log_likelihood) = self.expectation_step(&points);
+            self.log_likelihoods.push(log_likelihood);
+
+            let average = log_likelihood / points.len() as f64;
+            if (average - previous).abs() < self.tolerance {
+                self.converged = true;
+                break;
+            }
+            previous = average;
+            self.maximization_step(&points, &responsibilities);
+        }
+    }
+
+    fn maximization_step(&mut self, points: &Vec<Vec<f64>>, responsibilities: &Vec<Vec<f64>>) {
+        let dims = points[0].len();
+        self.weights = Vec::new();
+        self.means = Vec::new();
+        self.covariances = Vec::new();
+        for c in 0..self.num_components {
+            // A tiny floor keeps a component that lost all its points usable
+            let total: f64 = responsibilities.iter().map(|r| r[c]).sum::<f64>() + 10.0 * f64::EPSILON;
+            self.weights.push(total / points.len() as f64);
+
+            let mut mean = vec![0.0; dims];
+            for (point, r) in points.iter().zip(responsibilities) {
+                for d in 0..dims {
+                    mean[d] += r[c] * point[d] / total;
+                }
+            }
+
+            let mut covariance = Matrix::new();
+            covariance.zero_fill(dims, dims);
+            for (point, r) in points.iter().zip(responsibilities) {
+                for i in 0..dims {
+                    for j in 0..dims {
+                        let value = covariance.get_at_index(i, j) + r[c] * (point[i] - mean[i]) * (point[j] - mean[j]) / total;
+                        covariance.set_at_index(i, j, value);
+                    }
+                }
+            }
+            match self.covariance_type {
+                CovarianceType::Full => {},
+                CovarianceType::Diagonal => {
+                    covariance.map_with_index(|x, index| if index / dims == index % dims { x } else { 0.0 });
+                },
+                CovarianceType::Spherical => {
+                    let variance = (0..dims).map(|d| covariance.get_at_index(d, d)).sum::<f64>() / dims as f64;
+                    covariance = Matrix::identity(dims);
+                    covariance.map(|x| x * variance);
+                },
+            }
+            for d in 0..dims {
+                let value = covariance.get_at_index(d, d) + self.reg_covar;
+                covariance.set_at_index(d, d, value);
+            }
+
+            self.means.push(mean);
+            self.covariances.push(covariance);
+        }
+    }
+
+    // Log of weight * density for every point and component
+    fn weighted_log_densities(&self, points: &Vec<Vec<f64>>) -> Vec<Vec<f64>> {
+        if self.means.is_empty() {
+            panic!("Model has not been fitted!");
+        }
+        if points.iter().any(|point| point.len() != self.means[0].len()) {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        let components: Vec<Component> = self.means.iter().zip(&self.covariances)
+            .map(|(mean, covariance)| Component::new(mean, covariance))
+            .collect();
+        return points.iter().map(|point| {
+            components.iter().zip(&self.weights)
+                .map(|(component, weight)| weight.ln() + component.log_density(point))
+                .collect()
+        }).collect();
+    }
+
+    // Responsibilities and the total log-likelihood of the points
+    fn expectation_step(&self, points: &Vec<Vec<f64>>) -> (Vec<Vec<f64>>, f64) {
+        let mut responsibilities: Vec<Vec<f64>> = Vec::new();
+        let mut log_likelihood = 0.0;
+        for row in self.weighted_log_densities(points) {
+            let norm = log_sum_exp(&row);
+            log_likelihood += norm;
+            responsibilities.push(row.iter().map(|x| (x - norm).exp()).collect());
+        }
+        return (responsibilities, log_likelihood);
+    }
+
+    // Log-likelihood of each sample under the mixture
+    pub fn score_samples(&self, inputs: &Matrix) -> Vec<f64> {
+        return self.weighted_log_densities(&inputs.get_rows()).iter().map(log_sum_exp).collect();
+    }
+
+    pub fn log_likelihood(&self, inputs: &Matrix) -> f64 {
+        return self.score_samples(inputs).iter().sum();
+    }
+
+    // Bayesian information criterion; lower is better
+    pub fn bic(&self, inputs: &Matrix) -> f64 {
+        let num_samples = inputs.size().0 as f64;
+        return -2.0 * self.log_likelihood(inputs) + self.get_num_parameters() as f64 * num_samples.ln();
+    }
+
+    // Akaike information criterion; lower is better
+    pub fn aic(&self, inputs: &Matrix) -> f64 {
+        return -2.0 * self.log_likelihood(inputs) + 2.0 * self.get_num_parameters() as f64;
+    }
+
+    // Posterior probability of each component, one row per sample
+    pub fn predict_proba(&self, inputs: &Matrix) -> Matrix {
+        let (responsibilities, _) = self.expectation_step(&inputs.get_rows());
+        return Matrix::from_rows(&responsibilities);
+    }
+
+    pub fn predict(&self, inputs: &Matrix) -> Vec<usize> {
+        let (responsibilities, _) = self.expectation_step(&inputs.get_rows());
+        return responsibilities.iter().map(argmax).collect();
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_gaussian_mixture.rs"]
+mod test;
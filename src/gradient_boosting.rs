@@ -0,0 +1,405 @@
+extern crate rand;
+
+use super::matrix::Matrix;
+use super::math::sigmoid;
+use super::decision_tree::DecisionTreeRegressor;
+
+pub enum Loss {
+    SquaredError,
+    AbsoluteError,
+    // Binary cross-entropy on the log-odds
+    LogLoss,
+}
+
+impl Loss {
+    fn value(&self, targets: &Vec<f64>, scores: &Vec<f64>) -> f64 {
+        let total: f64 = targets.iter().zip(scores).map(|(&y, &f)| {
+            match self {
+                Loss::SquaredError => (y - f) * (y - f),
+                Loss::AbsoluteError => (y - f).abs(),
+                Loss::LogLoss => {
+                    let p = sigmoid(f).clamp(1e-15, 1.0 - 1e-15);
+                    -(y * p.ln() + (1.0 - y) * (1.0 - p).ln())
+                },
+            }
+        }).sum();
+        return total / targets.len() as f64;
+    }
+
+    fn initial_score(&self, targets: &Vec<f64>) -> f64 {
+        match self {
+            Loss::SquaredError => targets.iter().sum::<f64>() / targets.len() as f64,
+            Loss::AbsoluteError => median(targets.clone()),
+            Loss::LogLoss => {
+                let p = (targets.iter().sum::<f64>() / targets.len() as f64).clamp(1e-15, 1.0 - 1e-15);
+                (p / (1.0 - p)).ln()
+            },
+        }
+    }
+
+    fn negative_gradient(&self, target: f64, score: f64) -> f64 {
+        match self {
+            Loss::SquaredError => target - score,
+            // f64::signum maps 0.0 to 1.0, but an exact fit needs no push
+            Loss::AbsoluteError => if target == score { 0.0 } else { (target - score).signum() },
+            Loss::LogLoss => target - sigmoid(score),
+        }
+    }
+
+    // Optimal step for the samples that landed in one leaf, or None when the
+    // mean of the fitted gradient is already optimal
+    fn leaf_value(&self, targets: &Vec<f64>, scores: &Vec<f64>, indices: &Vec<usize>) -> Option<f64> {
+        match self {
+            Loss::SquaredError => None,
+            Loss::AbsoluteError => Some(median(indices.iter().map(|&i| targets[i] - scores[i]).collect())),
+            Loss::LogLoss => {
+                // One Newton-Raphson step
+                let mut numerator = 0.0;
+                let mut denominator = 0.0;
+                for &i in indices {
+                    let p = sigmoid(scores[i]);
+                    numerator += targets[i] - p;
+                    denominator += p * (1.0 - p);
+                }
+                if denominator < 1e-12 {
+                    return Some(0.0);
+                }
+                Some(numerator / denominator)
+            },
+        }
+    }
+}
+
+fn median(mut values: Vec<f64>) -> f64 {
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let middle = values.len() / 2;
+    if values.len() % 2 == 1 {
+        return values[middle];
+    }
+    return (values[middle - 1] + values[middle]) / 2.0;
+}
+
+// Additive model of shallow regression trees on the raw score scale
+struct Booster {
+    loss: Loss,
+    num_estimators: usize,
+    learning_rate: f64,
+    max_depth: usize,
+    min_samples_leaf: usize,
+    subsample: f64,
+    patience: Option<usize>,
+    initial_score: f64,
+    trees: Vec<DecisionTreeRegressor>,
+    train_losses: Vec<f64>,
+    validation_losses: Vec<f64>,
+}
+
+impl Booster {
+    fn new(loss: Loss, num_estimators: usize) -> Booster {
+        if num_estimators == 0 {
+            panic!("At least one estimator is required!");
+        }
+        Booster {
+            loss,
+            num_estimators,
+            learning_rate: 0.1,
+            max_depth: 3,
+            min_samples_leaf: 1,
+            subsample: 1.0,
+            patience: None,
+            initial_score: 0.0,
+            trees: Vec::new(),
+            train_losses: Vec::new(),
+            validation_losses: Vec::new(),
+        }
+    }
+
+    fn fit(&mut self, inputs: &Matrix, targets: &Vec<f64>, validation: Option<(&Matrix, &Vec<f64>)>) {
+        if inputs.size().0 != targets.len() {
+            panic!("Inputs and targets must have the same number of rows!");
+        }
+        let num_samples = targets.len();
+        let sample_size = ((self.subsample * num_samples as f64).round() as usize).max(1).min(num_samples);
+        let mut rng = rand::thread_rng();
+
+        self.initial_score = self.loss.initial_score(targets);
+        self.trees = Vec::new();
+        self.train_losses = Vec::new();
+        self.validation_losses = Vec::new();
+
+        let mut scores = vec![self.initial_score; num_samples];
+        let mut validation_scores = match validation {
+            Some((validation_inputs, _)) => vec![self.initial_score; validation_inputs.size().0],
+            None => Vec::new(),
+        };
+        let mut best: (f64, usize) = (f64::INFINITY, 0);
+
+        for stage in 0..self.num_estimators {
+            let gradients: Vec<f64> = targets.iter().zip(&scores)
+                .map(|(&y, &f)| self.loss.negative_gradient(y, f))
+                .collect();
+            let indices: Vec<usize> = if sample_size < num_samples {
+                rand::seq::index::sample(&mut rng, num_samples, sample_size).into_vec()
+            } else {
+                (0..num_samples).collect()
+            };
+
+            let mut tree = DecisionTreeRegressor::new();
+            tree.set_max_depth(self.max_depth);
+            tree.set_min_samples_leaf(self.min_samples_leaf);
+            tree.fit_samples(inputs, &gradients, indices.clone());
+
+            let leaves = tree.apply(inputs);
+            let mut leaf_samples: Vec<(usize, Vec<usize>)> = Vec::new();
+            for &i in &indices {
+                match leaf_samples.iter_mut().find(|(leaf, _)| *leaf == leaves[i]) {
+                    Some((_, samples)) => samples.push(i),
+                    None => leaf_samples.push((leaves[i], vec![i])),
+                }
+            }
+            for (leaf, samples) in &leaf_samples {
+                if let Some(value) = self.loss.leaf_value(targets, &scores, samples) {
+                    tree.set_leaf_value(*leaf, value);
+                }
+            }
+
+            for (score, step) in scores.iter_mut().zip(tree.predict(inputs)) {
+                *score += self.learning_rate * step;
+            }
+            self.train_losses.push(self.loss.value(targets, &scores));
+
+            if let Some((validation_inputs, validation_targets)) = validation {
+                for (score, step) in validation_scores.iter_mut().zip(tree.predict(validation_inputs)) {
+                    *score += self.learning_rate * step;
+                }
+                let validation_loss = self.loss.value(validation_targets, &validation_scores);
+                self.validation_losses.push(validation_loss);
+                if validation_loss < best.0 {
+                    best = (validation_loss, stage);
+                }
+            }
+            self.trees.push(tree);
+
+            if let (Some(patience), Some(_)) = (self.patience, validation) {
+                if stage - best.1 >= patience {
+                    break;
+                }
+            }
+        }
+
+        // Keep only the stages up to the best validation loss, along with
+        // their loss histories
+        if self.patience.is_some() && validation.is_some() {
+            self.trees.truncate(best.1 + 1);
+            self.train_losses.truncate(best.1 + 1);
+            self.validation_losses.truncate(best.1 + 1);
+        }
+    }
+
+    fn decision_function(&self, inputs: &Matrix) -> Vec<f64> {
+        return self.staged_decision_function(inputs).pop().unwrap();
+    }
+
+    fn staged_decision_function(&self, inputs: &Matrix) -> Vec<Vec<f64>> {
+        if self.trees.is_empty() {
+            panic!("Model has not been fitted!");
+        }
+        let mut scores = vec![self.initial_score; inputs.size().0];
+        let mut result: Vec<Vec<f64>> = Vec::new();
+        for tree in &self.trees {
+            for (score, step) in scores.iter_mut().zip(tree.predict(inputs)) {
+                *score += self.learning_rate * step;
+            }
+            result.push(scores.clone());
+        }
+        return result;
+    }
+}
+
+pub struct GradientBoostingRegressor {
+    booster: Booster,
+}
+
+impl GradientBoostingRegressor {
+    pub fn new(num_estimators: usize) -> GradientBoostingRegressor {
+        GradientBoostingRegressor {
+            booster: Booster::new(Loss::SquaredError, num_estimators),
+        }
+    }
+
+    pub fn set_loss(&mut self, loss: Loss) {
+        if let Loss::LogLoss = loss {
+            panic!("Log loss is only available for classification!");
+        }
+        self.booster.loss = loss;
+    }
+
+    // Shrinkage applied to every tree's contribution
+    pub fn set_learning_rate(&mut self, learning_rate: f64) {
+        self.booster.learning_rate = learning_rate;
+    }
+
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.booster.max_depth = max_depth;
+    }
+
+    pub fn set_min_samples_leaf(&mut self, min_samples_leaf: usize) {
+        self.booster.min_samples_leaf = min_samples_leaf;
+    }
+
+    // Fraction of the rows, drawn without replacement, used to fit each tree
+    pub fn set_subsample(&mut self, subsample: f64) {
+        if subsample <= 0.0 || subsample > 1.0 {
+            panic!("Subsample must be in (0, 1]!");
+        }
+        self.booster.subsample = subsample;
+    }
+
+    // Stop once the validation loss has not improved for `patience` stages
+    pub fn set_early_stopping(&mut self, patience: usize) {
+        if patience == 0 {
+            panic!("Early stopping patience must be at least 1!");
+        }
+        self.booster.patience = Some(patience);
+    }
+
+    pub fn get_num_estimators(&self) -> usize {
+        return self.booster.trees.len();
+    }
+
+    pub fn get_train_losses(&self) -> &Vec<f64> {
+        return &self.booster.train_losses;
+    }
+
+    pub fn get_validation_losses(&self) -> &Vec<f64> {
+        return &self.booster.validation_losses;
+    }
+
+    pub fn fit(&mut self, inputs: &Matrix, targets: &Vec<f64>) {
+        self.booster.fit(inputs, targets, None);
+    }
+
+    pub fn fit_with_validation(&mut self, inputs: &Matrix, targets: &Vec<f64>, validation_inputs: &Matrix, validation_targets: &Vec<f64>) {
+        self.booster.fit(inputs, targets, Some((validation_inputs, validation_targets)));
+    }
+
+    pub fn predict(&self, inputs: &Matrix) -> Vec<f64> {
+        return self.booster.decision_function(inputs);
+    }
+
+    // Predictions after each boosting stage
+    pub fn staged_predict(&self, inputs: &Matrix) -> Vec<Vec<f64>> {
+        return self.booster.staged_decision_function(inputs);
+    }
+}
+
+pub struct GradientBoostingClassifier {
+    booster: Booster,
+}
+
+impl GradientBoostingClassifier {
+    pub fn new(num_estimators: usize) -> GradientBoostingClassifier {
+        GradientBoostingClassifier {
+            booster: Booster::new(Loss::LogLoss, num_estimators),
+        }
+    }
+
+    // Shrinkage applied to every tree's contribution
+    pub fn set_learning_rate(&mut self, learning_rate: f64) {
+        self.booster.learning_rate = learning_rate;
+    }
+
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.booster.max_depth = max_depth;
+    }
+
+    pub fn set_min_samples_leaf(&mut self, min_samples_leaf: usize) {
+        self.booster.min_samples_leaf = min_samples_leaf;
+    }
+
+    // Fraction of the rows, drawn without replacement, used to fit each tree
+    pub fn set_subsample(&mut self, subsample: f64) {
+        if subsample <= 0.0 || subsample > 1.0 {
+            panic!("Subsample must be in (0, 1]!");
+        }
+        self.booster.subsample = subsample;
+    }
+
+    // Stop once the validation loss has not improved for `patience` stages
+    pub fn set_early_stopping(&mut self, patience: usize) {
+        if patience == 0 {
+            panic!("Early stopping patience must be at least 1!");
+        }
+        self.booster.patience = Some(patience);
+    }
+
+    pub fn get_num_estimators(&self) -> usize {
+        return self.booster.trees.len();
+    }
+
+    pub fn get_train_losses(&self) -> &Vec<f64> {
+        return &self.booster.train_losses;
+    }
+
+    pub fn get_validation_losses(&self) -> &Vec<f64> {
+        return &self.booster.validation_losses;
+    }
+
+    fn to_targets(labels: &Vec<usize>) -> Vec<f64> {
+        if labels.iter().any(|&label| label > 1) {
+            panic!("Only binary labels (0 or 1) are supported!");
+        }
+        return labels.iter().map(|&label| label as f64).collect();
+    }
+
+    pub fn fit(&mut self, inputs: &Matrix, labels: &Vec<usize>) {
+        self.booster.fit(inputs, &GradientBoostingClassifier::to_targets(labels), None);
+    }
+
+    pub fn fit_with_validation(&mut self, inputs: &Matrix, labels: &Vec<usize>, validation_inputs: &Matrix, validation_labels: &Vec<usize>) {
+        let targets = GradientBoostingClassifier::to_targets(labels);
+        let validation_targets = GradientBoostingClassifier::to_targets(validation_labels);
+        self.booster.fit(inputs, &targets, Some((validation_inputs, &validation_targets)));
+    }
+
+    // Log-odds of the positive class
+    pub fn decision_function(&self, inputs: &Matrix) -> Vec<f64> {
+        return self.booster.decision_function(inputs);
+    }
+
+    pub fn predict_proba(&self, inputs: &Matrix) -> Matrix {
+        return GradientBoostingClassifier::to_probabilities(&self.decision_function(inputs));
+    }
+
+    pub fn predict(&self, inputs: &Matrix) -> Vec<usize> {
+        return self.decision_function(inputs).iter().map(|&f| if f > 0.0 { 1 } else { 0 }).collect();
+    }
+
+    // Class probabilities after each boosting stage
+    pub fn staged_predict_proba(&self, inputs: &Matrix) -> Vec<Matrix> {
+        return self.booster.staged_decision_function(inputs).iter()
+            .map(GradientBoostingClassifier::to_probabilities)
+            .collect();
+    }
+
+    // Predicted labels after each boosting stage
+    pub fn staged_predict(&self, inputs: &Matrix) -> Vec<Vec<usize>> {
+        return self.booster.staged_decision_function(inputs).iter()
+            .map(|scores| scores.iter().map(|&f| if f > 0.0 { 1 } else { 0 }).collect())
+            .collect();
+    }
+
+    fn to_probabilities(scores: &Vec<f64>) -> Matrix {
+        let mut data: Vec<f64> = Vec::new();
+        for &score in scores {
+            let p = sigmoid(score);
+            data.push(1.0 - p);
+            data.push(p);
+        }
+        return Matrix::from_vec(&data, scores.len(), 2);
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_gradient_boosting.rs"]
+mod test;
@@ -0,0 +1,621 @@
+extern crate rand;
+
+use std::f64::consts::PI;
+
+use rand::{Rng, SeedableRng, FromEntropy};
+use rand::rngs::StdRng;
+
+use super::k_means::KMeans;
+use super::math::log_sum_exp;
+use super::matrix::Matrix;
+
+// All of the recursions below work on log probabilities, so sequences far
+// longer than a few hundred steps do not underflow to zero.
+
+fn log_table(table: &Matrix) -> Vec<Vec<f64>> {
+    return table.get_rows().iter().map(|row| row.iter().map(|p| p.ln()).collect()).collect();
+}
+
+fn random_distribution<R: Rng>(size: usize, rng: &mut R) -> Vec<f64> {
+    let values: Vec<f64> = (0..size).map(|_| rng.gen_range(0.1, 1.0)).collect();
+    let total: f64 = values.iter().sum();
+    return values.iter().map(|v| v / total).collect();
+}
+
+fn random_table<R: Rng>(rows: usize, cols: usize, rng: &mut R) -> Matrix {
+    let data: Vec<f64> = (0..rows).flat_map(|_| random_distribution(cols, rng)).collect();
+    return Matrix::from_vec(&data, rows, cols);
+}
+
+fn check_distribution(values: &Vec<f64>) {
+    if values.iter().any(|&p| p < 0.0) || (values.iter().sum::<f64>() - 1.0).abs() > 1e-6 {
+        panic!("Probabilities must be non-negative and sum to one!");
+    }
+}
+
+fn check_table(table: &Matrix, rows: usize, cols: usize) {
+    if table.size() != (rows, cols) {
+        panic!("Incompatible Matrix Dimensions!");
+    }
+    for row in table.get_rows() {
+        check_distribution(&row);
+    }
+}
+
+// Normalizes accumulated counts row by row, keeping the previous row when a
+// state was never visited
+fn normalize_rows(counts: &Vec<Vec<f64>>, previous: &Matrix) -> Matrix {
+    let mut rows: Vec<Vec<f64>> = Vec::new();
+    for (i, row) in counts.iter().enumerate() {
+        let total: f64 = row.iter().sum();
+        if total > 0.0 {
+            rows.push(row.iter().map(|c| c / total).collect());
+        } else {
+            rows.push(previous.get_row(i));
+        }
+    }
+    return Matrix::from_rows(&rows);
+}
+
+// log alpha[t][i] = log P(o_1..o_t, s_t = i)
+fn forward(log_start: &Vec<f64>, log_transitions: &Vec<Vec<f64>>, log_emissions: &Vec<Vec<f64>>) -> Vec<Vec<f64>> {
+    let num_states = log_start.len();
+    let mut alpha: Vec<Vec<f64>> = vec![(0..num_states).map(|i| log_start[i] + log_emissions[0][i]).collect()];
+    for t in 1..log_emissions.len() {
+        let row: Vec<f64> = (0..num_states).map(|j| {
+            let incoming: Vec<f64> = (0..num_states).map(|i| alpha[t - 1][i] + log_transitions[i][j]).collect();
+            log_sum_exp(&incoming) + log_emissions[t][j]
+        }).collect();
+        alpha.push(row);
+    }
+    return alpha;
+}
+
+// log beta[t][i] = log P(o_t+1..o_T | s_t = i)
+fn backward(log_transitions: &Vec<Vec<f64>>, log_emissions: &Vec<Vec<f64>>) -> Vec<Vec<f64>> {
+    let length = log_emissions.len();
+    let num_states = log_transitions.len();
+    let mut beta = vec![vec![0.0; num_states]; length];
+    for t in (0..(length - 1)).rev() {
+        for i in 0..num_states {
+            let outgoing: Vec<f64> = (0..num_states)
+                .map(|j| log_transitions[i][j] + log_emissions[t + 1][j] + beta[t + 1][j])
+                .collect();
+            beta[t][i] = log_sum_exp(&outgoing);
+        }
+    }
+    return beta;
+}
+
+// Most likely state path and its joint log probability with the observations
+#[allow(clippy::needless_range_loop)]
+fn viterbi(log_start: &Vec<f64>, log_transitions: &Vec<Vec<f64>>, log_emissions: &Vec<Vec<f64>>) -> (Vec<usize>, f64) {
+    let num_states = log_start.len();
+    let length = log_emissions.len();
+    let mut scores: Vec<f64> = (0..num_states).map(|i| log_start[i] + log_emissions[0][i]).collect();
+    let mut back_pointers: Vec<Vec<usize>> = Vec::new();
+    for t in 1..length {
+        let mut next = vec![0.0; num_states];
+        let mut pointers = vec![0; num_states];
+        for j in 0..num_states {
+            let mut best = 0;
+            for i in 1..num_states {
+                if scores[i] + log_transitions[i][j] > scores[best] + log_transitions[best][j] {
+                    best = i;
+                }
+            }
+            next[j] = scores[best] + log_transitions[best][j] + log_emissions[t][j];
+            pointers[j] = best;
+        }
+        scores = next;
+        back_pointers.push(pointers);
+    }
+
+    let mut last = 0;
+    for i in 1..num_states {
+        if scores[i] > scores[last] {
+            last = i;
+        }
+    }
+    let mut path = vec![last; length];
+    for t in (1..length).rev() {
+        path[t - 1] = back_pointers[t - 1][path[t]];
+    }
+    return (path, scores[last]);
+}
+
+// Expected state occupancies and summed transition counts for one sequence
+struct Expectations {
+    // gamma[t][i] = P(s_t = i | observations)
+    gamma: Vec<Vec<f64>>,
+    transitions: Vec<Vec<f64>>,
+    log_likelihood: f64,
+}
+
+fn expectations(log_start: &Vec<f64>, log_transitions: &Vec<Vec<f64>>, log_emissions: &Vec<Vec<f64>>) -> Expectations {
+    let num_states = log_start.len();
+    let alpha = forward(log_start, log_transitions, log_emissions);
+    let beta = backward(log_transitions, log_emissions);
+    let length = log_emissions.len();
+    let log_likelihood = log_sum_exp(&alpha[length - 1]);
+
+    let gamma: Vec<Vec<f64>> = (0..length)
+        .map(|t| (0..num_states).map(|i| (alpha[t][i] + beta[t][i] - log_likelihood).exp()).collect())
+        .collect();
+    let mut transitions = vec![vec![0.0; num_states]; num_states];
+    for t in 0..(length - 1) {
+        for i in 0..num_states {
+            for j in 0..num_states {
+                let log_xi = alpha[t][i] + log_transitions[i][j] + log_emissions[t + 1][j] + beta[t + 1][j] - log_likelihood;
+                transitions[i][j] += log_xi.exp();
+            }
+        }
+    }
+    return Expectations { gamma, transitions, log_likelihood };
+}
+
+// Start and transition probabilities shared by both emission models
+struct Chain {
+    start: Vec<f64>,
+    transitions: Matrix,
+}
+
+impl Chain {
+    fn new<R: Rng>(num_states: usize, rng: &mut R) -> Chain {
+        Chain {
+            start: random_distribution(num_states, rng),
+            transitions: random_table(num_states, num_states, rng),
+        }
+    }
+
+    fn num_states(&self) -> usize {
+        return self.start.len();
+    }
+
+    fn log_parameters(&self) -> (Vec<f64>, Vec<Vec<f64>>) {
+        return (self.start.iter().map(|p| p.ln()).collect(), log_table(&self.transitions));
+    }
+
+    fn set_start_probabilities(&mut self, start: Vec<f64>) {
+        if start.len() != self.num_states() {
+            panic!("Incompatible Vector Dimensions!");
+        }
+        check_distribution(&start);
+        self.start = start;
+    }
+
+    fn set_transitions(&mut self, transitions: Matrix) {
+        check_table(&transitions, self.num_states(), self.num_states());
+        self.transitions = transitions;
+    }
+
+    fn forward(&self, log_emissions: &Vec<Vec<f64>>) -> Matrix {
+        let (log_start, log_transitions) = self.log_parameters();
+        return Matrix::from_rows(&forward(&log_start, &log_transitions, log_emissions));
+    }
+
+    fn backward(&self, log_emissions: &Vec<Vec<f64>>) -> Matrix {
+        let (_, log_transitions) = self.log_parameters();
+        return Matrix::from_rows(&backward(&log_transitions, log_emissions));
+    }
+
+    fn log_likelihood(&self, log_emissions: &Vec<Vec<f64>>) -> f64 {
+        let (log_start, log_transitions) = self.log_parameters();
+        let alpha = forward(&log_start, &log_transitions, log_emissions);
+        return log_sum_exp(&alpha[alpha.len() - 1]);
+    }
+
+    fn posteriors(&self, log_emissions: &Vec<Vec<f64>>) -> Matrix {
+        let (log_start, log_transitions) = self.log_parameters();
+        return Matrix::from_rows(&expectations(&log_start, &log_transitions, log_emissions).gamma);
+    }
+
+    fn viterbi(&self, log_emissions: &Vec<Vec<f64>>) -> (Vec<usize>, f64) {
+        let (log_start, log_transitions) = self.log_parameters();
+        return viterbi(&log_start, &log_transitions, log_emissions);
+    }
+
+    // E-step over every sequence: re-estimates the chain and returns the
+    // posteriors for the emission update along with the total log-likelihood
+    #[allow(clippy::needless_range_loop)]
+    fn baum_welch_step(&mut self, log_emissions: &Vec<Vec<Vec<f64>>>) -> (Vec<Vec<Vec<f64>>>, f64) {
+        let num_states = self.num_states();
+        let (log_start, log_transitions) = self.log_parameters();
+        let mut start = vec![0.0; num_states];
+        let mut transitions = vec![vec![0.0; num_states]; num_states];
+        let mut gammas: Vec<Vec<Vec<f64>>> = Vec::new();
+        let mut log_likelihood = 0.0;
+        for sequence in log_emissions {
+            let result = expectations(&log_start, &log_transitions, sequence);
+            for i in 0..num_states {
+                start[i] += result.gamma[0][i] / log_emissions.len() as f64;
+                for j in 0..num_states {
+                    transitions[i][j] += result.transitions[i][j];
+                }
+            }
+            log_likelihood += result.log_likelihood;
+            gammas.push(result.gamma);
+        }
+        self.start = start;
+        self.transitions = normalize_rows(&transitions, &self.transitions);
+        return (gammas, log_likelihood);
+    }
+}
+
+fn check_sequences<T>(sequences: &Vec<T>, length: impl Fn(&T) -> usize) {
+    if sequences.is_empty() || sequences.iter().any(|sequence| length(sequence) == 0) {
+        panic!("Sequences must not be empty!");
+    }
+}
+
+// Hidden Markov model over a finite alphabet of observation symbols.
+// Probabilities start out random; Baum-Welch training refines them from there.
+pub struct DiscreteHMM {
+    chain: Chain,
+    // num_states x num_symbols
+    emissions: Matrix,
+    max_iterations: usize,
+    tolerance: f64,
+    log_likelihoods: Vec<f64>,
+}
+
+impl DiscreteHMM {
+    pub fn new(num_states: usize, num_symbols: usize) -> DiscreteHMM {
+        if num_states == 0 || num_symbols == 0 {
+            panic!("At least one state and one symbol are required!");
+        }
+        let mut rng = StdRng::from_entropy();
+        DiscreteHMM {
+            chain: Chain::new(num_states, &mut rng),
+            emissions: random_table(num_states, num_symbols, &mut rng),
+            max_iterations: 100,
+            tolerance: 1e-4,
+            log_likelihoods: Vec::new(),
+        }
+    }
+
+    pub fn set_start_probabilities(&mut self, start: Vec<f64>) {
+        self.chain.set_start_probabilities(start);
+    }
+
+    // num_states x num_states, row i holding P(next state | state i)
+    pub fn set_transitions(&mut self, transitions: Matrix) {
+        self.chain.set_transitions(transitions);
+    }
+
+    // num_states x num_symbols, row i holding P(symbol | state i)
+    pub fn set_emissions(&mut self, emissions: Matrix) {
+        check_table(&emissions, self.chain.num_states(), self.emissions.size().1);
+        self.emissions = emissions;
+    }
+
+    // Redraws the random starting probabilities from a generator seeded with
+    // `seed`, replacing any that were set explicitly
+    pub fn set_seed(&mut self, seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let num_states = self.chain.num_states();
+        self.chain = Chain::new(num_states, &mut rng);
+        self.emissions = random_table(num_states, self.emissions.size().1, &mut rng);
+    }
+
+    pub fn set_max_iterations(&mut self, max_iterations: usize) {
+        self.max_iterations = max_iterations;
+    }
+
+    // Stop once the total log-likelihood improves by less than this
+    pub fn set_tolerance(&mut self, tolerance: f64) {
+        self.tolerance = tolerance;
+    }
+
+    pub fn get_start_probabilities(&self) -> &Vec<f64> {
+        return &self.chain.start;
+    }
+
+    pub fn get_transitions(&self) -> &Matrix {
+        return &self.chain.transitions;
+    }
+
+    pub fn get_emissions(&self) -> &Matrix {
+        return &self.emissions;
+    }
+
+    // Total log-likelihood of the training sequences before each update
+    pub fn get_log_likelihoods(&self) -> &Vec<f64> {
+        return &self.log_likelihoods;
+    }
+
+    fn log_emissions(&self, observations: &Vec<usize>) -> Vec<Vec<f64>> {
+        if observations.is_empty() {
+            panic!("Sequences must not be empty!");
+        }
+        let (num_states, num_symbols) = self.emissions.size();
+        return observations.iter().map(|&symbol| {
+            if symbol >= num_symbols {
+                panic!("Symbol out of range!");
+            }
+            (0..num_states).map(|i| self.emissions.get_at_index(i, symbol).ln()).collect()
+        }).collect();
+    }
+
+    // Log forward probabilities, one row per time step
+    pub fn forward(&self, observations: &Vec<usize>) -> Matrix {
+        return self.chain.forward(&self.log_emissions(observations));
+    }
+
+    // Log backward probabilities, one row per time step
+    pub fn backward(&self, observations: &Vec<usize>) -> Matrix {
+        return self.chain.backward(&self.log_emissions(observations));
+    }
+
+    pub fn log_likelihood(&self, observations: &Vec<usize>) -> f64 {
+        return self.chain.log_likelihood(&self.log_emissions(observations));
+    }
+
+    // P(state | all observations) from forward-backward, one row per time step
+    pub fn posteriors(&self, observations: &Vec<usize>) -> Matrix {
+        return self.chain.posteriors(&self.log_emissions(observations));
+    }
+
+    // Most likely state sequence and its log probability
+    pub fn viterbi(&self, observations: &Vec<usize>) -> (Vec<usize>, f64) {
+        return self.chain.viterbi(&self.log_emissions(observations));
+    }
+
+    // Baum-Welch, starting from the current parameters
+    pub fn fit(&mut self, sequences: &Vec<Vec<usize>>) {
+        check_sequences(sequences, |sequence| sequence.len());
+        let (num_states, num_symbols) = self.emissions.size();
+        self.log_likelihoods = Vec::new();
+        for _ in 0..self.max_iterations {
+            let log_emissions: Vec<Vec<Vec<f64>>> = sequences.iter().map(|s| self.log_emissions(s)).collect();
+            let (gammas, log_likelihood) = self.chain.baum_welch_step(&log_emissions);
+
+            let mut counts = vec![vec![0.0; num_symbols]; num_states];
+            for (sequence, gamma) in sequences.iter().zip(&gammas) {
+                for (&symbol, occupancy) in sequence.iter().zip(gamma) {
+                    for i in 0..num_states {
+                        counts[i][symbol] += occupancy[i];
+                    }
+                }
+            }
+            self.emissions = normalize_rows(&counts, &self.emissions);
+
+            let improvement = match self.log_likelihoods.last() {
+                Some(previous) => log_likelihood - previous,
+                None => f64::INFINITY,
+            };
+            self.log_likelihoods.push(log_likelihood);
+            if improvement < self.tolerance {
+                break;
+            }
+        }
+    }
+}
+
+// Hidden Markov model whose states emit real vectors from Gaussians with
+// diagonal covariance. Unless set explicitly, the means and variances are
+// seeded from a k-means clustering of the training data.
+pub struct GaussianHMM {
+    chain: Chain,
+    num_features: usize,
+    // num_states x num_features
+    means: Matrix,
+    variances: Matrix,
+    min_variance: f64,
+    max_iterations: usize,
+    tolerance: f64,
+    log_likelihoods: Vec<f64>,
+    rng: StdRng,
+}
+
+impl GaussianHMM {
+    pub fn new(num_states: usize, num_features: usize) -> GaussianHMM {
+        if num_states == 0 || num_features == 0 {
+            panic!("At least one state and one feature are required!");
+        }
+        let mut rng = StdRng::from_entropy();
+        GaussianHMM {
+            chain: Chain::new(num_states, &mut rng),
+            num_features,
+            means: Matrix::new(),
+            variances: Matrix::new(),
+            min_variance: 1e-3,
+            max_iterations: 100,
+            tolerance: 1e-4,
+            log_likelihoods: Vec::new(),
+            rng,
+        }
+    }
+
+    // Redraws the random start and transition probabilities, replacing any
+    // set explicitly, from a generator seeded with `seed`. The same generator
+    // seeds the k-means clustering that initializes the emissions.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.chain = Chain::new(self.chain.num_states(), &mut self.rng);
+    }
+
+    pub fn set_start_probabilities(&mut self, start: Vec<f64>) {
+        self.chain.set_start_probabilities(start);
+    }
+
+    pub fn set_transitions(&mut self, transitions: Matrix) {
+        self.chain.set_transitions(transitions);
+    }
+
+    // One row of feature means per state
+    pub fn set_means(&mut self, means: Matrix) {
+        if means.size() != (self.chain.num_states(), self.num_features) {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        self.means = means;
+    }
+
+    // One row of feature variances per state
+    pub fn set_variances(&mut self, variances: Matrix) {
+        if variances.size() != (self.chain.num_states(), self.num_features) {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        if variances.as_vec().iter().any(|&v| v <= 0.0) {
+            panic!("Variances must be positive!");
+        }
+        self.variances = variances;
+    }
+
+    // Lower bound on every variance during training
+    pub fn set_min_variance(&mut self, min_variance: f64) {
+        if min_variance <= 0.0 {
+            panic!("Minimum variance must be positive!");
+        }
+        self.min_variance = min_variance;
+    }
+
+    pub fn set_max_iterations(&mut self, max_iterations: usize) {
+        self.max_iterations = max_iterations;
+    }
+
+    pub fn set_tolerance(&mut self, tolerance: f64) {
+        self.tolerance = tolerance;
+    }
+
+    pub fn get_start_probabilities(&self) -> &Vec<f64> {
+        return &self.chain.start;
+    }
+
+    pub fn get_transitions(&self) -> &Matrix {
+        return &self.chain.transitions;
+    }
+
+    pub fn get_means(&self) -> &Matrix {
+        return &self.means;
+    }
+
+    pub fn get_variances(&self) -> &Matrix {
+        return &self.variances;
+    }
+
+    pub fn get_log_likelihoods(&self) -> &Vec<f64> {
+        return &self.log_likelihoods;
+    }
+
+    fn log_emissions(&self, observations: &Matrix) -> Vec<Vec<f64>> {
+        if self.means.size().0 == 0 || self.variances.size().0 == 0 {
+            panic!("Model has not been fitted!");
+        }
+        if observations.size().0 == 0 {
+            panic!("Sequences must not be empty!");
+        }
+        if observations.size().1 != self.num_features {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        let means = self.means.get_rows();
+        let variances = self.variances.get_rows();
+        return observations.get_rows().iter().map(|point| {
+            means.iter().zip(&variances).map(|(mean, variance)| {
+                (0..self.num_features).map(|d| {
+                    let diff = point[d] - mean[d];
+                    -0.5 * ((2.0 * PI * variance[d]).ln() + diff * diff / variance[d])
+                }).sum()
+            }).collect()
+        }).collect();
+    }
+
+    pub fn forward(&self, observations: &Matrix) -> Matrix {
+        return self.chain.forward(&self.log_emissions(observations));
+    }
+
+    pub fn backward(&self, observations: &Matrix) -> Matrix {
+        return self.chain.backward(&self.log_emissions(observations));
+    }
+
+    pub fn log_likelihood(&self, observations: &Matrix) -> f64 {
+        return self.chain.log_likelihood(&self.log_emissions(observations));
+    }
+
+    pub fn posteriors(&self, observations: &Matrix) -> Matrix {
+        return self.chain.posteriors(&self.log_emissions(observations));
+    }
+
+    pub fn viterbi(&self, observations: &Matrix) -> (Vec<usize>, f64) {
+        return self.chain.viterbi(&self.log_emissions(observations));
+    }
+
+    fn initialize_emissions(&mut self, sequences: &Vec<Matrix>) {
+        let points: Vec<Vec<f64>> = sequences.iter().flat_map(Matrix::get_rows).collect();
+        let num_states = self.chain.num_states();
+        if points.len() < num_states {
+            panic!("Fewer observations than states!");
+        }
+        if self.means.size().0 == 0 {
+            let mut kmeans = KMeans::new(num_states);
+            kmeans.set_seed(self.rng.gen());
+            kmeans.fit(&Matrix::from_rows(&points));
+            self.means = kmeans.get_centroids().clone();
+        }
+        if self.variances.size().0 == 0 {
+            let count = points.len() as f64;
+            let variance: Vec<f64> = (0..self.num_features).map(|d| {
+                let mean = points.iter().map(|p| p[d]).sum::<f64>() / count;
+                let spread = points.iter().map(|p| (p[d] - mean) * (p[d] - mean)).sum::<f64>() / count;
+                spread.max(self.min_variance)
+            }).collect();
+            self.variances = Matrix::from_rows(&vec![variance; num_states]);
+        }
+    }
+
+    // Baum-Welch, starting from the current parameters
+    pub fn fit(&mut self, sequences: &Vec<Matrix>) {
+        check_sequences(sequences, |sequence| sequence.size().0);
+        self.initialize_emissions(sequences);
+        let num_states = self.chain.num_states();
+        let dims = self.num_features;
+        self.log_likelihoods = Vec::new();
+        for _ in 0..self.max_iterations {
+            let log_emissions: Vec<Vec<Vec<f64>>> = sequences.iter().map(|s| self.log_emissions(s)).collect();
+            let (gammas, log_likelihood) = self.chain.baum_welch_step(&log_emissions);
+
+            let mut occupancy = vec![0.0; num_states];
+            let mut sums = vec![vec![0.0; dims]; num_states];
+            let mut squares = vec![vec![0.0; dims]; num_states];
+            for (sequence, gamma) in sequences.iter().zip(&gammas) {
+                for (point, weights) in sequence.get_rows().iter().zip(gamma) {
+                    for i in 0..num_states {
+                        occupancy[i] += weights[i];
+                        for d in 0..dims {
+                            sums[i][d] += weights[i] * point[d];
+                            squares[i][d] += weights[i] * point[d] * point[d];
+                        }
+                    }
+                }
+            }
+            let mut means = self.means.get_rows();
+            let mut variances = self.variances.get_rows();
+            for i in 0..num_states {
+                // A state nobody visits keeps its old emission parameters
+                if occupancy[i] <= 0.0 {
+                    continue;
+                }
+                for d in 0..dims {
+                    let mean = sums[i][d] / occupancy[i];
+                    means[i][d] = mean;
+                    variances[i][d] = (squares[i][d] / occupancy[i] - mean * mean).max(self.min_variance);
+                }
+            }
+            self.means = Matrix::from_rows(&means);
+            self.variances = Matrix::from_rows(&variances);
+
+            let improvement = match self.log_likelihoods.last() {
+                Some(previous) => log_likelihood - previous,
+                None => f64::INFINITY,
+            };
+            self.log_likelihoods.push(log_likelihood);
+            if improvement < self.tolerance {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_hidden_markov_model.rs"]
+mod test;
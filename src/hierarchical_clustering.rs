@@ -0,0 +1,185 @@
+use super::matrix::Matrix;
+use super::math::euclidean;
+
+pub enum Linkage {
+    // Closest pair between the clusters
+    Single,
+    // Farthest pair between the clusters
+    Complete,
+    // Mean distance over all pairs
+    Average,
+    // Increase in within-cluster variance caused by the merge
+    Ward,
+}
+
+// One merge of the dendrogram. Clusters 0..n are the original points and
+// the merge at step i creates cluster n + i, as in SciPy's linkage matrix.
+#[derive(Debug, PartialEq)]
+pub struct Merge {
+    pub left: usize,
+    pub right: usize,
+    pub distance: f64,
+    pub size: usize,
+}
+
+pub struct AgglomerativeClustering {
+    linkage: Linkage,
+    dendrogram: Vec<Merge>,
+    num_samples: usize,
+}
+
+impl AgglomerativeClustering {
+    pub fn new(linkage: Linkage) -> AgglomerativeClustering {
+        AgglomerativeClustering {
+            linkage,
+            dendrogram: Vec::new(),
+            num_samples: 0,
+        }
+    }
+
+    pub fn get_dendrogram(&self) -> &Vec<Merge> {
+        return &self.dendrogram;
+    }
+
+    // Builds the full merge tree with the Lance-Williams update, so every
+    // linkage runs on the same distance matrix
+    pub fn fit(&mut self, inputs: &Matrix) {
+        let points = inputs.get_rows();
+        let n = points.len();
+        if n < 2 {
+            panic!("At least two samples are required!");
+        }
+        self.num_samples = n;
+        self.dendrogram = Vec::new();
+
+        // Ward's update works on squared distances
+        let is_ward = matches!(self.linkage, Linkage::Ward);
+        let mut distances = vec![vec![0.0; n]; n];
+        for i in 0..n {
+            for j in 0..n {
+                let d = euclidean(&points[i], &points[j]);
+                distances[i][j] = if is_ward { d * d } else { d };
+            }
+        }
+
+        // Slot i holds the id and size of a live cluster
+        let mut active: Vec<Option<(usize, usize)>> = (0..n).map(|i| Some((i, 1))).collect();
+        for step in 0..(n - 1) {
+            let mut best = (0, 0, f64::INFINITY);
+            for i in 0..n {
+                if active[i].is_none() {
+                    continue;
+                }
+                for j in (i + 1)..n {
+                    if active[j].is_some() && distances[i][j] < best.2 {
+                        best = (i, j, distances[i][j]);
+                    }
+                }
+            }
+            let (a, b, distance) = best;
+            let (id_a, size_a) = active[a].unwrap();
+            let (id_b, size_b) = active[b].unwrap();
+
+            for k in 0..n {
+                if k == a || k == b {
+                    continue;
+                }
+                let size_k = match active[k] {
+                    Some((_, size)) => size as f64,
+                    None => continue,
+                };
+                let (d_ak, d_bk) = (distances[a][k], distances[b][k]);
+                let (na, nb) = (size_a as f64, size_b as f64);
+                let updated = match self.linkage {
+                    Linkage::Single => d_ak.min(d_bk),
+                    Linkage::Complete => d_ak.max(d_bk),
+                    Linkage::Average => (na * d_ak + nb * d_bk) / (na + nb),
+                    Linkage::Ward => ((na + size_k) * d_ak + (nb + size_k) * d_bk - size_k * distance) / (na + nb + size_k),
+                };
+                distances[a][k] = updated;
+                distances[k][a] = updated;
+            }
+
+            let size = size_a + size_b;
+            active[a] = Some((n + step, size));
+            active[b] = None;
+            self.dendrogram.push(Merge {
+                left: id_a.min(id_b),
+                right: id_a.max(id_b),
+                distance: if is_ward { distance.max(0.0).sqrt() } else { distance },
+                size,
+            });
+        }
+    }
+
+    // Cuts the dendrogram so that `num_clusters` clusters remain; clusters
+    // are numbered in order of their first point
+    pub fn cut(&self, num_clusters: usize) -> Vec<usize> {
+        if self.dendrogram.is_empty() {
+            panic!("Model has not been fitted!");
+        }
+        if num_clusters == 0 || num_clusters > self.num_samples {
+            panic!("Number of clusters out of range!");
+        }
+        let merges = self.num_samples - num_clusters;
+        return self.labels_after(merges);
+    }
+
+    // Cuts the dendrogram at a merge distance
+    pub fn cut_at_distance(&self, threshold: f64) -> Vec<usize> {
+        if self.dendrogram.is_empty() {
+            panic!("Model has not been fitted!");
+        }
+        let merges = self.dendrogram.iter().take_while(|merge| merge.distance <= threshold).count();
+        return self.labels_after(merges);
+    }
+
+    fn labels_after(&self, merges: usize) -> Vec<usize> {
+        let n = self.num_samples;
+        // Union-find over cluster ids
+        let mut parent: Vec<usize> = (0..(2 * n - 1)).collect();
+        for (step, merge) in self.dendrogram.iter().take(merges).enumerate() {
+            parent[merge.left] = n + step;
+            parent[merge.right] = n + step;
+        }
+        let root = |mut id: usize| {
+            while parent[id] != id {
+                id = parent[id];
+            }
+            id
+        };
+
+        let mut roots: Vec<usize> = Vec::new();
+        let mut result: Vec<usize> = Vec::new();
+        for i in 0..n {
+            let r = root(i);
+            match roots.iter().position(|&existing| existing == r) {
+                Some(label) => result.push(label),
+                None => {
+                    roots.push(r);
+                    result.push(roots.len() - 1);
+                },
+            }
+        }
+        return result;
+    }
+
+    pub fn fit_predict(&mut self, inputs: &Matrix, num_clusters: usize) -> Vec<usize> {
+        self.fit(inputs);
+        return self.cut(num_clusters);
+    }
+
+    // Text rendering of the merge tree, one merge per line
+    pub fn dendrogram_to_string(&self) -> String {
+        let mut result = String::new();
+        for (step, merge) in self.dendrogram.iter().enumerate() {
+            result.push_str(&format!("{}: {} + {} -> {} (distance {:.4}, size {})\n",
+                                     step, merge.left, merge.right, self.num_samples + step, merge.distance, merge.size));
+        }
+        return result;
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_hierarchical_clustering.rs"]
+mod test;
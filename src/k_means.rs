@@ -0,0 +1,320 @@
+extern crate rand;
+
+use rand::{Rng, SeedableRng, FromEntropy};
+use rand::rngs::StdRng;
+
+use super::matrix::Matrix;
+
+pub enum Initialization {
+    Random,
+    KMeansPlusPlus,
+}
+
+fn squared_distance(a: &Vec<f64>, b: &Vec<f64>) -> f64 {
+    return a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum();
+}
+
+// Index of the closest centroid and the squared distance to it
+fn closest(point: &Vec<f64>, centroids: &Vec<Vec<f64>>) -> (usize, f64) {
+    let mut best = (0, f64::INFINITY);
+    for (c, centroid) in centroids.iter().enumerate() {
+        let distance = squared_distance(point, centroid);
+        if distance < best.1 {
+            best = (c, distance);
+        }
+    }
+    return best;
+}
+
+fn initial_centroids<R: Rng>(points: &Vec<Vec<f64>>, k: usize, initialization: &Initialization, rng: &mut R) -> Vec<Vec<f64>> {
+    let mut centroids: Vec<Vec<f64>> = Vec::new();
+    match initialization {
+        Initialization::Random => {
+            let chosen = rand::seq::index::sample(rng, points.len(), k);
+            for i in chosen.iter() {
+                centroids.push(points[i].clone());
+            }
+        },
+        Initialization::KMeansPlusPlus => {
+            // Each new seed is drawn with probability proportional to its
+            // squared distance from the nearest seed chosen so far
+            centroids.push(points[rng.gen_range(0, points.len())].clone());
+            let mut distances: Vec<f64> = points.iter().map(|p| squared_distance(p, &centroids[0])).collect();
+            while centroids.len() < k {
+                let total: f64 = distances.iter().sum();
+                let mut next = rng.gen_range(0, points.len());
+                if total > 0.0 {
+                    let mut target = rng.gen_range(0.0, total);
+                    for (i, distance) in distances.iter().enumerate() {
+                        if target < *distance {
+                            next = i;
+                            break;
+                        }
+                        target -= distance;
+                    }
+                }
+                centroids.push(points[next].clone());
+                let newest = &centroids[centroids.len() - 1];
+                for (i, point) in points.iter().enumerate() {
+                    distances[i] = distances[i].min(squared_distance(point, newest));
+                }
+            }
+        },
+    }
+    return centroids;
+}
+
+pub struct KMeans {
+    k: usize,
+    initialization: Initialization,
+    num_restarts: usize,
+    max_iterations: usize,
+    tolerance: f64,
+    centroids: Matrix,
+    inertia: f64,
+    num_iterations: usize,
+    rng: StdRng,
+}
+
+impl KMeans {
+    pub fn new(k: usize) -> KMeans {
+        if k == 0 {
+            panic!("k must be at least 1!");
+        }
+        KMeans {
+            k,
+            initialization: Initialization::KMeansPlusPlus,
+            num_restarts: 10,
+            max_iterations: 300,
+            tolerance: 1e-4,
+            centroids: Matrix::new(),
+            inertia: 0.0,
+            num_iterations: 0,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    pub fn set_initialization(&mut self, initialization: Initialization) {
+        self.initialization = initialization;
+    }
+
+    pub fn set_num_restarts(&mut self, num_restarts: usize) {
+        if num_restarts == 0 {
+            panic!("At least one run is required!");
+        }
+        self.num_restarts = num_restarts;
+    }
+
+    pub fn set_max_iterations(&mut self, max_iterations: usize) {
+        self.max_iterations = max_iterations;
+    }
+
+    pub fn set_tolerance(&mut self, tolerance: f64) {
+        self.tolerance = tolerance;
+    }
+
+    // Makes the seeding of the centroids reproducible
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    pub fn get_centroids(&self) -> &Matrix {
+        return &self.centroids;
+    }
+
+    // Sum of squared distances from each training point to its centroid
+    pub fn get_inertia(&self) -> f64 {
+        return self.inertia;
+    }
+
+    pub fn get_num_iterations(&self) -> usize {
+        return self.num_iterations;
+    }
+
+    pub fn fit(&mut self, inputs: &Matrix) {
+        let points = inputs.get_rows();
+        if points.len() < self.k {
+            panic!("Fewer samples than clusters!");
+        }
+        let mut rng = self.rng.clone();
+
+        // Keep the run with the lowest inertia
+        let mut best: Option<(Vec<Vec<f64>>, f64, usize)> = None;
+        for _ in 0..self.num_restarts {
+            let run = self.single_run(&points, &mut rng);
+            let is_better = match &best {
+                Some(current) => run.1 < current.1,
+                None => true,
+            };
+            if is_better {
+                best = Some(run);
+            }
+        }
+
+        self.rng = rng;
+
+        let (centroids, inertia, num_iterations) = best.unwrap();
+        self.centroids = Matrix::from_rows(&centroids);
+        self.inertia = inertia;
+        self.num_iterations = num_iterations;
+    }
+
+    fn single_run<R: Rng>(&self, points: &Vec<Vec<f64>>, rng: &mut R) -> (Vec<Vec<f64>>, f64, usize) {
+        let dims = points[0].len();
+        let mut centroids = initial_centroids(points, self.k, &self.initialization, rng);
+        let mut iterations = 0;
+
+        while iterations < self.max_iterations {
+            iterations += 1;
+
+            let mut sums = vec![vec![0.0; dims]; self.k];
+            let mut counts = vec![0usize; self.k];
+            for point in points {
+                let (c, _) = closest(point, &centroids);
+                counts[c] += 1;
+                for d in 0..dims {
+                    sums[c][d] += point[d];
+                }
+            }
+
+            let mut shift = 0.0;
+            for c in 0..self.k {
+                // An empty cluster keeps its previous centroid
+                if counts[c] == 0 {
+                    continue;
+                }
+                let updated: Vec<f64> = sums[c].iter().map(|s| s / counts[c] as f64).collect();
+                shift += squared_distance(&updated, &centroids[c]);
+                centroids[c] = updated;
+            }
+            if shift <= self.tolerance * self.tolerance {
+                break;
+            }
+        }
+
+        let inertia = points.iter().map(|p| closest(p, &centroids).1).sum();
+        return (centroids, inertia, iterations);
+    }
+
+    pub fn predict(&self, inputs: &Matrix) -> Vec<usize> {
+        if self.centroids.size().0 == 0 {
+            panic!("Model has not been fitted!");
+        }
+        let centroids = self.centroids.get_rows();
+        return inputs.get_rows().iter().map(|p| closest(p, &centroids).0).collect();
+    }
+}
+
+pub struct MiniBatchKMeans {
+    k: usize,
+    batch_size: usize,
+    max_iterations: usize,
+    tolerance: f64,
+    centroids: Vec<Vec<f64>>,
+    // Points seen by each centroid, which sets its per-center learning rate
+    counts: Vec<usize>,
+    inertia: f64,
+    rng: StdRng,
+}
+
+impl MiniBatchKMeans {
+    pub fn new(k: usize, batch_size: usize) -> MiniBatchKMeans {
+        if k == 0 {
+            panic!("k must be at least 1!");
+        }
+        MiniBatchKMeans {
+            k,
+            batch_size,
+            max_iterations: 100,
+            tolerance: 1e-4,
+            centroids: Vec::new(),
+            counts: Vec::new(),
+            inertia: 0.0,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    pub fn set_max_iterations(&mut self, max_iterations: usize) {
+        self.max_iterations = max_iterations;
+    }
+
+    pub fn set_tolerance(&mut self, tolerance: f64) {
+        self.tolerance = tolerance;
+    }
+
+    // Makes the seeding of the centroids and the batch sampling reproducible
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    pub fn get_centroids(&self) -> Matrix {
+        return Matrix::from_rows(&self.centroids);
+    }
+
+    pub fn get_inertia(&self) -> f64 {
+        return self.inertia;
+    }
+
+    pub fn fit(&mut self, inputs: &Matrix) {
+        let points = inputs.get_rows();
+        if points.len() < self.k {
+            panic!("Fewer samples than clusters!");
+        }
+        self.centroids = initial_centroids(&points, self.k, &Initialization::KMeansPlusPlus, &mut self.rng);
+        self.counts = vec![0; self.k];
+
+        let batch_size = self.batch_size.min(points.len());
+        for _ in 0..self.max_iterations {
+            let batch: Vec<Vec<f64>> = rand::seq::index::sample(&mut self.rng, points.len(), batch_size)
+                .iter()
+                .map(|i| points[i].clone())
+                .collect();
+            let shift = self.update(&batch);
+            if shift <= self.tolerance * self.tolerance {
+                break;
+            }
+        }
+        self.inertia = points.iter().map(|p| closest(p, &self.centroids).1).sum();
+    }
+
+    // Updates the centroids from one batch of a stream; the first call seeds
+    // the centroids from that batch
+    pub fn partial_fit(&mut self, inputs: &Matrix) {
+        let points = inputs.get_rows();
+        if self.centroids.is_empty() {
+            if points.len() < self.k {
+                panic!("Fewer samples than clusters!");
+            }
+            self.centroids = initial_centroids(&points, self.k, &Initialization::KMeansPlusPlus, &mut self.rng);
+            self.counts = vec![0; self.k];
+        }
+        self.update(&points);
+        self.inertia = points.iter().map(|p| closest(p, &self.centroids).1).sum();
+    }
+
+    // Moves each centroid towards its assigned points with a learning rate
+    // of 1 / count; returns the total squared centroid shift
+    fn update(&mut self, batch: &Vec<Vec<f64>>) -> f64 {
+        let previous = self.centroids.clone();
+        let assignments: Vec<usize> = batch.iter().map(|p| closest(p, &self.centroids).0).collect();
+        for (point, &c) in batch.iter().zip(assignments.iter()) {
+            self.counts[c] += 1;
+            let rate = 1.0 / self.counts[c] as f64;
+            for (centroid, x) in self.centroids[c].iter_mut().zip(point.iter()) {
+                *centroid = (1.0 - rate) * *centroid + rate * x;
+            }
+        }
+        return previous.iter().zip(self.centroids.iter()).map(|(a, b)| squared_distance(a, b)).sum();
+    }
+
+    pub fn predict(&self, inputs: &Matrix) -> Vec<usize> {
+        if self.centroids.is_empty() {
+            panic!("Model has not been fitted!");
+        }
+        return inputs.get_rows().iter().map(|p| closest(p, &self.centroids).0).collect();
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_k_means.rs"]
+mod test;
@@ -0,0 +1,374 @@
+use super::matrix::Matrix;
+use super::math::{argmax, euclidean};
+
+pub enum Distance {
+    Euclidean,
+    Manhattan,
+    Cosine,
+    Minkowski(f64),
+}
+
+impl Distance {
+    pub fn compute(&self, a: &Vec<f64>, b: &Vec<f64>) -> f64 {
+        if a.len() != b.len() {
+            panic!("Incompatible Vector Dimensions!");
+        }
+        match self {
+            Distance::Euclidean => {
+                return euclidean(a, b);
+            },
+            Distance::Manhattan => {
+                return a.iter().zip(b).map(|(x, y)| (x - y).abs()).sum();
+            },
+            Distance::Cosine => {
+                let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+                let norm_a: f64 = a.iter().map(|x| x * x).sum::<f64>().sqrt();
+                let norm_b: f64 = b.iter().map(|x| x * x).sum::<f64>().sqrt();
+                if norm_a == 0.0 || norm_b == 0.0 {
+                    return 1.0;
+                }
+                return 1.0 - dot / (norm_a * norm_b);
+            },
+            Distance::Minkowski(p) => {
+                let sum: f64 = a.iter().zip(b).map(|(x, y)| (x - y).abs().powf(*p)).sum();
+                return sum.powf(1.0 / p);
+            },
+        }
+    }
+
+    // Whether |a_i - b_i| on a single axis bounds the full distance from
+    // below, which is what lets the KD-tree prune whole branches
+    fn is_axis_bounded(&self) -> bool {
+        match self {
+            Distance::Euclidean | Distance::Manhattan => true,
+            Distance::Cosine => false,
+            Distance::Minkowski(p) => *p >= 1.0,
+        }
+    }
+}
+
+pub enum Weighting {
+    Uniform,
+    // Each neighbor votes with 1 / distance
+    Distance,
+}
+
+pub enum Algorithm {
+    // KD-tree when the distance allows it, brute force otherwise
+    Auto,
+    BruteForce,
+    KdTree,
+}
+
+enum KdNode {
+    Leaf(Vec<usize>),
+    Split {
+        axis: usize,
+        value: f64,
+        left: Box<KdNode>,
+        right: Box<KdNode>,
+    },
+}
+
+pub struct KdTree {
+    points: Vec<Vec<f64>>,
+    root: KdNode,
+}
+
+impl KdTree {
+    const LEAF_SIZE: usize = 8;
+
+    pub fn new(points: &Matrix) -> KdTree {
+        return KdTree::from_points(points.get_rows());
+    }
+
+    fn from_points(points: Vec<Vec<f64>>) -> KdTree {
+        let indices: Vec<usize> = (0..points.len()).collect();
+        let root = KdTree::build(&points, indices);
+        KdTree {
+            points,
+            root,
+        }
+    }
+
+    #[allow(clippy::needless_range_loop)]
+    fn build(points: &Vec<Vec<f64>>, mut indices: Vec<usize>) -> KdNode {
+        if indices.len() <= KdTree::LEAF_SIZE {
+            return KdNode::Leaf(indices);
+        }
+
+        // Split along the axis with the widest spread
+        let dims = points[indices[0]].len();
+        let mut axis = 0;
+        let mut widest = -1.0;
+        for d in 0..dims {
+            let min = indices.iter().map(|&i| points[i][d]).fold(f64::INFINITY, f64::min);
+            let max = indices.iter().map(|&i| points[i][d]).fold(f64::NEG_INFINITY, f64::max);
+            if max - min > widest {
+                widest = max - min;
+                axis = d;
+            }
+        }
+        if widest <= 0.0 {
+            return KdNode::Leaf(indices);
+        }
+
+        indices.sort_by(|&a, &b| points[a][axis].partial_cmp(&points[b][axis]).unwrap());
+        let middle = indices.len() / 2;
+        let value = points[indices[middle]][axis];
+        let right = indices.split_off(middle);
+        KdNode::Split {
+            axis,
+            value,
+            left: Box::new(KdTree::build(points, indices)),
+            right: Box::new(KdTree::build(points, right)),
+        }
+    }
+
+    // The k nearest points as (index, distance), closest first
+    pub fn query(&self, point: &Vec<f64>, k: usize, distance: &Distance) -> Vec<(usize, f64)> {
+        if !distance.is_axis_bounded() {
+            panic!("KD-tree does not support this distance!");
+        }
+        let mut result: Vec<(usize, f64)> = Vec::new();
+        self.search(&self.root, point, k, distance, &mut result);
+        return result;
+    }
+
+    fn search(&self, node: &KdNode, point: &Vec<f64>, k: usize, distance: &Distance, best: &mut Vec<(usize, f64)>) {
+        match node {
+            KdNode::Leaf(indices) => {
+                for &i in indices {
+                    insert_neighbor(best, k, (i, distance.compute(point, &self.points[i])));
+                }
+            },
+            KdNode::Split { axis, value, left, right } => {
+                let diff = point[*axis] - value;
+                let (near, far) = if diff < 0.0 { (left, right) } else { (right, left) };
+                self.search(near, point, k, distance, best);
+                if best.len() < k || diff.abs() <= best[best.len() - 1].1 {
+                    self.search(far, point, k, distance, best);
+                }
+            },
+        }
+    }
+}
+
+// Keeps `best` sorted by distance and no longer than k
+fn insert_neighbor(best: &mut Vec<(usize, f64)>, k: usize, candidate: (usize, f64)) {
+    if best.len() == k && candidate.1 >= best[k - 1].1 {
+        return;
+    }
+    let position = best.iter().position(|n| n.1 > candidate.1).unwrap_or(best.len());
+    best.insert(position, candidate);
+    best.truncate(k);
+}
+
+struct NeighborIndex {
+    points: Vec<Vec<f64>>,
+    tree: Option<KdTree>,
+}
+
+impl NeighborIndex {
+    fn new(inputs: &Matrix, distance: &Distance, algorithm: &Algorithm) -> NeighborIndex {
+        return NeighborIndex::build(inputs.get_rows(), distance, algorithm);
+    }
+
+    fn build(points: Vec<Vec<f64>>, distance: &Distance, algorithm: &Algorithm) -> NeighborIndex {
+        let use_tree = match algorithm {
+            Algorithm::Auto => distance.is_axis_bounded(),
+            Algorithm::BruteForce => false,
+            Algorithm::KdTree => {
+                if !distance.is_axis_bounded() {
+                    panic!("KD-tree does not support this distance!");
+                }
+                true
+            },
+        };
+        let tree = if use_tree { Some(KdTree::from_points(points.clone())) } else { None };
+        NeighborIndex {
+            points,
+            tree,
+        }
+    }
+
+    // Same points indexed for a new distance or algorithm, since a tree
+    // built for one distance cannot answer queries for every other
+    fn rebuild(&self, distance: &Distance, algorithm: &Algorithm) -> NeighborIndex {
+        return NeighborIndex::build(self.points.clone(), distance, algorithm);
+    }
+
+    fn query(&self, point: &Vec<f64>, k: usize, distance: &Distance) -> Vec<(usize, f64)> {
+        if let Some(tree) = &self.tree {
+            return tree.query(point, k, distance);
+        }
+        let mut best: Vec<(usize, f64)> = Vec::new();
+        for (i, other) in self.points.iter().enumerate() {
+            insert_neighbor(&mut best, k, (i, distance.compute(point, other)));
+        }
+        return best;
+    }
+}
+
+// Vote weights for a set of neighbors; exact matches take all the weight
+fn neighbor_weights(neighbors: &Vec<(usize, f64)>, weighting: &Weighting) -> Vec<f64> {
+    match weighting {
+        Weighting::Uniform => vec![1.0; neighbors.len()],
+        Weighting::Distance => {
+            if neighbors.iter().any(|n| n.1 == 0.0) {
+                return neighbors.iter().map(|n| if n.1 == 0.0 { 1.0 } else { 0.0 }).collect();
+            }
+            neighbors.iter().map(|n| 1.0 / n.1).collect()
+        },
+    }
+}
+
+pub struct KNNClassifier {
+    k: usize,
+    num_classes: usize,
+    distance: Distance,
+    weighting: Weighting,
+    algorithm: Algorithm,
+    index: Option<NeighborIndex>,
+    labels: Vec<usize>,
+}
+
+impl KNNClassifier {
+    pub fn new(k: usize, num_classes: usize) -> KNNClassifier {
+        if k == 0 {
+            panic!("k must be at least 1!");
+        }
+        KNNClassifier {
+            k,
+            num_classes,
+            distance: Distance::Euclidean,
+            weighting: Weighting::Uniform,
+            algorithm: Algorithm::Auto,
+            index: None,
+            labels: Vec::new(),
+        }
+    }
+
+    // A fitted model is re-indexed for the new distance
+    pub fn set_distance(&mut self, distance: Distance) {
+        self.distance = distance;
+        self.index = self.index.as_ref().map(|index| index.rebuild(&self.distance, &self.algorithm));
+    }
+
+    pub fn set_weighting(&mut self, weighting: Weighting) {
+        self.weighting = weighting;
+    }
+
+    pub fn set_algorithm(&mut self, algorithm: Algorithm) {
+        self.algorithm = algorithm;
+        self.index = self.index.as_ref().map(|index| index.rebuild(&self.distance, &self.algorithm));
+    }
+
+    pub fn fit(&mut self, inputs: &Matrix, labels: &Vec<usize>) {
+        if inputs.size().0 != labels.len() {
+            panic!("Inputs and labels must have the same number of rows!");
+        }
+        if labels.iter().any(|&label| label >= self.num_classes) {
+            panic!("Label out of range!");
+        }
+        self.index = Some(NeighborIndex::new(inputs, &self.distance, &self.algorithm));
+        self.labels = labels.clone();
+    }
+
+    pub fn kneighbors(&self, point: &Vec<f64>) -> Vec<(usize, f64)> {
+        let index = self.index.as_ref().expect("Model has not been fitted!");
+        return index.query(point, self.k, &self.distance);
+    }
+
+    pub fn predict_proba(&self, inputs: &Matrix) -> Matrix {
+        let rows = inputs.size().0;
+        let mut data: Vec<f64> = Vec::new();
+        for i in 0..rows {
+            let neighbors = self.kneighbors(&inputs.get_row(i));
+            let weights = neighbor_weights(&neighbors, &self.weighting);
+            let total: f64 = weights.iter().sum();
+            let mut votes = vec![0.0; self.num_classes];
+            for (neighbor, weight) in neighbors.iter().zip(weights) {
+                votes[self.labels[neighbor.0]] += weight / total;
+            }
+            data.extend(votes);
+        }
+        return Matrix::from_vec(&data, rows, self.num_classes);
+    }
+
+    pub fn predict(&self, inputs: &Matrix) -> Vec<usize> {
+        let probabilities = self.predict_proba(inputs);
+        return (0..probabilities.size().0).map(|i| argmax(&probabilities.get_row(i))).collect();
+    }
+}
+
+pub struct KNNRegressor {
+    k: usize,
+    distance: Distance,
+    weighting: Weighting,
+    algorithm: Algorithm,
+    index: Option<NeighborIndex>,
+    targets: Vec<f64>,
+}
+
+impl KNNRegressor {
+    pub fn new(k: usize) -> KNNRegressor {
+        if k == 0 {
+            panic!("k must be at least 1!");
+        }
+        KNNRegressor {
+            k,
+            distance: Distance::Euclidean,
+            weighting: Weighting::Uniform,
+            algorithm: Algorithm::Auto,
+            index: None,
+            targets: Vec::new(),
+        }
+    }
+
+    // A fitted model is re-indexed for the new distance
+    pub fn set_distance(&mut self, distance: Distance) {
+        self.distance = distance;
+        self.index = self.index.as_ref().map(|index| index.rebuild(&self.distance, &self.algorithm));
+    }
+
+    pub fn set_weighting(&mut self, weighting: Weighting) {
+        self.weighting = weighting;
+    }
+
+    pub fn set_algorithm(&mut self, algorithm: Algorithm) {
+        self.algorithm = algorithm;
+        self.index = self.index.as_ref().map(|index| index.rebuild(&self.distance, &self.algorithm));
+    }
+
+    pub fn fit(&mut self, inputs: &Matrix, targets: &Vec<f64>) {
+        if inputs.size().0 != targets.len() {
+            panic!("Inputs and targets must have the same number of rows!");
+        }
+        self.index = Some(NeighborIndex::new(inputs, &self.distance, &self.algorithm));
+        self.targets = targets.clone();
+    }
+
+    pub fn kneighbors(&self, point: &Vec<f64>) -> Vec<(usize, f64)> {
+        let index = self.index.as_ref().expect("Model has not been fitted!");
+        return index.query(point, self.k, &self.distance);
+    }
+
+    pub fn predict(&self, inputs: &Matrix) -> Vec<f64> {
+        let mut result: Vec<f64> = Vec::new();
+        for i in 0..inputs.size().0 {
+            let neighbors = self.kneighbors(&inputs.get_row(i));
+            let weights = neighbor_weights(&neighbors, &self.weighting);
+            let total: f64 = weights.iter().sum();
+            let value: f64 = neighbors.iter().zip(weights)
+                .map(|(neighbor, weight)| self.targets[neighbor.0] * weight)
+                .sum();
+            result.push(value / total);
+        }
+        return result;
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_k_nearest_neighbors.rs"]
+mod test;
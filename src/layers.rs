@@ -0,0 +1,746 @@
+extern crate rand;
+
+use rand::{Rng, SeedableRng, FromEntropy};
+use rand::rngs::StdRng;
+use rand::distributions::StandardNormal;
+
+use super::math::{sigmoid, softmax};
+use super::matrix::Matrix;
+
+// A building block of a network working on a batch of samples, one sample
+// per row. `forward` keeps whatever `backward` needs, while `predict` is the
+// inference path and leaves the layer untouched.
+pub trait Layer {
+    fn predict(&self, inputs: &Matrix) -> Matrix;
+
+    fn forward(&mut self, inputs: &Matrix) -> Matrix;
+
+    // Takes the gradients of the loss with respect to the outputs of the last
+    // forward pass, accumulates parameter gradients and returns the gradients
+    // with respect to its inputs
+    fn backward(&mut self, output_gradients: &Matrix) -> Matrix;
+
+    // Layers that behave differently while training, such as dropout, check
+    // this in `forward`. `predict` always behaves as in evaluation.
+    fn set_training(&mut self, _training: bool) {}
+
+    // Redraws the parameters and any random state, such as dropout masks,
+    // from `rng`, so a seeded generator gives a reproducible layer
+    fn initialize(&mut self, _rng: &mut StdRng) {}
+
+    fn parameters(&self) -> Vec<&Matrix> {
+        return Vec::new();
+    }
+
+    // Accumulated since the last `update`, in the same order as `parameters`
+    fn gradients(&self) -> Vec<&Matrix> {
+        return Vec::new();
+    }
+
+    // Whether each parameter is a weight matrix, one row per output, subject
+    // to weight penalties and max-norm constraints. Biases and normalization
+    // scales and shifts are left alone.
+    fn regularized(&self) -> Vec<bool> {
+        return self.parameters().iter().map(|_| false).collect();
+    }
+
+    fn parameters_and_gradients(&mut self) -> Vec<(&mut Matrix, &mut Matrix)> {
+        return Vec::new();
+    }
+
+    fn set_parameters(&mut self, parameters: Vec<Matrix>) {
+        let mut current = self.parameters_and_gradients();
+        if current.len() != parameters.len() ||
+           current.iter().zip(&parameters).any(|((old, _), new)| old.size() != new.size()) {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        for ((old, _), new) in current.iter_mut().zip(parameters) {
+            **old = new;
+        }
+    }
+
+    // Gradient descent step, after which the gradients are cleared
+    fn update(&mut self, learning_rate: f64) {
+        for (parameter, gradient) in self.parameters_and_gradients() {
+            let mut step = gradient.clone();
+            step.map(|g| g * learning_rate);
+            *parameter = parameter.subtract(&step);
+            gradient.map(|_| 0.0);
+        }
+    }
+}
+
+fn zeros(rows: usize, cols: usize) -> Matrix {
+    let mut result = Matrix::new();
+    result.zero_fill(rows, cols);
+    return result;
+}
+
+// Schemes for drawing the initial weights of a layer from its fan in (number
+// of inputs) and fan out (number of outputs)
+#[derive(Clone)]
+pub enum Initializer {
+    Zeros,
+    // Uniform in +-1 / sqrt(fan_in), the original scheme
+    FanInUniform,
+    // Glorot & Bengio, variance 2 / (fan_in + fan_out), suited to tanh and
+    // sigmoid layers
+    XavierUniform,
+    XavierNormal,
+    // He et al., variance 2 / fan_in, suited to ReLU layers
+    HeUniform,
+    HeNormal,
+    // Variance 1 / fan_in
+    LeCunUniform,
+    LeCunNormal,
+    // Orthonormal rows (or columns, when there are more outputs than inputs)
+    Orthogonal,
+}
+
+impl Initializer {
+    // Returns num_outputs x num_inputs weights, one row per output
+    pub fn sample<R: Rng>(&self, num_outputs: usize, num_inputs: usize, rng: &mut R) -> Matrix {
+        let (fan_in, fan_out) = (num_inputs as f64, num_outputs as f64);
+        let count = num_outputs * num_inputs;
+        // Uniform on [-a, a] has variance a^2 / 3
+        let uniform = |rng: &mut R, variance: f64| -> Vec<f64> {
+            let bound = (3.0 * variance).sqrt();
+            return (0..count).map(|_| rng.gen_range(-bound, bound)).collect();
+        };
+        let normal = |rng: &mut R, variance: f64| -> Vec<f64> {
+            return (0..count).map(|_| variance.sqrt() * rng.sample(StandardNormal)).collect();
+        };
+        let data = match self {
+            Initializer::Zeros => vec![0.0; count],
+            Initializer::FanInUniform => uniform(rng, 1.0 / (3.0 * fan_in)),
+            Initializer::XavierUniform => uniform(rng, 2.0 / (fan_in + fan_out)),
+            Initializer::XavierNormal => normal(rng, 2.0 / (fan_in + fan_out)),
+            Initializer::HeUniform => uniform(rng, 2.0 / fan_in),
+            Initializer::HeNormal => normal(rng, 2.0 / fan_in),
+            Initializer::LeCunUniform => uniform(rng, 1.0 / fan_in),
+            Initializer::LeCunNormal => normal(rng, 1.0 / fan_in),
+            Initializer::Orthogonal => return orthogonal(num_outputs, num_inputs, rng),
+        };
+        return Matrix::from_vec(&data, num_outputs, num_inputs);
+    }
+}
+
+// Orthonormalizes the columns of a tall Gaussian matrix with Gram-Schmidt
+fn orthogonal<R: Rng>(rows: usize, cols: usize, rng: &mut R) -> Matrix {
+    let (long, short) = (rows.max(cols), rows.min(cols));
+    let mut columns: Vec<Vec<f64>> = Vec::with_capacity(short);
+    for _ in 0..short {
+        let mut column: Vec<f64> = (0..long).map(|_| rng.sample(StandardNormal)).collect();
+        for previous in &columns {
+            let dot: f64 = column.iter().zip(previous).map(|(a, b)| a * b).sum();
+            for (x, p) in column.iter_mut().zip(previous) {
+                *x -= dot * p;
+            }
+        }
+        let norm = column.iter().map(|x| x * x).sum::<f64>().sqrt();
+        columns.push(column.iter().map(|x| x / norm).collect());
+    }
+    // Column i of the long x short result, stored row by row
+    let data: Vec<f64> = (0..long).flat_map(|r| columns.iter().map(move |column| column[r])).collect();
+    let result = Matrix::from_vec(&data, long, short);
+    if rows < cols {
+        return result.transpose();
+    }
+    return result;
+}
+
+// Fully connected layer y = x W^T + b, with weights stored one row per output
+pub struct Dense {
+    weights: Matrix,
+    biases: Matrix,
+    weight_gradients: Matrix,
+    bias_gradients: Matrix,
+    use_bias: bool,
+    initializer: Initializer,
+    inputs: Matrix,
+}
+
+impl Dense {
+    pub fn new(num_inputs: usize, num_outputs: usize) -> Dense {
+        if num_inputs == 0 || num_outputs == 0 {
+            panic!("Layer must have at least one input and output!");
+        }
+        let mut dense = Dense {
+            weights: zeros(num_outputs, num_inputs),
+            biases: zeros(1, num_outputs),
+            weight_gradients: zeros(num_outputs, num_inputs),
+            bias_gradients: zeros(1, num_outputs),
+            use_bias: true,
+            initializer: Initializer::FanInUniform,
+            inputs: Matrix::new(),
+        };
+        dense.initialize(&mut StdRng::from_entropy());
+        return dense;
+    }
+
+    // Scheme used the next time the layer is initialized, e.g. by
+    // Sequential::set_seed; the current weights are left as they are
+    pub fn set_initializer(&mut self, initializer: Initializer) {
+        self.initializer = initializer;
+    }
+
+    // Without a bias the biases are left out of the parameters
+    pub fn set_use_bias(&mut self, use_bias: bool) {
+        self.use_bias = use_bias;
+        if !use_bias {
+            self.biases.map(|_| 0.0);
+        }
+    }
+
+    pub fn get_weights(&self) -> &Matrix {
+        return &self.weights;
+    }
+
+    pub fn get_biases(&self) -> &Matrix {
+        return &self.biases;
+    }
+}
+
+impl Layer for Dense {
+    fn predict(&self, inputs: &Matrix) -> Matrix {
+        if inputs.size().1 != self.weights.size().1 {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        return inputs.dot_prod(&self.weights.transpose()).add_row(&self.biases);
+    }
+
+    fn forward(&mut self, inputs: &Matrix) -> Matrix {
+        let outputs = self.predict(inputs);
+        self.inputs = inputs.clone();
+        return outputs;
+    }
+
+    fn backward(&mut self, output_gradients: &Matrix) -> Matrix {
+        self.weight_gradients = self.weight_gradients.add(&output_gradients.transpose().dot_prod(&self.inputs));
+        if self.use_bias {
+            self.bias_gradients = self.bias_gradients.add(&output_gradients.sum_rows());
+        }
+        return output_gradients.dot_prod(&self.weights);
+    }
+
+    // Draws new weights with the layer's initializer and zeroes the biases
+    fn initialize(&mut self, rng: &mut StdRng) {
+        let (num_outputs, num_inputs) = self.weights.size();
+        self.weights = self.initializer.sample(num_outputs, num_inputs, rng);
+        self.biases.map(|_| 0.0);
+    }
+
+    fn parameters(&self) -> Vec<&Matrix> {
+        if self.use_bias {
+            return vec![&self.weights, &self.biases];
+        }
+        return vec![&self.weights];
+    }
+
+    fn gradients(&self) -> Vec<&Matrix> {
+        if self.use_bias {
+            return vec![&self.weight_gradients, &self.bias_gradients];
+        }
+        return vec![&self.weight_gradients];
+    }
+
+    fn regularized(&self) -> Vec<bool> {
+        if self.use_bias {
+            return vec![true, false];
+        }
+        return vec![true];
+    }
+
+    fn parameters_and_gradients(&mut self) -> Vec<(&mut Matrix, &mut Matrix)> {
+        if self.use_bias {
+            return vec![(&mut self.weights, &mut self.weight_gradients), (&mut self.biases, &mut self.bias_gradients)];
+        }
+        return vec![(&mut self.weights, &mut self.weight_gradients)];
+    }
+}
+
+pub enum ActivationFunction {
+    Identity,
+    Sigmoid,
+    Tanh,
+    ReLU,
+    // Slope used for negative inputs
+    LeakyReLU(f64),
+    // Applied across each row, e.g. to turn scores into class probabilities
+    Softmax,
+}
+
+// Element-wise (or for softmax, row-wise) activation without parameters
+pub struct Activation {
+    function: ActivationFunction,
+    inputs: Matrix,
+    outputs: Matrix,
+}
+
+impl Activation {
+    pub fn new(function: ActivationFunction) -> Activation {
+        Activation {
+            function,
+            inputs: Matrix::new(),
+            outputs: Matrix::new(),
+        }
+    }
+}
+
+impl Layer for Activation {
+    fn predict(&self, inputs: &Matrix) -> Matrix {
+        let mut outputs = inputs.clone();
+        match self.function {
+            ActivationFunction::Identity => {},
+            ActivationFunction::Sigmoid => outputs.map(sigmoid),
+            ActivationFunction::Tanh => outputs.map(f64::tanh),
+            ActivationFunction::ReLU => outputs.map(|x| x.max(0.0)),
+            ActivationFunction::LeakyReLU(slope) => outputs.map(|x| if x > 0.0 { x } else { slope * x }),
+            ActivationFunction::Softmax => {
+                let (rows, cols) = inputs.size();
+                let mut data: Vec<f64> = Vec::with_capacity(rows * cols);
+                for r in 0..rows {
+                    data.extend(softmax(&inputs.get_row(r)));
+                }
+                outputs = Matrix::from_vec(&data, rows, cols);
+            },
+        }
+        return outputs;
+    }
+
+    fn forward(&mut self, inputs: &Matrix) -> Matrix {
+        let outputs = self.predict(inputs);
+        self.inputs = inputs.clone();
+        self.outputs = outputs.clone();
+        return outputs;
+    }
+
+    fn backward(&mut self, output_gradients: &Matrix) -> Matrix {
+        if output_gradients.size() != self.outputs.size() {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        let inputs = self.inputs.as_vec();
+        let outputs = self.outputs.as_vec();
+        let mut result = output_gradients.clone();
+        match self.function {
+            ActivationFunction::Identity => {},
+            ActivationFunction::Sigmoid => result.map_with_index(|g, i| g * outputs[i] * (1.0 - outputs[i])),
+            ActivationFunction::Tanh => result.map_with_index(|g, i| g * (1.0 - outputs[i] * outputs[i])),
+            ActivationFunction::ReLU => result.map_with_index(|g, i| if inputs[i] > 0.0 { g } else { 0.0 }),
+            ActivationFunction::LeakyReLU(slope) => result.map_with_index(|g, i| if inputs[i] > 0.0 { g } else { slope * g }),
+            ActivationFunction::Softmax => {
+                // dx = y * (dy - sum(dy * y)) for each row
+                let cols = self.outputs.size().1;
+                let gradients = output_gradients.as_vec();
+                let dots: Vec<f64> = (0..self.outputs.size().0)
+                    .map(|r| (0..cols).map(|c| gradients[r * cols + c] * outputs[r * cols + c]).sum())
+                    .collect();
+                result.map_with_index(|g, i| outputs[i] * (g - dots[i / cols]));
+            },
+        }
+        return result;
+    }
+}
+
+pub enum DropoutVariant {
+    // Kept outputs are scaled by 1 / (1 - rate) while training, so nothing
+    // changes at inference
+    Inverted,
+    // Kept outputs are left as they are while training, and every output is
+    // scaled by (1 - rate) at inference instead
+    Standard,
+}
+
+// Randomly zeroes each output with probability `rate` while training
+pub struct Dropout {
+    rate: f64,
+    variant: DropoutVariant,
+    training: bool,
+    rng: StdRng,
+    // Factor applied to each output in the last forward pass
+    mask: Matrix,
+}
+
+impl Dropout {
+    pub fn new(rate: f64) -> Dropout {
+        if !(0.0..1.0).contains(&rate) {
+            panic!("Dropout rate must be in [0, 1)!");
+        }
+        Dropout {
+            rate,
+            variant: DropoutVariant::Inverted,
+            training: true,
+            rng: StdRng::from_entropy(),
+            mask: Matrix::new(),
+        }
+    }
+
+    pub fn set_variant(&mut self, variant: DropoutVariant) {
+        self.variant = variant;
+    }
+
+    // Makes the sequence of masks reproducible
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    pub fn get_rate(&self) -> f64 {
+        return self.rate;
+    }
+
+    pub fn get_mask(&self) -> &Matrix {
+        return &self.mask;
+    }
+
+    fn inference_scale(&self) -> f64 {
+        return match self.variant {
+            DropoutVariant::Inverted => 1.0,
+            DropoutVariant::Standard => 1.0 - self.rate,
+        };
+    }
+}
+
+impl Layer for Dropout {
+    fn predict(&self, inputs: &Matrix) -> Matrix {
+        let scale = self.inference_scale();
+        let mut outputs = inputs.clone();
+        outputs.map(|x| x * scale);
+        return outputs;
+    }
+
+    fn forward(&mut self, inputs: &Matrix) -> Matrix {
+        let (rows, cols) = inputs.size();
+        let mask = if self.training {
+            let kept = match self.variant {
+                DropoutVariant::Inverted => 1.0 / (1.0 - self.rate),
+                DropoutVariant::Standard => 1.0,
+            };
+            let data: Vec<f64> = (0..(rows * cols))
+                .map(|_| if self.rng.gen::<f64>() < self.rate { 0.0 } else { kept })
+                .collect();
+            Matrix::from_vec(&data, rows, cols)
+        } else {
+            Matrix::from_vec(&vec![self.inference_scale(); rows * cols], rows, cols)
+        };
+        let outputs = inputs.hadamard(&mask);
+        self.mask = mask;
+        return outputs;
+    }
+
+    fn backward(&mut self, output_gradients: &Matrix) -> Matrix {
+        if output_gradients.size() != self.mask.size() {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        return output_gradients.hadamard(&self.mask);
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+
+    // Masks come from a generator of the layer's own, seeded from `rng`
+    fn initialize(&mut self, rng: &mut StdRng) {
+        self.rng = StdRng::seed_from_u64(rng.gen());
+    }
+}
+
+// Normalizes each row to zero mean and unit variance, returning the
+// normalized rows and 1 / std of each row
+fn normalize_rows(inputs: &Matrix, epsilon: f64) -> (Matrix, Vec<f64>) {
+    let (rows, cols) = inputs.size();
+    let mut normalized = zeros(rows, cols);
+    let mut inverse_stds: Vec<f64> = Vec::with_capacity(rows);
+    for r in 0..rows {
+        let row = inputs.get_row(r);
+        let mean = row.iter().sum::<f64>() / cols as f64;
+        let variance = row.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / cols as f64;
+        let inverse_std = 1.0 / (variance + epsilon).sqrt();
+        for (c, x) in row.iter().enumerate() {
+            normalized.set_at_index(r, c, (x - mean) * inverse_std);
+        }
+        inverse_stds.push(inverse_std);
+    }
+    return (normalized, inverse_stds);
+}
+
+// Gradients with respect to the inputs of `normalize_rows`, given the
+// gradients with respect to its outputs:
+//   dx = (n * dx_hat - sum(dx_hat) - x_hat * sum(dx_hat * x_hat)) / (n * std)
+fn normalize_rows_backward(gradients: &Matrix, normalized: &Matrix, inverse_stds: &Vec<f64>) -> Matrix {
+    let (rows, cols) = gradients.size();
+    let mut result = zeros(rows, cols);
+    for (r, inverse_std) in inverse_stds.iter().enumerate() {
+        let normalized = normalized.get_row(r);
+        let gradients = gradients.get_row(r);
+        let sum: f64 = gradients.iter().sum();
+        let dot: f64 = gradients.iter().zip(&normalized).map(|(g, x)| g * x).sum();
+        for (c, (g, x)) in gradients.iter().zip(&normalized).enumerate() {
+            let value = (cols as f64 * g - sum - x * dot) * inverse_std / cols as f64;
+            result.set_at_index(r, c, value);
+        }
+    }
+    return result;
+}
+
+// Multiplies each column by the matching entry of a 1 x cols Matrix
+fn scale_columns(matrix: &Matrix, scales: &Matrix) -> Matrix {
+    let scales = scales.as_vec();
+    let mut result = matrix.clone();
+    result.map_with_index(|x, i| x * scales[i % scales.len()]);
+    return result;
+}
+
+// Normalizes each row (sample) to zero mean and unit variance over its
+// features, then applies a learned scale (gamma) and shift (beta) per feature
+pub struct LayerNorm {
+    size: usize,
+    epsilon: f64,
+    gamma: Matrix,
+    beta: Matrix,
+    gamma_gradients: Matrix,
+    beta_gradients: Matrix,
+    // Normalized inputs and 1 / std of each row from the last forward pass
+    normalized: Matrix,
+    inverse_stds: Vec<f64>,
+}
+
+impl LayerNorm {
+    pub fn new(size: usize) -> LayerNorm {
+        if size == 0 {
+            panic!("Layer size must be at least 1!");
+        }
+        LayerNorm {
+            size,
+            epsilon: 1e-5,
+            gamma: Matrix::from_vec(&vec![1.0; size], 1, size),
+            beta: zeros(1, size),
+            gamma_gradients: zeros(1, size),
+            beta_gradients: zeros(1, size),
+            normalized: Matrix::new(),
+            inverse_stds: Vec::new(),
+        }
+    }
+
+    // Added to the variance to avoid dividing by zero
+    pub fn set_epsilon(&mut self, epsilon: f64) {
+        self.epsilon = epsilon;
+    }
+
+    fn normalize(&self, inputs: &Matrix) -> (Matrix, Vec<f64>) {
+        if inputs.size().1 != self.size {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        return normalize_rows(inputs, self.epsilon);
+    }
+}
+
+impl Layer for LayerNorm {
+    fn predict(&self, inputs: &Matrix) -> Matrix {
+        return scale_columns(&self.normalize(inputs).0, &self.gamma).add_row(&self.beta);
+    }
+
+    fn forward(&mut self, inputs: &Matrix) -> Matrix {
+        let (normalized, inverse_stds) = self.normalize(inputs);
+        let outputs = scale_columns(&normalized, &self.gamma).add_row(&self.beta);
+        self.normalized = normalized;
+        self.inverse_stds = inverse_stds;
+        return outputs;
+    }
+
+    fn backward(&mut self, output_gradients: &Matrix) -> Matrix {
+        let (rows, cols) = output_gradients.size();
+        if self.inverse_stds.len() != rows || cols != self.size {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        self.gamma_gradients = self.gamma_gradients.add(&output_gradients.hadamard(&self.normalized).sum_rows());
+        self.beta_gradients = self.beta_gradients.add(&output_gradients.sum_rows());
+        let gradients = scale_columns(output_gradients, &self.gamma);
+        return normalize_rows_backward(&gradients, &self.normalized, &self.inverse_stds);
+    }
+
+    // Nothing random: back to the identity transform
+    fn initialize(&mut self, _rng: &mut StdRng) {
+        self.gamma.map(|_| 1.0);
+        self.beta.map(|_| 0.0);
+    }
+
+    // Gamma followed by beta, each 1 x size
+    fn parameters(&self) -> Vec<&Matrix> {
+        return vec![&self.gamma, &self.beta];
+    }
+
+    fn gradients(&self) -> Vec<&Matrix> {
+        return vec![&self.gamma_gradients, &self.beta_gradients];
+    }
+
+    fn parameters_and_gradients(&mut self) -> Vec<(&mut Matrix, &mut Matrix)> {
+        return vec![(&mut self.gamma, &mut self.gamma_gradients), (&mut self.beta, &mut self.beta_gradients)];
+    }
+}
+
+// Normalizes each feature (column) over the batch, then applies a learned
+// scale (gamma) and shift (beta). While training the batch statistics are
+// used and folded into running averages, which replace them at inference
+// and whenever the layer is not in training mode.
+pub struct BatchNorm {
+    size: usize,
+    epsilon: f64,
+    momentum: f64,
+    training: bool,
+    gamma: Matrix,
+    beta: Matrix,
+    gamma_gradients: Matrix,
+    beta_gradients: Matrix,
+    running_mean: Matrix,
+    running_variance: Matrix,
+    // Normalized inputs, 1 / std of each feature and whether batch statistics
+    // were used in the last forward pass
+    normalized: Matrix,
+    inverse_stds: Vec<f64>,
+    batch_statistics: bool,
+}
+
+impl BatchNorm {
+    pub fn new(size: usize) -> BatchNorm {
+        if size == 0 {
+            panic!("Layer size must be at least 1!");
+        }
+        BatchNorm {
+            size,
+            epsilon: 1e-5,
+            momentum: 0.9,
+            training: true,
+            gamma: Matrix::from_vec(&vec![1.0; size], 1, size),
+            beta: zeros(1, size),
+            gamma_gradients: zeros(1, size),
+            beta_gradients: zeros(1, size),
+            running_mean: zeros(1, size),
+            running_variance: Matrix::from_vec(&vec![1.0; size], 1, size),
+            normalized: Matrix::new(),
+            inverse_stds: Vec::new(),
+            batch_statistics: false,
+        }
+    }
+
+    // Added to the variance to avoid dividing by zero
+    pub fn set_epsilon(&mut self, epsilon: f64) {
+        self.epsilon = epsilon;
+    }
+
+    // Weight of the old running averages in each update:
+    //   running = momentum * running + (1 - momentum) * batch
+    pub fn set_momentum(&mut self, momentum: f64) {
+        if !(0.0..=1.0).contains(&momentum) {
+            panic!("Momentum must be in [0, 1]!");
+        }
+        self.momentum = momentum;
+    }
+
+    pub fn get_running_mean(&self) -> &Matrix {
+        return &self.running_mean;
+    }
+
+    pub fn get_running_variance(&self) -> &Matrix {
+        return &self.running_variance;
+    }
+
+    pub fn set_running_statistics(&mut self, mean: Matrix, variance: Matrix) {
+        if mean.size() != (1, self.size) || variance.size() != (1, self.size) {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        self.running_mean = mean;
+        self.running_variance = variance;
+    }
+
+    // Normalizes with the running averages, returning 1 / std of each feature
+    fn normalize_running(&self, inputs: &Matrix) -> (Matrix, Vec<f64>) {
+        if inputs.size().1 != self.size {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        let means = self.running_mean.as_vec();
+        let inverse_stds: Vec<f64> = self.running_variance.as_vec().iter().map(|v| 1.0 / (v + self.epsilon).sqrt()).collect();
+        let mut normalized = inputs.clone();
+        normalized.map_with_index(|x, i| (x - means[i % self.size]) * inverse_stds[i % self.size]);
+        return (normalized, inverse_stds);
+    }
+}
+
+impl Layer for BatchNorm {
+    fn predict(&self, inputs: &Matrix) -> Matrix {
+        return scale_columns(&self.normalize_running(inputs).0, &self.gamma).add_row(&self.beta);
+    }
+
+    fn forward(&mut self, inputs: &Matrix) -> Matrix {
+        let (rows, cols) = inputs.size();
+        if cols != self.size {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        self.batch_statistics = self.training;
+        if self.training {
+            if rows == 0 {
+                panic!("Batch must not be empty!");
+            }
+            let transposed = inputs.transpose();
+            let means: Vec<f64> = (0..cols).map(|c| transposed.get_row(c).iter().sum::<f64>() / rows as f64).collect();
+            let variances: Vec<f64> = (0..cols)
+                .map(|c| transposed.get_row(c).iter().map(|x| (x - means[c]).powi(2)).sum::<f64>() / rows as f64)
+                .collect();
+            let momentum = self.momentum;
+            self.running_mean.map_with_index(|m, i| momentum * m + (1.0 - momentum) * means[i]);
+            self.running_variance.map_with_index(|v, i| momentum * v + (1.0 - momentum) * variances[i]);
+            let (normalized, inverse_stds) = normalize_rows(&transposed, self.epsilon);
+            self.normalized = normalized.transpose();
+            self.inverse_stds = inverse_stds;
+        } else {
+            let (normalized, inverse_stds) = self.normalize_running(inputs);
+            self.normalized = normalized;
+            self.inverse_stds = inverse_stds;
+        }
+        return scale_columns(&self.normalized, &self.gamma).add_row(&self.beta);
+    }
+
+    fn backward(&mut self, output_gradients: &Matrix) -> Matrix {
+        if output_gradients.size() != self.normalized.size() {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        self.gamma_gradients = self.gamma_gradients.add(&output_gradients.hadamard(&self.normalized).sum_rows());
+        self.beta_gradients = self.beta_gradients.add(&output_gradients.sum_rows());
+        let gradients = scale_columns(output_gradients, &self.gamma);
+        if self.batch_statistics {
+            // Each feature was normalized like a row of the transposed batch
+            return normalize_rows_backward(&gradients.transpose(), &self.normalized.transpose(), &self.inverse_stds).transpose();
+        }
+        // The running averages are constants, leaving a per feature scaling
+        let inverse_stds = Matrix::from_vec(&self.inverse_stds, 1, self.size);
+        return scale_columns(&gradients, &inverse_stds);
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+    }
+
+    // Nothing random: back to the identity transform and empty running averages
+    fn initialize(&mut self, _rng: &mut StdRng) {
+        self.gamma.map(|_| 1.0);
+        self.beta.map(|_| 0.0);
+        self.running_mean.map(|_| 0.0);
+        self.running_variance.map(|_| 1.0);
+    }
+
+    // Gamma followed by beta, each 1 x size
+    fn parameters(&self) -> Vec<&Matrix> {
+        return vec![&self.gamma, &self.beta];
+    }
+
+    fn gradients(&self) -> Vec<&Matrix> {
+        return vec![&self.gamma_gradients, &self.beta_gradients];
+    }
+
+    fn parameters_and_gradients(&mut self) -> Vec<(&mut Matrix, &mut Matrix)> {
+        return vec![(&mut self.gamma, &mut self.gamma_gradients), (&mut self.beta, &mut self.beta_gradients)];
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_layers.rs"]
+pub(crate) mod test;
@@ -1,7 +1,53 @@
+#![allow(
+    clippy::needless_return,
+    clippy::new_without_default,
+    clippy::ptr_arg,
+)]
+
 extern crate rand;
 
 pub mod math;
 
 pub mod matrix;
 
+pub mod loss;
+
+pub mod layers;
+
+pub mod logistic_regression;
+
+pub mod k_nearest_neighbors;
+
+pub mod k_means;
+
+pub mod gaussian_mixture;
+
+pub mod dbscan;
+
+pub mod hierarchical_clustering;
+
+pub mod hidden_markov_model;
+
+pub mod decision_tree;
+
+pub mod random_forest;
+
+pub mod gradient_boosting;
+
+pub mod naive_bayes;
+
+pub mod svm;
+
+pub mod pca;
+
+pub mod rl;
+
+pub mod convolution;
+
+pub mod recurrent;
+
+pub mod transformer;
+
+pub mod perceptron;
+
 pub mod neural_network;
\ No newline at end of file
@@ -0,0 +1,327 @@
+use super::matrix::Matrix;
+use super::math::{sigmoid, softmax, argmax};
+
+pub enum Solver {
+    GradientDescent,
+    Newton,
+}
+
+pub enum Penalty {
+    None,
+    L1(f64),
+    L2(f64),
+    // (strength, l1_ratio)
+    ElasticNet(f64, f64),
+}
+
+pub enum ClassWeight {
+    Uniform,
+    // Weights each class by n_samples / (n_classes * class_count)
+    Balanced,
+    Custom(Vec<f64>),
+}
+
+pub struct LogisticRegression {
+    num_features: usize,
+    num_classes: usize,
+    // (num_features + 1) x 1 for binary, (num_features + 1) x num_classes
+    // for multinomial; the first row holds the intercepts
+    weights: Matrix,
+    solver: Solver,
+    penalty: Penalty,
+    class_weight: ClassWeight,
+    learning_rate: f64,
+    max_iterations: usize,
+    tolerance: f64,
+}
+
+impl LogisticRegression {
+    pub fn new(num_features: usize, num_classes: usize) -> LogisticRegression {
+        if num_classes < 2 {
+            panic!("At least two classes are required!");
+        }
+        let mut weights = Matrix::new();
+        weights.zero_fill(num_features + 1, LogisticRegression::output_count(num_classes));
+        LogisticRegression {
+            num_features,
+            num_classes,
+            weights,
+            solver: Solver::GradientDescent,
+            penalty: Penalty::None,
+            class_weight: ClassWeight::Uniform,
+            learning_rate: 0.1,
+            max_iterations: 1000,
+            tolerance: 1e-6,
+        }
+    }
+
+    fn output_count(num_classes: usize) -> usize {
+        if num_classes == 2 {
+            return 1;
+        }
+        return num_classes;
+    }
+
+    pub fn set_solver(&mut self, solver: Solver) {
+        self.solver = solver;
+    }
+
+    pub fn set_penalty(&mut self, penalty: Penalty) {
+        self.penalty = penalty;
+    }
+
+    pub fn set_class_weight(&mut self, class_weight: ClassWeight) {
+        self.class_weight = class_weight;
+    }
+
+    pub fn set_learning_rate(&mut self, learning_rate: f64) {
+        self.learning_rate = learning_rate;
+    }
+
+    pub fn set_max_iterations(&mut self, max_iterations: usize) {
+        self.max_iterations = max_iterations;
+    }
+
+    pub fn set_tolerance(&mut self, tolerance: f64) {
+        self.tolerance = tolerance;
+    }
+
+    pub fn get_weights(&self) -> &Matrix {
+        return &self.weights;
+    }
+
+    pub fn set_weights(&mut self, new_weights: &Matrix) {
+        let expected = (self.num_features + 1, LogisticRegression::output_count(self.num_classes));
+        if new_weights.size() != expected {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        self.weights = new_weights.clone();
+    }
+
+    fn with_bias_column(&self, inputs: &Matrix) -> Matrix {
+        let (rows, cols) = inputs.size();
+        if cols != self.num_features {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        let mut result = Matrix::new();
+        result.zero_fill(rows, cols + 1);
+        for i in 0..rows {
+            result.set_at_index(i, 0, 1.0);
+            for j in 0..cols {
+                result.set_at_index(i, j + 1, inputs.get_at_index(i, j));
+            }
+        }
+        return result;
+    }
+
+    // Probabilities for each row of an input already carrying the bias column
+    fn probabilities(&self, augmented: &Matrix) -> Matrix {
+        let scores = augmented.dot_prod(&self.weights);
+        let rows = scores.size().0;
+        let mut data: Vec<f64> = Vec::new();
+        for i in 0..rows {
+            if self.num_classes == 2 {
+                let p = sigmoid(scores.get_at_index(i, 0));
+                data.push(1.0 - p);
+                data.push(p);
+            } else {
+                data.extend(softmax(&scores.get_row(i)));
+            }
+        }
+        return Matrix::from_vec(&data, rows, self.num_classes);
+    }
+
+    fn sample_weights(&self, labels: &Vec<usize>) -> Vec<f64> {
+        let class_weights: Vec<f64> = match &self.class_weight {
+            ClassWeight::Uniform => vec![1.0; self.num_classes],
+            ClassWeight::Balanced => {
+                let mut counts = vec![0usize; self.num_classes];
+                for &label in labels {
+                    counts[label] += 1;
+                }
+                counts.iter().map(|&count| {
+                    if count == 0 {
+                        return 0.0;
+                    }
+                    return labels.len() as f64 / (self.num_classes as f64 * count as f64);
+                }).collect()
+            },
+            ClassWeight::Custom(weights) => {
+                if weights.len() != self.num_classes {
+                    panic!("Class weights must match the number of classes!");
+                }
+                weights.clone()
+            },
+        };
+        return labels.iter().map(|&label| class_weights[label]).collect();
+    }
+
+    fn penalty_strengths(&self) -> (f64, f64) {
+        match self.penalty {
+            Penalty::None => (0.0, 0.0),
+            Penalty::L1(strength) => (strength, 0.0),
+            Penalty::L2(strength) => (0.0, strength),
+            Penalty::ElasticNet(strength, l1_ratio) => (strength * l1_ratio, strength * (1.0 - l1_ratio)),
+        }
+    }
+
+    // Weighted mean of the data term gradient, one column per output
+    fn gradient(&self, augmented: &Matrix, labels: &Vec<usize>, sample_weights: &Vec<f64>) -> Matrix {
+        let probabilities = self.probabilities(augmented);
+        let outputs = self.weights.size().1;
+        let total_weight: f64 = sample_weights.iter().sum();
+
+        let mut residuals = Matrix::new();
+        residuals.zero_fill(labels.len(), outputs);
+        for i in 0..labels.len() {
+            for c in 0..outputs {
+                // Binary models only carry the positive class column
+                let class = if outputs == 1 { 1 } else { c };
+                let target = if labels[i] == class { 1.0 } else { 0.0 };
+                let residual = probabilities.get_at_index(i, class) - target;
+                residuals.set_at_index(i, c, sample_weights[i] * residual / total_weight);
+            }
+        }
+        return augmented.transpose().dot_prod(&residuals);
+    }
+
+    #[allow(clippy::needless_range_loop)]
+    fn hessian(&self, augmented: &Matrix, sample_weights: &Vec<f64>) -> Matrix {
+        let probabilities = self.probabilities(augmented);
+        let (rows, dims) = augmented.size();
+        let outputs = self.weights.size().1;
+        let size = dims * outputs;
+        let total_weight: f64 = sample_weights.iter().sum();
+
+        let mut result = Matrix::new();
+        result.zero_fill(size, size);
+        for i in 0..rows {
+            let x = augmented.get_row(i);
+            for c in 0..outputs {
+                for d in 0..outputs {
+                    let curvature = if outputs == 1 {
+                        let p = probabilities.get_at_index(i, 1);
+                        p * (1.0 - p)
+                    } else {
+                        let p_c = probabilities.get_at_index(i, c);
+                        let p_d = probabilities.get_at_index(i, d);
+                        if c == d { p_c * (1.0 - p_d) } else { -p_c * p_d }
+                    };
+                    let scale = sample_weights[i] * curvature / total_weight;
+                    for a in 0..dims {
+                        for b in 0..dims {
+                            let row = a * outputs + c;
+                            let col = b * outputs + d;
+                            let value = result.get_at_index(row, col) + scale * x[a] * x[b];
+                            result.set_at_index(row, col, value);
+                        }
+                    }
+                }
+            }
+        }
+        return result;
+    }
+
+    pub fn fit(&mut self, inputs: &Matrix, labels: &Vec<usize>) {
+        if inputs.size().0 != labels.len() {
+            panic!("Inputs and labels must have the same number of rows!");
+        }
+        if labels.iter().any(|&label| label >= self.num_classes) {
+            panic!("Label out of range!");
+        }
+        let augmented = self.with_bias_column(inputs);
+        let sample_weights = self.sample_weights(labels);
+        let (l1, l2) = self.penalty_strengths();
+        if let Solver::Newton = self.solver {
+            if l1 > 0.0 {
+                panic!("Newton's method does not support L1 penalties!");
+            }
+        }
+
+        let (dims, outputs) = self.weights.size();
+        for _ in 0..self.max_iterations {
+            let mut gradient = self.gradient(&augmented, labels, &sample_weights);
+            let current = self.weights.as_vec();
+            // Intercepts (row 0) are never penalized
+            gradient.map_with_index(|g, i| {
+                if i < outputs {
+                    return g;
+                }
+                return g + l2 * current[i];
+            });
+
+            let previous = self.weights.clone();
+            match self.solver {
+                Solver::GradientDescent => {
+                    let learning_rate = self.learning_rate;
+                    let mut step = gradient.clone();
+                    step.map(|g| learning_rate * g);
+                    self.weights = self.weights.subtract(&step);
+                    // Proximal step for the L1 term (soft thresholding)
+                    let threshold = learning_rate * l1;
+                    self.weights.map_with_index(|w, i| {
+                        if i < outputs || threshold == 0.0 {
+                            return w;
+                        }
+                        return w.signum() * (w.abs() - threshold).max(0.0);
+                    });
+                },
+                Solver::Newton => {
+                    let mut hessian = self.hessian(&augmented, &sample_weights);
+                    // The L2 term, plus a small ridge to keep the softmax Hessian invertible
+                    let size = dims * outputs;
+                    for i in 0..size {
+                        let ridge = if i < outputs { 1e-8 } else { l2 + 1e-8 };
+                        hessian.set_at_index(i, i, hessian.get_at_index(i, i) + ridge);
+                    }
+                    let gradient = Matrix::from_vec(&gradient.as_vec(), size, 1);
+                    let step = hessian.inverse().dot_prod(&gradient);
+                    let step = Matrix::from_vec(&step.as_vec(), dims, outputs);
+                    self.weights = self.weights.subtract(&step);
+                },
+            }
+
+            let change = self.weights.subtract(&previous).as_vec()
+                .iter().fold(0.0f64, |max, x| max.max(x.abs()));
+            if change < self.tolerance {
+                break;
+            }
+        }
+    }
+
+    // Weighted mean cross-entropy including the penalty term
+    pub fn loss(&self, inputs: &Matrix, labels: &Vec<usize>) -> f64 {
+        let augmented = self.with_bias_column(inputs);
+        let probabilities = self.probabilities(&augmented);
+        let sample_weights = self.sample_weights(labels);
+        let total_weight: f64 = sample_weights.iter().sum();
+
+        let mut loss = 0.0;
+        for i in 0..labels.len() {
+            let p = probabilities.get_at_index(i, labels[i]).max(1e-15);
+            loss -= sample_weights[i] * p.ln();
+        }
+        loss /= total_weight;
+
+        let (l1, l2) = self.penalty_strengths();
+        let outputs = self.weights.size().1;
+        for w in self.weights.as_vec().iter().skip(outputs) {
+            loss += l1 * w.abs() + 0.5 * l2 * w * w;
+        }
+        return loss;
+    }
+
+    pub fn predict_proba(&self, inputs: &Matrix) -> Matrix {
+        let augmented = self.with_bias_column(inputs);
+        return self.probabilities(&augmented);
+    }
+
+    pub fn predict(&self, inputs: &Matrix) -> Vec<usize> {
+        let probabilities = self.predict_proba(inputs);
+        return (0..probabilities.size().0).map(|i| argmax(&probabilities.get_row(i))).collect();
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_logistic_regression.rs"]
+mod test;
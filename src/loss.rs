@@ -0,0 +1,62 @@
+use super::math::{softmax, log_sum_exp};
+use super::matrix::Matrix;
+
+// Losses over a batch of outputs, one sample per row, averaged over the rows
+pub enum Loss {
+    // Half the squared error summed over the outputs of a sample
+    MeanSquaredError,
+    // Softmax cross-entropy; outputs are raw scores (logits) and targets are
+    // one-hot rows or probability distributions
+    CrossEntropy,
+}
+
+fn check_sizes(outputs: &Matrix, targets: &Matrix) {
+    if outputs.size() != targets.size() {
+        panic!("Incompatible Matrix Dimensions!");
+    }
+    if outputs.size().0 == 0 {
+        panic!("Loss needs at least one sample!");
+    }
+}
+
+impl Loss {
+    pub fn compute(&self, outputs: &Matrix, targets: &Matrix) -> f64 {
+        check_sizes(outputs, targets);
+        let (rows, _) = outputs.size();
+        let mut total = 0.0;
+        for r in 0..rows {
+            let output = outputs.get_row(r);
+            let target = targets.get_row(r);
+            total += match self {
+                Loss::MeanSquaredError => {
+                    0.5 * output.iter().zip(&target).map(|(o, t)| (o - t).powi(2)).sum::<f64>()
+                },
+                Loss::CrossEntropy => {
+                    let log_norm = log_sum_exp(&output);
+                    -output.iter().zip(&target).map(|(o, t)| t * (o - log_norm)).sum::<f64>()
+                },
+            };
+        }
+        return total / rows as f64;
+    }
+
+    // Gradient of `compute` with respect to the outputs
+    pub fn gradient(&self, outputs: &Matrix, targets: &Matrix) -> Matrix {
+        check_sizes(outputs, targets);
+        let (rows, cols) = outputs.size();
+        let mut data: Vec<f64> = Vec::with_capacity(rows * cols);
+        for r in 0..rows {
+            let target = targets.get_row(r);
+            let predicted = match self {
+                Loss::MeanSquaredError => outputs.get_row(r),
+                Loss::CrossEntropy => softmax(&outputs.get_row(r)),
+            };
+            data.extend(predicted.iter().zip(&target).map(|(p, t)| (p - t) / rows as f64));
+        }
+        return Matrix::from_vec(&data, rows, cols);
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_loss.rs"]
+mod test;
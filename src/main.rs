@@ -1,3 +1,5 @@
+#![allow(clippy::needless_return)]
+
 pub mod examples;
 
 use std::io;
@@ -9,6 +11,12 @@ fn main() {
         println!("Machine Learning Examples: ");
         println!("  0) Quit");
         println!("  1) Learning XOR Gate with Neural Network");
+        println!("  2) Perceptron vs Neural Network on XOR Gate");
+        println!("  3) Q-Learning and SARSA on Grid Worlds");
+        println!("  4) Deep Q-Network on Cart Pole");
+        println!("  5) Small CNN on Images of Shapes");
+        println!("  6) RNN, LSTM and GRU on Binary Addition");
+        println!("  7) Tiny Transformer Language Model");
         
         let mut stdout = io::stdout();
         write!(stdout, "\nEnter option number: ").unwrap();
@@ -23,6 +31,12 @@ fn main() {
         match option {
             0 => break,
             1 => examples::neural_network::learing_xor_gate(),
+            2 => examples::perceptron::perceptron_vs_neural_network_on_xor(),
+            3 => examples::reinforcement_learning::q_learning_and_sarsa(),
+            4 => examples::reinforcement_learning::dqn_on_cart_pole(),
+            5 => examples::convolution::small_cnn_on_shapes(),
+            6 => examples::recurrent::recurrent_networks_on_binary_addition(),
+            7 => examples::transformer::tiny_language_model(),
             _ => {
                 println!("Invalid option!\n");
                 continue;
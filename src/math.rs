@@ -2,6 +2,39 @@ pub fn sigmoid(x: f64) -> f64 {
     return 1.0 / (1.0 + (-x).exp());
 } 
 
+pub fn softmax(inputs: &Vec<f64>) -> Vec<f64> {
+    // Shift by the max so large inputs do not overflow exp()
+    let max = inputs.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let exps: Vec<f64> = inputs.iter().map(|x| (x - max).exp()).collect();
+    let sum: f64 = exps.iter().sum();
+    return exps.iter().map(|x| x / sum).collect();
+}
+
+// ln(sum(exp(x))) without overflowing or underflowing exp()
+pub fn log_sum_exp(inputs: &Vec<f64>) -> f64 {
+    let max = inputs.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    if max == f64::NEG_INFINITY {
+        return f64::NEG_INFINITY;
+    }
+    let sum: f64 = inputs.iter().map(|x| (x - max).exp()).sum();
+    return max + sum.ln();
+}
+
+pub fn euclidean(a: &Vec<f64>, b: &Vec<f64>) -> f64 {
+    return a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum::<f64>().sqrt();
+}
+
+// Index of the largest value, the first one on ties
+pub fn argmax(values: &Vec<f64>) -> usize {
+    let mut best = 0;
+    for (i, value) in values.iter().enumerate() {
+        if *value > values[best] {
+            best = i;
+        }
+    }
+    return best;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -20,4 +53,41 @@ mod tests {
             assert_eq!(actual, expected[i]);
         }
     }
+
+    #[test]
+    fn test_math_softmax() {
+        let actual = softmax(&vec![1.0, 2.0, 3.0]);
+        let expected = ["0.0900305732", "0.2447284711", "0.6652409558"];
+
+        for i in 0..actual.len() {
+            assert_eq!(format!("{:.*}", 10, actual[i]), expected[i]);
+        }
+
+        let actual = softmax(&vec![1000.0, 1000.0]);
+        assert_eq!(actual, vec![0.5, 0.5]);
+    }
+
+    #[test]
+    fn test_math_log_sum_exp() {
+        let actual = log_sum_exp(&vec![1.0, 2.0, 3.0]);
+        assert_eq!(format!("{:.*}", 10, actual), "3.4076059644");
+
+        let actual = log_sum_exp(&vec![-1000.0, -1000.0]);
+        assert_eq!(format!("{:.*}", 10, actual), "-999.3068528194");
+
+        assert_eq!(log_sum_exp(&vec![f64::NEG_INFINITY]), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_math_euclidean() {
+        assert_eq!(euclidean(&vec![1.0, 2.0], &vec![4.0, 6.0]), 5.0);
+        assert_eq!(euclidean(&vec![3.0], &vec![3.0]), 0.0);
+    }
+
+    #[test]
+    fn test_math_argmax() {
+        assert_eq!(argmax(&vec![0.1, 0.7, 0.2]), 1);
+        assert_eq!(argmax(&vec![3.0, -1.0, 3.0]), 0);
+        assert_eq!(argmax(&vec![-2.0]), 0);
+    }
 }
@@ -22,6 +22,7 @@ impl Matrix {
         }    
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(input: &str) -> Matrix {
         let input = input.replace(" ", "");
         let temp = input.split("],[").collect::<Vec<&str>>();
@@ -85,34 +86,68 @@ impl Matrix {
         (self.rows_count, self.cols_count)
     }
 
+    #[allow(clippy::inherent_to_string)]
     pub fn to_string(&self) -> String {
         return self.to_string_fmt(0);
     }
 
     pub fn to_string_fmt(&self, decimal_places: usize) -> String {
         let mut result = String::new();
-        result.push_str("[");
+        result.push('[');
         for (i, num) in self.data.iter().enumerate() {
             if i % self.cols_count == 0 {
-                result.push_str("[");
+                result.push('[');
             }
             result.push_str(&format!("{:.*}", decimal_places, num));
             if (i + 1) % self.cols_count == 0 {
-                result.push_str("]");
+                result.push(']');
                 if i < self.data.len() - 1 {
-                    result.push_str(",");
+                    result.push(',');
                 }
             } else {
-                result.push_str(",");
+                result.push(',');
             }
         }
-        result.push_str("]");
+        result.push(']');
         return result;
     }
 
     pub fn as_vec(&self) -> Vec<f64> {
         return self.data.clone();
     }
+
+    pub fn identity(size: usize) -> Matrix {
+        let mut result = Matrix::new();
+        result.zero_fill(size, size);
+        for i in 0..size {
+            result.data[i * size + i] = 1.0;
+        }
+        return result;
+    }
+
+    pub fn get_row(&self, row: usize) -> Vec<f64> {
+        if row > self.rows_count - 1 {
+            panic!("Index out of bound!");
+        }
+        let start = row * self.cols_count;
+        return self.data[start..start + self.cols_count].to_vec();
+    }
+
+    pub fn get_rows(&self) -> Vec<Vec<f64>> {
+        return (0..self.rows_count).map(|row| self.get_row(row)).collect();
+    }
+
+    pub fn from_rows(rows: &Vec<Vec<f64>>) -> Matrix {
+        let cols_count = if rows.is_empty() { 0 } else { rows[0].len() };
+        if rows.iter().any(|row| row.len() != cols_count) {
+            panic!("Incompatible Vector Dimensions!");
+        }
+        Matrix {
+            data: rows.iter().flat_map(|row| row.clone()).collect(),
+            rows_count: rows.len(),
+            cols_count,
+        }
+    }
 }
 
 // Advanced Operations
@@ -143,6 +178,73 @@ impl Matrix {
         return result;
     }
 
+    // Element-wise (Hadamard) product
+    pub fn hadamard(&self, other: &Matrix) -> Matrix {
+        if (self.rows_count != other.rows_count) ||
+           (self.cols_count != other.cols_count) {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        let mut result = Matrix::new();
+        result.zero_fill(self.rows_count, self.cols_count);
+        for i in 0..self.data.len() {
+            result.data[i] = self.data[i] * other.data[i];
+        }
+        return result;
+    }
+
+    // Copy of `count` columns starting at `start`
+    pub fn get_columns(&self, start: usize, count: usize) -> Matrix {
+        if start + count > self.cols_count {
+            panic!("Index out of bound!");
+        }
+        let mut result = Matrix::new();
+        result.zero_fill(self.rows_count, count);
+        for i in 0..self.rows_count {
+            for j in 0..count {
+                result.data[i * count + j] = self.data[i * self.cols_count + start + j];
+            }
+        }
+        return result;
+    }
+
+    // Matrices with the same number of rows placed side by side
+    pub fn concat_columns(parts: &Vec<Matrix>) -> Matrix {
+        if parts.is_empty() || parts.iter().any(|part| part.rows_count != parts[0].rows_count) {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        let mut result = Matrix::new();
+        result.rows_count = parts[0].rows_count;
+        result.cols_count = parts.iter().map(|part| part.cols_count).sum();
+        for i in 0..result.rows_count {
+            for part in parts {
+                result.data.extend(&part.data[i * part.cols_count..(i + 1) * part.cols_count]);
+            }
+        }
+        return result;
+    }
+
+    // Adds a 1 x cols row to every row
+    pub fn add_row(&self, row: &Matrix) -> Matrix {
+        if row.rows_count != 1 || row.cols_count != self.cols_count {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        let mut result = self.clone();
+        for i in 0..result.data.len() {
+            result.data[i] += row.data[i % self.cols_count];
+        }
+        return result;
+    }
+
+    // 1 x cols row of column sums
+    pub fn sum_rows(&self) -> Matrix {
+        let mut result = Matrix::new();
+        result.zero_fill(1, self.cols_count);
+        for i in 0..self.data.len() {
+            result.data[i % self.cols_count] += self.data[i];
+        }
+        return result;
+    }
+
     pub fn dot_prod(&self, other: &Matrix) -> Matrix {
         if self.cols_count != other.rows_count {
             panic!("Incompatible Matrix Dimensions!");
@@ -175,11 +277,150 @@ impl Matrix {
         return result;
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn clone(&self) -> Matrix {
         let result = Matrix::from_vec(&self.data, self.rows_count, self.cols_count);
         return result;
     }
 
+    // Gauss-Jordan elimination with partial pivoting
+    pub fn inverse(&self) -> Matrix {
+        if self.rows_count != self.cols_count {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        let size = self.rows_count;
+        let mut left = self.clone();
+        let mut result = Matrix::identity(size);
+        for col in 0..size {
+            let mut pivot = col;
+            for row in (col + 1)..size {
+                if left.get_at_index(row, col).abs() > left.get_at_index(pivot, col).abs() {
+                    pivot = row;
+                }
+            }
+            if left.get_at_index(pivot, col).abs() < 1e-12 {
+                panic!("Matrix is singular!");
+            }
+            for k in 0..size {
+                left.data.swap(col * size + k, pivot * size + k);
+                result.data.swap(col * size + k, pivot * size + k);
+            }
+            let pivot_value = left.get_at_index(col, col);
+            for k in 0..size {
+                left.data[col * size + k] /= pivot_value;
+                result.data[col * size + k] /= pivot_value;
+            }
+            for row in 0..size {
+                if row == col {
+                    continue;
+                }
+                let factor = left.get_at_index(row, col);
+                if factor == 0.0 {
+                    continue;
+                }
+                for k in 0..size {
+                    left.data[row * size + k] -= factor * left.data[col * size + k];
+                    result.data[row * size + k] -= factor * result.data[col * size + k];
+                }
+            }
+        }
+        return result;
+    }
+
+    // Eigen decomposition of a symmetric matrix by cyclic Jacobi rotations.
+    // Returns the eigenvalues in descending order and the matching unit
+    // eigenvectors as the columns of a matrix.
+    pub fn symmetric_eigen(&self) -> (Vec<f64>, Matrix) {
+        if self.rows_count != self.cols_count {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        let size = self.rows_count;
+        let mut a = self.clone();
+        let mut v = Matrix::identity(size);
+        for _ in 0..100 {
+            let mut off_diagonal = 0.0;
+            for p in 0..size {
+                for q in (p + 1)..size {
+                    off_diagonal += a.get_at_index(p, q) * a.get_at_index(p, q);
+                }
+            }
+            if off_diagonal < 1e-22 {
+                break;
+            }
+            for p in 0..size {
+                for q in (p + 1)..size {
+                    let a_pq = a.get_at_index(p, q);
+                    if a_pq.abs() < 1e-300 {
+                        continue;
+                    }
+                    let theta = (a.get_at_index(q, q) - a.get_at_index(p, p)) / (2.0 * a_pq);
+                    let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                    let t = if theta == 0.0 { 1.0 } else { t };
+                    let c = 1.0 / (t * t + 1.0).sqrt();
+                    let s = t * c;
+                    for k in 0..size {
+                        let a_kp = a.get_at_index(k, p);
+                        let a_kq = a.get_at_index(k, q);
+                        a.set_at_index(k, p, c * a_kp - s * a_kq);
+                        a.set_at_index(k, q, s * a_kp + c * a_kq);
+                    }
+                    for k in 0..size {
+                        let a_pk = a.get_at_index(p, k);
+                        let a_qk = a.get_at_index(q, k);
+                        a.set_at_index(p, k, c * a_pk - s * a_qk);
+                        a.set_at_index(q, k, s * a_pk + c * a_qk);
+                    }
+                    for k in 0..size {
+                        let v_kp = v.get_at_index(k, p);
+                        let v_kq = v.get_at_index(k, q);
+                        v.set_at_index(k, p, c * v_kp - s * v_kq);
+                        v.set_at_index(k, q, s * v_kp + c * v_kq);
+                    }
+                }
+            }
+        }
+
+        let mut order: Vec<usize> = (0..size).collect();
+        order.sort_by(|&i, &j| a.get_at_index(j, j).partial_cmp(&a.get_at_index(i, i)).unwrap());
+        let values: Vec<f64> = order.iter().map(|&i| a.get_at_index(i, i)).collect();
+        let mut vectors = Matrix::new();
+        vectors.zero_fill(size, size);
+        for (col, &i) in order.iter().enumerate() {
+            for k in 0..size {
+                vectors.set_at_index(k, col, v.get_at_index(k, i));
+            }
+        }
+        return (values, vectors);
+    }
+
+    // Lower triangular L with L * L^T equal to this symmetric positive
+    // definite matrix
+    pub fn cholesky(&self) -> Matrix {
+        if self.rows_count != self.cols_count {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        let size = self.rows_count;
+        let mut result = Matrix::new();
+        result.zero_fill(size, size);
+        for i in 0..size {
+            for j in 0..(i + 1) {
+                let mut sum = self.get_at_index(i, j);
+                for k in 0..j {
+                    sum -= result.get_at_index(i, k) * result.get_at_index(j, k);
+                }
+                if i == j {
+                    if sum <= 0.0 {
+                        panic!("Matrix is not positive definite!");
+                    }
+                    result.set_at_index(i, i, sum.sqrt());
+                } else {
+                    result.set_at_index(i, j, sum / result.get_at_index(j, j));
+                }
+            }
+        }
+        return result;
+    }
+
     pub fn map<F>(&mut self, func: F) 
         where F: Fn(f64) -> f64 {
         for i in 0..self.data.len() {
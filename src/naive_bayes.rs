@@ -0,0 +1,352 @@
+use std::f64::consts::PI;
+
+use super::matrix::Matrix;
+use super::math::{log_sum_exp, argmax};
+
+fn check_batch(inputs: &Matrix, labels: &Vec<usize>, num_classes: usize, num_features: usize) {
+    if inputs.size().0 != labels.len() {
+        panic!("Inputs and labels must have the same number of rows!");
+    }
+    if inputs.size().1 != num_features {
+        panic!("Incompatible Matrix Dimensions!");
+    }
+    if labels.iter().any(|&label| label >= num_classes) {
+        panic!("Label out of range!");
+    }
+}
+
+fn log_priors(class_counts: &Vec<f64>) -> Vec<f64> {
+    let total: f64 = class_counts.iter().sum();
+    if total == 0.0 {
+        panic!("Model has not been fitted!");
+    }
+    return class_counts.iter().map(|&count| (count / total).ln()).collect();
+}
+
+// Normalizes rows of joint log-likelihoods into log posteriors
+fn normalize(joint: Vec<Vec<f64>>) -> Matrix {
+    let rows = joint.len();
+    let cols = if rows > 0 { joint[0].len() } else { 0 };
+    let mut data: Vec<f64> = Vec::new();
+    for row in joint {
+        let total = log_sum_exp(&row);
+        data.extend(row.iter().map(|x| x - total));
+    }
+    return Matrix::from_vec(&data, rows, cols);
+}
+
+fn exp_matrix(log_proba: Matrix) -> Matrix {
+    let mut result = log_proba;
+    result.map(f64::exp);
+    return result;
+}
+
+fn argmax_rows(log_proba: &Matrix) -> Vec<usize> {
+    return (0..log_proba.size().0).map(|i| argmax(&log_proba.get_row(i))).collect();
+}
+
+pub struct GaussianNB {
+    num_classes: usize,
+    num_features: usize,
+    var_smoothing: f64,
+    class_counts: Vec<f64>,
+    means: Vec<Vec<f64>>,
+    // Sums of squared deviations from the mean, per class and feature
+    squared_deviations: Vec<Vec<f64>>,
+}
+
+impl GaussianNB {
+    pub fn new(num_features: usize, num_classes: usize) -> GaussianNB {
+        GaussianNB {
+            num_classes,
+            num_features,
+            var_smoothing: 1e-9,
+            class_counts: vec![0.0; num_classes],
+            means: vec![vec![0.0; num_features]; num_classes],
+            squared_deviations: vec![vec![0.0; num_features]; num_classes],
+        }
+    }
+
+    // Fraction of the largest variance added to every variance for stability
+    pub fn set_var_smoothing(&mut self, var_smoothing: f64) {
+        self.var_smoothing = var_smoothing;
+    }
+
+    pub fn get_means(&self) -> &Vec<Vec<f64>> {
+        return &self.means;
+    }
+
+    pub fn get_variances(&self) -> Vec<Vec<f64>> {
+        let epsilon = self.epsilon();
+        let mut result: Vec<Vec<f64>> = Vec::new();
+        for c in 0..self.num_classes {
+            let count = self.class_counts[c].max(1.0);
+            result.push(self.squared_deviations[c].iter().map(|m| m / count + epsilon).collect());
+        }
+        return result;
+    }
+
+    fn epsilon(&self) -> f64 {
+        let mut largest = 0.0f64;
+        for c in 0..self.num_classes {
+            if self.class_counts[c] == 0.0 {
+                continue;
+            }
+            for m in &self.squared_deviations[c] {
+                largest = largest.max(m / self.class_counts[c]);
+            }
+        }
+        if largest == 0.0 {
+            return self.var_smoothing;
+        }
+        return self.var_smoothing * largest;
+    }
+
+    pub fn fit(&mut self, inputs: &Matrix, labels: &Vec<usize>) {
+        self.class_counts = vec![0.0; self.num_classes];
+        self.means = vec![vec![0.0; self.num_features]; self.num_classes];
+        self.squared_deviations = vec![vec![0.0; self.num_features]; self.num_classes];
+        self.partial_fit(inputs, labels);
+    }
+
+    // Merges the batch statistics into the running ones (Chan et al.), so
+    // data can be streamed in chunks
+    pub fn partial_fit(&mut self, inputs: &Matrix, labels: &Vec<usize>) {
+        check_batch(inputs, labels, self.num_classes, self.num_features);
+        for c in 0..self.num_classes {
+            let rows: Vec<Vec<f64>> = (0..labels.len())
+                .filter(|&i| labels[i] == c)
+                .map(|i| inputs.get_row(i))
+                .collect();
+            if rows.is_empty() {
+                continue;
+            }
+            let batch_count = rows.len() as f64;
+            let old_count = self.class_counts[c];
+            let total = old_count + batch_count;
+            for j in 0..self.num_features {
+                let batch_mean = rows.iter().map(|row| row[j]).sum::<f64>() / batch_count;
+                let batch_deviations: f64 = rows.iter().map(|row| (row[j] - batch_mean) * (row[j] - batch_mean)).sum();
+                let delta = batch_mean - self.means[c][j];
+                self.means[c][j] += delta * batch_count / total;
+                self.squared_deviations[c][j] += batch_deviations + delta * delta * old_count * batch_count / total;
+            }
+            self.class_counts[c] = total;
+        }
+    }
+
+    pub fn predict_log_proba(&self, inputs: &Matrix) -> Matrix {
+        if inputs.size().1 != self.num_features {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        let priors = log_priors(&self.class_counts);
+        let variances = self.get_variances();
+        let mut joint: Vec<Vec<f64>> = Vec::new();
+        for i in 0..inputs.size().0 {
+            let row = inputs.get_row(i);
+            let mut scores: Vec<f64> = Vec::new();
+            for c in 0..self.num_classes {
+                if self.class_counts[c] == 0.0 {
+                    scores.push(f64::NEG_INFINITY);
+                    continue;
+                }
+                let mut score = priors[c];
+                for j in 0..self.num_features {
+                    let diff = row[j] - self.means[c][j];
+                    score -= 0.5 * (2.0 * PI * variances[c][j]).ln() + diff * diff / (2.0 * variances[c][j]);
+                }
+                scores.push(score);
+            }
+            joint.push(scores);
+        }
+        return normalize(joint);
+    }
+
+    pub fn predict_proba(&self, inputs: &Matrix) -> Matrix {
+        return exp_matrix(self.predict_log_proba(inputs));
+    }
+
+    pub fn predict(&self, inputs: &Matrix) -> Vec<usize> {
+        return argmax_rows(&self.predict_log_proba(inputs));
+    }
+}
+
+pub struct MultinomialNB {
+    num_classes: usize,
+    num_features: usize,
+    alpha: f64,
+    class_counts: Vec<f64>,
+    feature_counts: Vec<Vec<f64>>,
+}
+
+impl MultinomialNB {
+    pub fn new(num_features: usize, num_classes: usize) -> MultinomialNB {
+        MultinomialNB {
+            num_classes,
+            num_features,
+            alpha: 1.0,
+            class_counts: vec![0.0; num_classes],
+            feature_counts: vec![vec![0.0; num_features]; num_classes],
+        }
+    }
+
+    // Additive (Laplace) smoothing; 1.0 is Laplace, below 1.0 is Lidstone
+    pub fn set_alpha(&mut self, alpha: f64) {
+        self.alpha = alpha;
+    }
+
+    pub fn fit(&mut self, inputs: &Matrix, labels: &Vec<usize>) {
+        self.class_counts = vec![0.0; self.num_classes];
+        self.feature_counts = vec![vec![0.0; self.num_features]; self.num_classes];
+        self.partial_fit(inputs, labels);
+    }
+
+    pub fn partial_fit(&mut self, inputs: &Matrix, labels: &Vec<usize>) {
+        check_batch(inputs, labels, self.num_classes, self.num_features);
+        for (i, &label) in labels.iter().enumerate() {
+            let row = inputs.get_row(i);
+            if row.iter().any(|&x| x < 0.0) {
+                panic!("Multinomial Naive Bayes requires non-negative counts!");
+            }
+            self.class_counts[label] += 1.0;
+            for (count, x) in self.feature_counts[label].iter_mut().zip(row.iter()) {
+                *count += x;
+            }
+        }
+    }
+
+    // ln P(feature | class), smoothed
+    pub fn feature_log_proba(&self) -> Vec<Vec<f64>> {
+        let mut result: Vec<Vec<f64>> = Vec::new();
+        for counts in &self.feature_counts {
+            let total: f64 = counts.iter().sum::<f64>() + self.alpha * self.num_features as f64;
+            result.push(counts.iter().map(|&count| ((count + self.alpha) / total).ln()).collect());
+        }
+        return result;
+    }
+
+    pub fn predict_log_proba(&self, inputs: &Matrix) -> Matrix {
+        if inputs.size().1 != self.num_features {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        let priors = log_priors(&self.class_counts);
+        let feature_log_proba = self.feature_log_proba();
+        let mut joint: Vec<Vec<f64>> = Vec::new();
+        for i in 0..inputs.size().0 {
+            let row = inputs.get_row(i);
+            let scores: Vec<f64> = (0..self.num_classes).map(|c| {
+                priors[c] + row.iter().zip(&feature_log_proba[c]).map(|(x, p)| x * p).sum::<f64>()
+            }).collect();
+            joint.push(scores);
+        }
+        return normalize(joint);
+    }
+
+    pub fn predict_proba(&self, inputs: &Matrix) -> Matrix {
+        return exp_matrix(self.predict_log_proba(inputs));
+    }
+
+    pub fn predict(&self, inputs: &Matrix) -> Vec<usize> {
+        return argmax_rows(&self.predict_log_proba(inputs));
+    }
+}
+
+pub struct BernoulliNB {
+    num_classes: usize,
+    num_features: usize,
+    alpha: f64,
+    binarize: f64,
+    class_counts: Vec<f64>,
+    feature_counts: Vec<Vec<f64>>,
+}
+
+impl BernoulliNB {
+    pub fn new(num_features: usize, num_classes: usize) -> BernoulliNB {
+        BernoulliNB {
+            num_classes,
+            num_features,
+            alpha: 1.0,
+            binarize: 0.0,
+            class_counts: vec![0.0; num_classes],
+            feature_counts: vec![vec![0.0; num_features]; num_classes],
+        }
+    }
+
+    // Additive (Laplace) smoothing; 1.0 is Laplace, below 1.0 is Lidstone
+    pub fn set_alpha(&mut self, alpha: f64) {
+        self.alpha = alpha;
+    }
+
+    // Values above the threshold count as present
+    pub fn set_binarize(&mut self, threshold: f64) {
+        self.binarize = threshold;
+    }
+
+    pub fn fit(&mut self, inputs: &Matrix, labels: &Vec<usize>) {
+        self.class_counts = vec![0.0; self.num_classes];
+        self.feature_counts = vec![vec![0.0; self.num_features]; self.num_classes];
+        self.partial_fit(inputs, labels);
+    }
+
+    pub fn partial_fit(&mut self, inputs: &Matrix, labels: &Vec<usize>) {
+        check_batch(inputs, labels, self.num_classes, self.num_features);
+        for (i, &label) in labels.iter().enumerate() {
+            let row = inputs.get_row(i);
+            self.class_counts[label] += 1.0;
+            for (count, &x) in self.feature_counts[label].iter_mut().zip(row.iter()) {
+                if x > self.binarize {
+                    *count += 1.0;
+                }
+            }
+        }
+    }
+
+    // ln P(feature present | class), smoothed
+    pub fn feature_log_proba(&self) -> Vec<Vec<f64>> {
+        let mut result: Vec<Vec<f64>> = Vec::new();
+        for c in 0..self.num_classes {
+            let total = self.class_counts[c] + 2.0 * self.alpha;
+            result.push(self.feature_counts[c].iter().map(|&count| ((count + self.alpha) / total).ln()).collect());
+        }
+        return result;
+    }
+
+    pub fn predict_log_proba(&self, inputs: &Matrix) -> Matrix {
+        if inputs.size().1 != self.num_features {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        let priors = log_priors(&self.class_counts);
+        let feature_log_proba = self.feature_log_proba();
+        let mut joint: Vec<Vec<f64>> = Vec::new();
+        for i in 0..inputs.size().0 {
+            let row = inputs.get_row(i);
+            let mut scores: Vec<f64> = Vec::new();
+            for c in 0..self.num_classes {
+                let mut score = priors[c];
+                for j in 0..self.num_features {
+                    let log_present = feature_log_proba[c][j];
+                    if row[j] > self.binarize {
+                        score += log_present;
+                    } else {
+                        score += (1.0 - log_present.exp()).ln();
+                    }
+                }
+                scores.push(score);
+            }
+            joint.push(scores);
+        }
+        return normalize(joint);
+    }
+
+    pub fn predict_proba(&self, inputs: &Matrix) -> Matrix {
+        return exp_matrix(self.predict_log_proba(inputs));
+    }
+
+    pub fn predict(&self, inputs: &Matrix) -> Vec<usize> {
+        return argmax_rows(&self.predict_log_proba(inputs));
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_naive_bayes.rs"]
+mod test;
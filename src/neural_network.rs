@@ -1,72 +1,595 @@
 extern crate rand;
 
-use rand::Rng;
+use std::cell::OnceCell;
+
+use rand::{SeedableRng, FromEntropy};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
 
 use super::matrix::Matrix;
-use super::math::sigmoid;
+use super::math::argmax;
+use super::loss::Loss;
+use super::layers::{Layer, Dense, Activation, ActivationFunction, Initializer};
 
 pub type TrainingData = Vec<(Vec<f64>, Vec<f64>)>;
 
+pub enum GradientClipping {
+    None,
+    // Clamps every gradient to [-value, value]
+    Value(f64),
+    // Rescales all gradients together so their combined L2 norm is at most
+    // the given value
+    GlobalNorm(f64),
+}
+
+// Stacks samples into a matrix with one sample per row
+fn to_rows(samples: &Vec<&Vec<f64>>) -> Matrix {
+    let cols = samples[0].len();
+    let mut data: Vec<f64> = Vec::with_capacity(samples.len() * cols);
+    for sample in samples {
+        if sample.len() != cols {
+            panic!("Incompatible Vector Dimensions!");
+        }
+        data.extend(sample.iter());
+    }
+    return Matrix::from_vec(&data, samples.len(), cols);
+}
+
+pub enum Metric {
+    // Loss on the training data with the weights at the end of each epoch,
+    // penalties included, so it matches the weights a restore goes back to;
+    // lower is better
+    TrainingLoss,
+    // Loss on the validation data; lower is better
+    ValidationLoss,
+    // Fraction of validation samples whose largest output is at the position
+    // of the largest target; higher is better
+    ValidationAccuracy,
+}
+
+impl Metric {
+    fn higher_is_better(&self) -> bool {
+        return matches!(self, Metric::ValidationAccuracy);
+    }
+}
+
+// Stops training once the metric has gone `patience` epochs without
+// improving on its best value by more than `min_delta`
+pub struct EarlyStopping {
+    metric: Metric,
+    patience: usize,
+    min_delta: f64,
+    restore_best_weights: bool,
+}
+
+impl EarlyStopping {
+    pub fn new(metric: Metric, patience: usize) -> EarlyStopping {
+        if patience == 0 {
+            panic!("Patience must be at least 1!");
+        }
+        EarlyStopping {
+            metric,
+            patience,
+            min_delta: 0.0,
+            restore_best_weights: true,
+        }
+    }
+
+    pub fn set_min_delta(&mut self, min_delta: f64) {
+        if min_delta < 0.0 {
+            panic!("Minimum improvement must not be negative!");
+        }
+        self.min_delta = min_delta;
+    }
+
+    // When training ends, go back to the parameters from the best epoch.
+    // Batch normalization running averages are left as they are.
+    pub fn set_restore_best_weights(&mut self, restore_best_weights: bool) {
+        self.restore_best_weights = restore_best_weights;
+    }
+}
+
+// Layers applied one after the other, trained with minibatch gradient
+// descent on a loss over the final outputs. The model starts in training mode,
+// so layers such as dropout are active in `forward` until
+// `set_training(false)`. `train` always runs in training mode and puts the
+// previous mode back when it returns, while `execute` and `evaluate` always
+// run in evaluation mode.
+pub struct Sequential {
+    layers: Vec<Box<dyn Layer>>,
+    loss: Loss,
+    batch_size: usize,
+    l1_penalty: f64,
+    l2_penalty: f64,
+    max_norm: f64,
+    gradient_clipping: GradientClipping,
+    shuffle: bool,
+    training: bool,
+    rng: StdRng,
+    validation_data: TrainingData,
+    early_stopping: Option<EarlyStopping>,
+    // Per epoch results and the best epoch of the last call to `train`
+    validation_losses: Vec<f64>,
+    validation_accuracies: Vec<f64>,
+    best_epoch: Option<usize>,
+}
+
+impl Sequential {
+    pub fn new() -> Sequential {
+        Sequential {
+            layers: Vec::new(),
+            loss: Loss::MeanSquaredError,
+            batch_size: 1,
+            l1_penalty: 0.0,
+            l2_penalty: 0.0,
+            max_norm: f64::INFINITY,
+            gradient_clipping: GradientClipping::None,
+            shuffle: false,
+            training: true,
+            rng: StdRng::from_entropy(),
+            validation_data: Vec::new(),
+            early_stopping: None,
+            validation_losses: Vec::new(),
+            validation_accuracies: Vec::new(),
+            best_epoch: None,
+        }
+    }
+
+    pub fn add<L: Layer + 'static>(&mut self, layer: L) {
+        self.layers.push(Box::new(layer));
+    }
+
+    // Swaps out the layer at `index`, keeping the rest of the model as it is
+    pub fn set_layer<L: Layer + 'static>(&mut self, index: usize, layer: L) {
+        if index >= self.layers.len() {
+            panic!("Layer index out of bound!");
+        }
+        self.layers[index] = Box::new(layer);
+    }
+
+    pub fn get_layers(&self) -> &Vec<Box<dyn Layer>> {
+        return &self.layers;
+    }
+
+    pub fn num_layers(&self) -> usize {
+        return self.layers.len();
+    }
+
+    // With cross-entropy the last layer should output raw scores, as the
+    // softmax is folded into the loss
+    pub fn set_loss(&mut self, loss: Loss) {
+        self.loss = loss;
+    }
+
+    pub fn get_loss(&self) -> &Loss {
+        return &self.loss;
+    }
+
+    pub fn is_training(&self) -> bool {
+        return self.training;
+    }
+
+    pub fn set_batch_size(&mut self, batch_size: usize) {
+        if batch_size == 0 {
+            panic!("Batch size must be at least 1!");
+        }
+        self.batch_size = batch_size;
+    }
+
+    // Visit the training data in a new random order every epoch
+    pub fn set_shuffle(&mut self, shuffle: bool) {
+        self.shuffle = shuffle;
+    }
+
+    // Held out data evaluated after every epoch of training
+    pub fn set_validation_data(&mut self, data: &TrainingData) {
+        self.validation_data = data.clone();
+    }
+
+    pub fn set_early_stopping(&mut self, early_stopping: EarlyStopping) {
+        self.early_stopping = Some(early_stopping);
+    }
+
+    pub fn get_validation_losses(&self) -> &Vec<f64> {
+        return &self.validation_losses;
+    }
+
+    pub fn get_validation_accuracies(&self) -> &Vec<f64> {
+        return &self.validation_accuracies;
+    }
+
+    // Zero based epoch with the best early stopping metric
+    pub fn get_best_epoch(&self) -> Option<usize> {
+        return self.best_epoch;
+    }
+
+    // Redraws every layer from a generator seeded with `seed`, which then
+    // also drives shuffling. Adding the same layers, setting the same seed
+    // and training on the same data gives bit-identical models.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.reinitialize();
+    }
+
+    // Redraws every layer from the model's generator
+    pub fn reinitialize(&mut self) {
+        for layer in self.layers.iter_mut() {
+            layer.initialize(&mut self.rng);
+        }
+    }
+
+    // Adds strength * sum(|w|) over the weights to the training loss. Biases
+    // and normalization parameters are never penalized.
+    pub fn set_l1_penalty(&mut self, strength: f64) {
+        if strength < 0.0 {
+            panic!("Penalty strength must not be negative!");
+        }
+        self.l1_penalty = strength;
+    }
+
+    // Adds 0.5 * strength * sum(w^2) over the weights to the training loss
+    pub fn set_l2_penalty(&mut self, strength: f64) {
+        if strength < 0.0 {
+            panic!("Penalty strength must not be negative!");
+        }
+        self.l2_penalty = strength;
+    }
+
+    // After each update, rescales the incoming weights of any unit whose
+    // L2 norm exceeds max_norm. Infinite (the default) disables it.
+    pub fn set_max_norm(&mut self, max_norm: f64) {
+        if max_norm <= 0.0 {
+            panic!("Max norm must be positive!");
+        }
+        self.max_norm = max_norm;
+    }
+
+    // Applied to the gradients, penalties included, before each update
+    pub fn set_gradient_clipping(&mut self, gradient_clipping: GradientClipping) {
+        match gradient_clipping {
+            GradientClipping::Value(limit) | GradientClipping::GlobalNorm(limit) if limit <= 0.0 => {
+                panic!("Clipping threshold must be positive!");
+            },
+            _ => {},
+        }
+        self.gradient_clipping = gradient_clipping;
+    }
+
+    // The weight penalty term of the training loss
+    pub fn penalty(&self) -> f64 {
+        let mut result = 0.0;
+        for (weights, regularized) in self.parameters().into_iter().zip(self.regularized()) {
+            if regularized {
+                result += weights.as_vec().iter().map(|w| self.l1_penalty * w.abs() + 0.5 * self.l2_penalty * w * w).sum::<f64>();
+            }
+        }
+        return result;
+    }
+
+    // One gradient step on a batch, returning the loss, penalty included,
+    // before the step
+    pub fn train_batch(&mut self, inputs: &Matrix, targets: &Matrix, learning_rate: f64) -> f64 {
+        let outputs = self.forward(inputs);
+        let loss = self.loss.compute(&outputs, targets) + self.penalty();
+        let gradients = self.loss.gradient(&outputs, targets);
+        self.backward(&gradients);
+        self.apply_gradients(learning_rate);
+        return loss;
+    }
+
+    // Adds the penalty gradients, clips, takes a gradient descent step, clears
+    // the gradients and enforces the max-norm constraint
+    fn apply_gradients(&mut self, learning_rate: f64) {
+        let (l1, l2, max_norm) = (self.l1_penalty, self.l2_penalty, self.max_norm);
+        let (clip_value, clip_norm) = match self.gradient_clipping {
+            GradientClipping::None => (f64::INFINITY, f64::INFINITY),
+            GradientClipping::Value(limit) => (limit, f64::INFINITY),
+            GradientClipping::GlobalNorm(limit) => (f64::INFINITY, limit),
+        };
+        let regularized = self.regularized();
+        let mut parameters = self.parameters_and_gradients();
+
+        for ((weights, gradients), &regularized) in parameters.iter_mut().zip(&regularized) {
+            if regularized && (l1 > 0.0 || l2 > 0.0) {
+                // The L1 subgradient is taken as zero at zero
+                let values = weights.as_vec();
+                gradients.map_with_index(|g, i| {
+                    let sign = if values[i] > 0.0 { 1.0 } else if values[i] < 0.0 { -1.0 } else { 0.0 };
+                    return g + l1 * sign + l2 * values[i];
+                });
+            }
+            if clip_value.is_finite() {
+                gradients.map(|g| g.max(-clip_value).min(clip_value));
+            }
+        }
+        if clip_norm.is_finite() {
+            let norm = parameters.iter()
+                .map(|(_, gradients)| gradients.as_vec().iter().map(|g| g * g).sum::<f64>())
+                .sum::<f64>()
+                .sqrt();
+            if norm > clip_norm {
+                for (_, gradients) in parameters.iter_mut() {
+                    gradients.map(|g| g * clip_norm / norm);
+                }
+            }
+        }
+
+        for ((weights, gradients), &regularized) in parameters.into_iter().zip(&regularized) {
+            let mut step = gradients.clone();
+            step.map(|g| g * learning_rate);
+            *weights = weights.subtract(&step);
+            gradients.map(|_| 0.0);
+            if regularized && max_norm.is_finite() {
+                let (rows, cols) = weights.size();
+                for r in 0..rows {
+                    let norm = weights.get_row(r).iter().map(|w| w * w).sum::<f64>().sqrt();
+                    if norm > max_norm {
+                        for c in 0..cols {
+                            let value = weights.get_at_index(r, c) * max_norm / norm;
+                            weights.set_at_index(r, c, value);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Returns the average loss over each epoch, which has fewer than `epochs`
+    // entries when training stops early
+    pub fn train(&mut self, data: &TrainingData, learning_rate: f64, epochs: usize) -> Vec<f64> {
+        if data.is_empty() {
+            panic!("Training data must not be empty!");
+        }
+        if let Some(early_stopping) = &self.early_stopping {
+            if !matches!(early_stopping.metric, Metric::TrainingLoss) && self.validation_data.is_empty() {
+                panic!("Early stopping on a validation metric requires validation data!");
+            }
+        }
+        let was_training = self.training;
+        self.set_training(true);
+        self.validation_losses.clear();
+        self.validation_accuracies.clear();
+        self.best_epoch = None;
+        let mut best_value = 0.0;
+        let mut best_parameters: Vec<Matrix> = Vec::new();
+
+        let mut losses: Vec<f64> = Vec::new();
+        let mut order: Vec<usize> = (0..data.len()).collect();
+        for epoch in 0..epochs {
+            if self.shuffle {
+                order.shuffle(&mut self.rng);
+            }
+            let mut total = 0.0;
+            for batch in order.chunks(self.batch_size) {
+                let inputs = to_rows(&batch.iter().map(|&i| &data[i].0).collect());
+                let targets = to_rows(&batch.iter().map(|&i| &data[i].1).collect());
+                total += batch.len() as f64 * self.train_batch(&inputs, &targets, learning_rate);
+            }
+            losses.push(total / data.len() as f64);
+            if !self.validation_data.is_empty() {
+                self.validation_losses.push(self.evaluate(&self.validation_data));
+                self.validation_accuracies.push(self.accuracy(&self.validation_data));
+            }
+
+            if let Some(early_stopping) = &self.early_stopping {
+                let value = match early_stopping.metric {
+                    Metric::TrainingLoss => self.evaluate(data) + self.penalty(),
+                    Metric::ValidationLoss => self.validation_losses[epoch],
+                    Metric::ValidationAccuracy => self.validation_accuracies[epoch],
+                };
+                let improved = match self.best_epoch {
+                    None => true,
+                    Some(_) if early_stopping.metric.higher_is_better() => value > best_value + early_stopping.min_delta,
+                    Some(_) => value < best_value - early_stopping.min_delta,
+                };
+                if improved {
+                    best_value = value;
+                    self.best_epoch = Some(epoch);
+                    if early_stopping.restore_best_weights {
+                        best_parameters = self.parameters().into_iter().map(|p| p.clone()).collect();
+                    }
+                } else if epoch - self.best_epoch.unwrap() >= early_stopping.patience {
+                    break;
+                }
+            }
+        }
+
+        if !best_parameters.is_empty() {
+            self.set_parameters(best_parameters);
+        }
+        self.set_training(was_training);
+        return losses;
+    }
+
+    pub fn execute(&self, inputs: &Vec<f64>) -> Vec<f64> {
+        return self.predict(&Matrix::from_vec(inputs, 1, inputs.len())).as_vec();
+    }
+
+    // Average loss over the data, without the weight penalty
+    pub fn evaluate(&self, data: &TrainingData) -> f64 {
+        if data.is_empty() {
+            panic!("Data must not be empty!");
+        }
+        let inputs = to_rows(&data.iter().map(|pair| &pair.0).collect());
+        let targets = to_rows(&data.iter().map(|pair| &pair.1).collect());
+        return self.loss.compute(&self.predict(&inputs), &targets);
+    }
+
+    // Fraction of samples whose largest output is at the position of the
+    // largest target, e.g. the class of one-hot targets
+    pub fn accuracy(&self, data: &TrainingData) -> f64 {
+        if data.is_empty() {
+            panic!("Data must not be empty!");
+        }
+        let inputs = to_rows(&data.iter().map(|pair| &pair.0).collect());
+        let outputs = self.predict(&inputs);
+        let correct = data.iter().enumerate().filter(|(r, pair)| argmax(&outputs.get_row(*r)) == argmax(&pair.1)).count();
+        return correct as f64 / data.len() as f64;
+    }
+}
+
+impl Layer for Sequential {
+    fn predict(&self, inputs: &Matrix) -> Matrix {
+        let mut outputs = inputs.clone();
+        for layer in &self.layers {
+            outputs = layer.predict(&outputs);
+        }
+        return outputs;
+    }
+
+    fn forward(&mut self, inputs: &Matrix) -> Matrix {
+        let mut outputs = inputs.clone();
+        for layer in self.layers.iter_mut() {
+            outputs = layer.forward(&outputs);
+        }
+        return outputs;
+    }
+
+    fn backward(&mut self, output_gradients: &Matrix) -> Matrix {
+        let mut gradients = output_gradients.clone();
+        for layer in self.layers.iter_mut().rev() {
+            gradients = layer.backward(&gradients);
+        }
+        return gradients;
+    }
+
+    fn set_training(&mut self, training: bool) {
+        self.training = training;
+        for layer in self.layers.iter_mut() {
+            layer.set_training(training);
+        }
+    }
+
+    fn parameters(&self) -> Vec<&Matrix> {
+        return self.layers.iter().flat_map(|layer| layer.parameters()).collect();
+    }
+
+    fn gradients(&self) -> Vec<&Matrix> {
+        return self.layers.iter().flat_map(|layer| layer.gradients()).collect();
+    }
+
+    fn initialize(&mut self, rng: &mut StdRng) {
+        for layer in self.layers.iter_mut() {
+            layer.initialize(rng);
+        }
+    }
+
+    fn regularized(&self) -> Vec<bool> {
+        return self.layers.iter().flat_map(|layer| layer.regularized()).collect();
+    }
+
+    fn parameters_and_gradients(&mut self) -> Vec<(&mut Matrix, &mut Matrix)> {
+        return self.layers.iter_mut().flat_map(|layer| layer.parameters_and_gradients()).collect();
+    }
+}
+
+// The original network: num_layers square num_inputs x num_inputs layers
+// without biases, each followed by a sigmoid, trained one sample at a time
+// on the squared error. Built on a Sequential of Dense and Activation layers.
 pub struct NeuralNetwork {
     num_inputs: usize,
     num_layers: usize,
-    weights: Vec<Matrix>,
+    model: Sequential,
+    // Copy of the Dense weights lent out by get_weights, rebuilt on the first
+    // call after anything that can change them
+    weights: OnceCell<Vec<Matrix>>,
 }
 
 impl NeuralNetwork {
     pub fn new(num_inputs: usize, num_layers: usize) -> NeuralNetwork {
+        let mut model = Sequential::new();
+        for _ in 0..num_layers {
+            let mut dense = Dense::new(num_inputs, num_inputs);
+            dense.set_use_bias(false);
+            model.add(dense);
+            model.add(Activation::new(ActivationFunction::Sigmoid));
+        }
         NeuralNetwork {
             num_inputs,
             num_layers,
-            weights: Vec::<Matrix>::new(),
+            model,
+            weights: OnceCell::new(),
         }
     }
 
+    pub fn get_model(&self) -> &Sequential {
+        return &self.model;
+    }
+
+    // For configuring training, e.g. penalties and gradient clipping
+    pub fn get_model_mut(&mut self) -> &mut Sequential {
+        self.weights.take();
+        return &mut self.model;
+    }
+
+    // One num_inputs x num_inputs matrix per layer, one row per output
     pub fn get_weights(&self) -> &Vec<Matrix> {
-        return &self.weights;
+        return self.weights.get_or_init(|| {
+            self.model.parameters().into_iter().map(|weights| weights.clone()).collect()
+        });
     }
 
     pub fn set_weights(&mut self, new_weights: &Vec<Matrix>) {
-        self.weights = Vec::new();
-        for weight in new_weights {
-            self.weights.push(weight.clone());
-        }
+        self.weights.take();
+        self.model.set_parameters(new_weights.iter().map(|weights| weights.clone()).collect());
     }
 
+    // Replaces the weights of every layer with uniform values in
+    // +-1 / sqrt(num_inputs), drawn from the model's generator
     pub fn randomize_weights(&mut self) {
-        let mut rng = rand::thread_rng();
-        let num_inputs = self.num_inputs;
-        for _ in 0..self.num_layers {
-            let mut weights: Vec<f64> = Vec::new();
-            for _ in 0..(num_inputs * num_inputs) {
-                let bound: f64 = 1.0 / (self.num_inputs as f64).sqrt();
-                weights.push(rng.gen_range(-bound, bound));
-            }
-            self.weights.push(Matrix::from_vec(&weights, num_inputs, num_inputs));
+        self.weights.take();
+        self.model.reinitialize();
+    }
+
+    // Redraws the weights from a generator seeded with `seed`, which also
+    // drives later calls to `randomize_weights`
+    pub fn set_seed(&mut self, seed: u64) {
+        self.weights.take();
+        self.model.set_seed(seed);
+    }
+
+    // Gives each layer its own scheme and redraws the weights from a
+    // generator seeded with `seed`. The schemes are kept, so later calls to
+    // `randomize_weights` or `set_seed` draw from them too.
+    pub fn initialize_weights(&mut self, initializers: &Vec<Initializer>, seed: u64) {
+        if initializers.len() != self.num_layers {
+            panic!("One initializer per layer is required!");
         }
+        for (i, initializer) in initializers.iter().enumerate() {
+            let mut dense = Dense::new(self.num_inputs, self.num_inputs);
+            dense.set_use_bias(false);
+            dense.set_initializer(initializer.clone());
+            self.model.set_layer(2 * i, dense);
+        }
+        self.set_seed(seed);
     }
 
+    // The inputs followed by the output of every layer, as column vectors
     pub fn get_outputs(&self, inputs: &Vec<f64>) -> Vec<Matrix> {
         let mut result: Vec<Matrix> = Vec::new();
 
-        let mut inputs = Matrix::from_vec(&inputs, self.num_inputs, 1);
-        result.push(inputs.clone());
-        for i in 0..self.num_layers {
-            let mut outputs = self.weights[i].dot_prod(&inputs);
-            outputs.map(sigmoid);
-            inputs = outputs.clone();
-            result.push(outputs);
+        let mut outputs = Matrix::from_vec(inputs, 1, self.num_inputs);
+        result.push(outputs.transpose());
+        for pair in self.model.get_layers().chunks(2) {
+            for layer in pair {
+                outputs = layer.predict(&outputs);
+            }
+            result.push(outputs.transpose());
         }
 
         return result;
     }
 
+    // Errors of the original delta rule, passed back through each layer's
+    // weights as they are rather than transposed. `train` backpropagates
+    // through the model instead.
+    #[deprecated(note = "train no longer uses the delta rule; use the model's backward pass instead")]
     pub fn get_errors(&self, inputs: &Vec<f64>, targets: &Vec<f64>) -> Vec<Matrix> {
         let mut result: Vec<Matrix> = Vec::new();
 
         let weights = self.get_weights();
         let outputs = self.get_outputs(inputs);
-        let targets = Matrix::from_vec(targets, outputs[0].size().0, outputs[0].size().1); 
+        let targets = Matrix::from_vec(targets, outputs[0].size().0, outputs[0].size().1);
 
         let errors = targets.subtract(&outputs[outputs.len() - 1]);
         result.push(errors);
@@ -80,11 +603,13 @@ impl NeuralNetwork {
         return result;
     }
 
+    #[deprecated(note = "train no longer uses the delta rule; use the model's backward pass instead")]
+    #[allow(deprecated)]
     pub fn get_deltas(&self, inputs: &Vec<f64>, targets: &Vec<f64>, learning_rate: f64) -> Vec<Matrix> {
         let mut result: Vec<Matrix> = Vec::new();
 
         let outputs = self.get_outputs(inputs);
-        
+
         let errors = self.get_errors(inputs, targets);
 
         for layer in 0..self.num_layers {
@@ -94,42 +619,31 @@ impl NeuralNetwork {
 
             let mut delta_vec: Vec<f64> = Vec::new();
             for j in 0..error.len() {
-                for k in 0..output.len() {
-                    let delta: f64 = learning_rate * error[j] * output[j] * (1.0 - output[j]) * prev_output[k];
-                    delta_vec.push(delta); 
+                for prev in prev_output.iter().take(output.len()) {
+                    let delta: f64 = learning_rate * error[j] * output[j] * (1.0 - output[j]) * prev;
+                    delta_vec.push(delta);
                 }
             }
             let delta_matrix = Matrix::from_vec(&delta_vec, inputs.len(), inputs.len());
-            // println!("-->{}", delta_matrix.to_string_fmt(10));
             result.push(delta_matrix);
         }
 
         return result;
     }
 
+    // Backpropagates the squared error through the model, one sample at a time
     pub fn train(&mut self, data: &TrainingData, learning_rate: f64, epochs: usize) {
-        for _ in 0..epochs {
-            for pair in data {
-                let inputs: &Vec<f64> = &pair.0;
-                let targets: &Vec<f64> = &pair.1;
-                
-                let deltas = self.get_deltas(inputs, targets, learning_rate);
-                
-                for i in 0..self.num_layers {
-                    self.weights[i] = self.weights[i].add(&deltas[i]);
-                }
-            }        
-        }
-        
+        self.weights.take();
+        self.model.train(data, learning_rate, epochs);
     }
 
     pub fn execute(&self, inputs: &Vec<f64>) -> Matrix {
-        let outputs: Vec<Matrix> = self.get_outputs(inputs);
-        return outputs.last().unwrap().clone();
+        let outputs = self.model.execute(inputs);
+        return Matrix::from_vec(&outputs, outputs.len(), 1);
     }
 
 }
 
 #[cfg(test)]
 #[path = "tests/test_neural_network.rs"]
-mod test;
\ No newline at end of file
+mod test;
@@ -0,0 +1,165 @@
+use super::matrix::Matrix;
+
+pub enum Components {
+    All,
+    Count(usize),
+    // Smallest number of components whose explained variance ratios add up
+    // to at least this fraction
+    VarianceRatio(f64),
+}
+
+pub struct PCA {
+    components_choice: Components,
+    mean: Vec<f64>,
+    // One principal axis per row, num_components x num_features
+    components: Matrix,
+    explained_variance: Vec<f64>,
+    explained_variance_ratio: Vec<f64>,
+}
+
+impl PCA {
+    pub fn new(components: Components) -> PCA {
+        if let Components::VarianceRatio(ratio) = components {
+            if ratio <= 0.0 || ratio > 1.0 {
+                panic!("Variance ratio must be in (0, 1]!");
+            }
+        }
+        PCA {
+            components_choice: components,
+            mean: Vec::new(),
+            components: Matrix::new(),
+            explained_variance: Vec::new(),
+            explained_variance_ratio: Vec::new(),
+        }
+    }
+
+    pub fn get_mean(&self) -> &Vec<f64> {
+        return &self.mean;
+    }
+
+    pub fn get_components(&self) -> &Matrix {
+        return &self.components;
+    }
+
+    // Variance of the data along each kept component
+    pub fn get_explained_variance(&self) -> &Vec<f64> {
+        return &self.explained_variance;
+    }
+
+    pub fn get_explained_variance_ratio(&self) -> &Vec<f64> {
+        return &self.explained_variance_ratio;
+    }
+
+    pub fn get_num_components(&self) -> usize {
+        return self.components.size().0;
+    }
+
+    pub fn fit(&mut self, inputs: &Matrix) {
+        let (rows, cols) = inputs.size();
+        if rows < 2 {
+            panic!("At least two samples are required!");
+        }
+
+        self.mean = vec![0.0; cols];
+        for i in 0..rows {
+            for j in 0..cols {
+                self.mean[j] += inputs.get_at_index(i, j) / rows as f64;
+            }
+        }
+        let centered = self.center(inputs);
+        let mut covariance = centered.transpose().dot_prod(&centered);
+        covariance.map(|x| x / (rows - 1) as f64);
+
+        let (values, vectors) = covariance.symmetric_eigen();
+        // Rounding can leave tiny negative eigenvalues
+        let values: Vec<f64> = values.iter().map(|v| v.max(0.0)).collect();
+        let total: f64 = values.iter().sum();
+        let ratios: Vec<f64> = values.iter().map(|v| if total > 0.0 { v / total } else { 0.0 }).collect();
+
+        let count = match self.components_choice {
+            Components::All => cols,
+            Components::Count(count) => {
+                if count == 0 || count > cols {
+                    panic!("Number of components out of range!");
+                }
+                count
+            },
+            Components::VarianceRatio(threshold) => {
+                let mut cumulative = 0.0;
+                let mut count = cols;
+                for (i, ratio) in ratios.iter().enumerate() {
+                    cumulative += ratio;
+                    if cumulative >= threshold - 1e-12 {
+                        count = i + 1;
+                        break;
+                    }
+                }
+                count
+            },
+        };
+
+        // Flip signs so the largest loading of each axis is positive, which
+        // keeps the output stable between runs
+        let mut data: Vec<f64> = Vec::new();
+        for c in 0..count {
+            let axis: Vec<f64> = (0..cols).map(|k| vectors.get_at_index(k, c)).collect();
+            let mut largest = 0;
+            for k in 1..cols {
+                if axis[k].abs() > axis[largest].abs() {
+                    largest = k;
+                }
+            }
+            let sign = if axis[largest] < 0.0 { -1.0 } else { 1.0 };
+            data.extend(axis.iter().map(|x| x * sign));
+        }
+        self.components = Matrix::from_vec(&data, count, cols);
+        self.explained_variance = values[..count].to_vec();
+        self.explained_variance_ratio = ratios[..count].to_vec();
+    }
+
+    fn center(&self, inputs: &Matrix) -> Matrix {
+        let cols = inputs.size().1;
+        if cols != self.mean.len() {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        let mut result = inputs.clone();
+        let mean = &self.mean;
+        result.map_with_index(|x, i| x - mean[i % cols]);
+        return result;
+    }
+
+    // Projects samples onto the principal axes, num_samples x num_components
+    pub fn transform(&self, inputs: &Matrix) -> Matrix {
+        if self.mean.is_empty() {
+            panic!("Model has not been fitted!");
+        }
+        return self.center(inputs).dot_prod(&self.components.transpose());
+    }
+
+    pub fn fit_transform(&mut self, inputs: &Matrix) -> Matrix {
+        self.fit(inputs);
+        return self.transform(inputs);
+    }
+
+    // Maps projected samples back to the original feature space
+    pub fn inverse_transform(&self, projected: &Matrix) -> Matrix {
+        if self.mean.is_empty() {
+            panic!("Model has not been fitted!");
+        }
+        let mut result = projected.dot_prod(&self.components);
+        let mean = &self.mean;
+        let cols = mean.len();
+        result.map_with_index(|x, i| x + mean[i % cols]);
+        return result;
+    }
+
+    // Single sample version of `transform`, ready for `NeuralNetwork::execute`
+    pub fn transform_vec(&self, input: &Vec<f64>) -> Vec<f64> {
+        let row = Matrix::from_vec(input, 1, input.len());
+        return self.transform(&row).as_vec();
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_pca.rs"]
+mod test;
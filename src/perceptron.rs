@@ -0,0 +1,181 @@
+use super::matrix::Matrix;
+
+fn check_binary(inputs: &Matrix, labels: &Vec<usize>, num_inputs: usize) {
+    if inputs.size().0 != labels.len() {
+        panic!("Inputs and labels must have the same number of rows!");
+    }
+    if inputs.size().1 != num_inputs {
+        panic!("Incompatible Matrix Dimensions!");
+    }
+    if labels.iter().any(|&label| label > 1) {
+        panic!("Only binary labels (0 or 1) are supported!");
+    }
+}
+
+fn net_input(weights: &Vec<f64>, bias: f64, row: &Vec<f64>) -> f64 {
+    return bias + weights.iter().zip(row).map(|(w, x)| w * x).sum::<f64>();
+}
+
+// Rosenblatt's perceptron: a threshold unit whose weights only move when a
+// sample is misclassified. Converges if and only if the classes are
+// linearly separable.
+pub struct Perceptron {
+    num_inputs: usize,
+    weights: Vec<f64>,
+    bias: f64,
+    learning_rate: f64,
+    max_epochs: usize,
+    errors_per_epoch: Vec<usize>,
+}
+
+impl Perceptron {
+    pub fn new(num_inputs: usize) -> Perceptron {
+        Perceptron {
+            num_inputs,
+            weights: vec![0.0; num_inputs],
+            bias: 0.0,
+            learning_rate: 1.0,
+            max_epochs: 100,
+            errors_per_epoch: Vec::new(),
+        }
+    }
+
+    pub fn get_weights(&self) -> &Vec<f64> {
+        return &self.weights;
+    }
+
+    pub fn get_bias(&self) -> f64 {
+        return self.bias;
+    }
+
+    pub fn set_learning_rate(&mut self, learning_rate: f64) {
+        self.learning_rate = learning_rate;
+    }
+
+    pub fn set_max_epochs(&mut self, max_epochs: usize) {
+        self.max_epochs = max_epochs;
+    }
+
+    // Number of misclassified samples seen in each training epoch
+    pub fn get_errors_per_epoch(&self) -> &Vec<usize> {
+        return &self.errors_per_epoch;
+    }
+
+    // Whether the last epoch of training classified every sample correctly
+    pub fn has_converged(&self) -> bool {
+        return self.errors_per_epoch.last() == Some(&0);
+    }
+
+    pub fn fit(&mut self, inputs: &Matrix, labels: &Vec<usize>) {
+        check_binary(inputs, labels, self.num_inputs);
+        self.weights = vec![0.0; self.num_inputs];
+        self.bias = 0.0;
+        self.errors_per_epoch = Vec::new();
+
+        for _ in 0..self.max_epochs {
+            let mut errors = 0;
+            for (i, &label) in labels.iter().enumerate() {
+                let row = inputs.get_row(i);
+                let predicted = if net_input(&self.weights, self.bias, &row) >= 0.0 { 1.0 } else { 0.0 };
+                let update = self.learning_rate * (label as f64 - predicted);
+                if update != 0.0 {
+                    errors += 1;
+                    for (weight, x) in self.weights.iter_mut().zip(row.iter()) {
+                        *weight += update * x;
+                    }
+                    self.bias += update;
+                }
+            }
+            self.errors_per_epoch.push(errors);
+            if errors == 0 {
+                break;
+            }
+        }
+    }
+
+    pub fn predict(&self, inputs: &Matrix) -> Vec<usize> {
+        return (0..inputs.size().0)
+            .map(|i| if net_input(&self.weights, self.bias, &inputs.get_row(i)) >= 0.0 { 1 } else { 0 })
+            .collect();
+    }
+}
+
+// Adaptive linear neuron: learns with the delta rule on the continuous
+// activation (batch gradient descent on the sum of squared errors against
+// targets of -1 and +1) and only thresholds when predicting.
+pub struct Adaline {
+    num_inputs: usize,
+    weights: Vec<f64>,
+    bias: f64,
+    learning_rate: f64,
+    epochs: usize,
+    costs: Vec<f64>,
+}
+
+impl Adaline {
+    pub fn new(num_inputs: usize) -> Adaline {
+        Adaline {
+            num_inputs,
+            weights: vec![0.0; num_inputs],
+            bias: 0.0,
+            learning_rate: 0.01,
+            epochs: 50,
+            costs: Vec::new(),
+        }
+    }
+
+    pub fn get_weights(&self) -> &Vec<f64> {
+        return &self.weights;
+    }
+
+    pub fn get_bias(&self) -> f64 {
+        return self.bias;
+    }
+
+    pub fn set_learning_rate(&mut self, learning_rate: f64) {
+        self.learning_rate = learning_rate;
+    }
+
+    pub fn set_epochs(&mut self, epochs: usize) {
+        self.epochs = epochs;
+    }
+
+    // Half the sum of squared errors, recorded before each epoch's update
+    pub fn get_costs(&self) -> &Vec<f64> {
+        return &self.costs;
+    }
+
+    pub fn fit(&mut self, inputs: &Matrix, labels: &Vec<usize>) {
+        check_binary(inputs, labels, self.num_inputs);
+        self.weights = vec![0.0; self.num_inputs];
+        self.bias = 0.0;
+        self.costs = Vec::new();
+
+        let rows = inputs.get_rows();
+        let targets: Vec<f64> = labels.iter().map(|&label| if label == 1 { 1.0 } else { -1.0 }).collect();
+        for _ in 0..self.epochs {
+            let errors: Vec<f64> = rows.iter().zip(&targets)
+                .map(|(row, target)| target - net_input(&self.weights, self.bias, row))
+                .collect();
+            self.costs.push(0.5 * errors.iter().map(|e| e * e).sum::<f64>());
+            for j in 0..self.num_inputs {
+                let gradient: f64 = rows.iter().zip(&errors).map(|(row, e)| row[j] * e).sum();
+                self.weights[j] += self.learning_rate * gradient;
+            }
+            self.bias += self.learning_rate * errors.iter().sum::<f64>();
+        }
+    }
+
+    // The linear activation before thresholding
+    pub fn activation(&self, inputs: &Matrix) -> Vec<f64> {
+        return (0..inputs.size().0).map(|i| net_input(&self.weights, self.bias, &inputs.get_row(i))).collect();
+    }
+
+    pub fn predict(&self, inputs: &Matrix) -> Vec<usize> {
+        return self.activation(inputs).iter().map(|&a| if a >= 0.0 { 1 } else { 0 }).collect();
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_perceptron.rs"]
+mod test;
@@ -0,0 +1,362 @@
+extern crate rand;
+
+use rand::Rng;
+use std::thread;
+
+use super::matrix::Matrix;
+use super::math::argmax;
+use super::decision_tree::{DecisionTreeClassifier, DecisionTreeRegressor, Criterion};
+
+// Number of features considered at each split. `All` turns the forest into
+// plain bagging of decision trees.
+pub enum MaxFeatures {
+    All,
+    Sqrt,
+    Log2,
+    Count(usize),
+}
+
+impl MaxFeatures {
+    fn resolve(&self, num_features: usize) -> usize {
+        let count = match self {
+            MaxFeatures::All => num_features,
+            MaxFeatures::Sqrt => (num_features as f64).sqrt() as usize,
+            MaxFeatures::Log2 => (num_features as f64).log2() as usize,
+            MaxFeatures::Count(count) => *count,
+        };
+        return count.max(1).min(num_features);
+    }
+}
+
+fn default_num_threads() -> usize {
+    return thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+}
+
+// Trains `num_trees` learners split across `num_threads` threads. Each learner
+// receives the row indices of its (bootstrap) sample and is returned with a
+// mask of the rows that were in its bag.
+fn train_trees<T, F>(num_trees: usize, num_threads: usize, num_samples: usize, bootstrap: bool, build: F) -> Vec<(T, Vec<bool>)>
+    where T: Send, F: Fn(Vec<usize>) -> T + Sync {
+    let build = &build;
+    let num_threads = num_threads.max(1).min(num_trees.max(1));
+    let mut result: Vec<(T, Vec<bool>)> = Vec::new();
+    thread::scope(|scope| {
+        let mut handles = Vec::new();
+        for t in 0..num_threads {
+            let count = num_trees / num_threads + if t < num_trees % num_threads { 1 } else { 0 };
+            handles.push(scope.spawn(move || {
+                let mut rng = rand::thread_rng();
+                let mut trees: Vec<(T, Vec<bool>)> = Vec::new();
+                for _ in 0..count {
+                    let indices: Vec<usize> = if bootstrap {
+                        (0..num_samples).map(|_| rng.gen_range(0, num_samples)).collect()
+                    } else {
+                        (0..num_samples).collect()
+                    };
+                    let mut in_bag = vec![false; num_samples];
+                    for &i in &indices {
+                        in_bag[i] = true;
+                    }
+                    trees.push((build(indices), in_bag));
+                }
+                trees
+            }));
+        }
+        for handle in handles {
+            result.extend(handle.join().unwrap());
+        }
+    });
+    return result;
+}
+
+fn mean_importances(importances: Vec<&Vec<f64>>) -> Vec<f64> {
+    let mut result = vec![0.0; importances[0].len()];
+    for tree in &importances {
+        for (total, value) in result.iter_mut().zip(tree.iter()) {
+            *total += value / importances.len() as f64;
+        }
+    }
+    return result;
+}
+
+pub struct RandomForestClassifier {
+    num_trees: usize,
+    num_classes: usize,
+    criterion_is_entropy: bool,
+    max_features: MaxFeatures,
+    max_depth: Option<usize>,
+    min_samples_split: usize,
+    min_samples_leaf: usize,
+    bootstrap: bool,
+    num_threads: usize,
+    trees: Vec<DecisionTreeClassifier>,
+    oob_error: Option<f64>,
+}
+
+impl RandomForestClassifier {
+    pub fn new(num_trees: usize, num_classes: usize) -> RandomForestClassifier {
+        if num_trees == 0 {
+            panic!("A forest needs at least one tree!");
+        }
+        RandomForestClassifier {
+            num_trees,
+            num_classes,
+            criterion_is_entropy: false,
+            max_features: MaxFeatures::Sqrt,
+            max_depth: None,
+            min_samples_split: 2,
+            min_samples_leaf: 1,
+            bootstrap: true,
+            num_threads: default_num_threads(),
+            trees: Vec::new(),
+            oob_error: None,
+        }
+    }
+
+    pub fn set_criterion(&mut self, criterion: Criterion) {
+        self.criterion_is_entropy = match criterion {
+            Criterion::Gini => false,
+            Criterion::Entropy => true,
+            Criterion::MSE => panic!("Classification trees support Gini or entropy only!"),
+        };
+    }
+
+    pub fn set_max_features(&mut self, max_features: MaxFeatures) {
+        self.max_features = max_features;
+    }
+
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = Some(max_depth);
+    }
+
+    pub fn set_min_samples_split(&mut self, min_samples_split: usize) {
+        self.min_samples_split = min_samples_split;
+    }
+
+    pub fn set_min_samples_leaf(&mut self, min_samples_leaf: usize) {
+        self.min_samples_leaf = min_samples_leaf;
+    }
+
+    pub fn set_bootstrap(&mut self, bootstrap: bool) {
+        self.bootstrap = bootstrap;
+    }
+
+    pub fn set_num_threads(&mut self, num_threads: usize) {
+        self.num_threads = num_threads.max(1);
+    }
+
+    pub fn get_trees(&self) -> &Vec<DecisionTreeClassifier> {
+        return &self.trees;
+    }
+
+    // Misclassification rate on out-of-bag samples, if any sample was left out
+    pub fn get_oob_error(&self) -> Option<f64> {
+        return self.oob_error;
+    }
+
+    pub fn fit(&mut self, inputs: &Matrix, labels: &Vec<usize>) {
+        if inputs.size().0 != labels.len() {
+            panic!("Inputs and labels must have the same number of rows!");
+        }
+        let max_features = self.max_features.resolve(inputs.size().1);
+        let trained = train_trees(self.num_trees, self.num_threads, labels.len(), self.bootstrap, |indices| {
+            let mut tree = DecisionTreeClassifier::new(self.num_classes);
+            if self.criterion_is_entropy {
+                tree.set_criterion(Criterion::Entropy);
+            }
+            if let Some(max_depth) = self.max_depth {
+                tree.set_max_depth(max_depth);
+            }
+            tree.set_min_samples_split(self.min_samples_split);
+            tree.set_min_samples_leaf(self.min_samples_leaf);
+            tree.set_max_features(max_features);
+            tree.fit_samples(inputs, labels, indices);
+            tree
+        });
+
+        // Each sample is voted on only by the trees that did not see it
+        let mut votes = vec![vec![0.0; self.num_classes]; labels.len()];
+        for (tree, in_bag) in &trained {
+            let probabilities = tree.predict_proba(inputs);
+            for (i, vote) in votes.iter_mut().enumerate() {
+                if in_bag[i] {
+                    continue;
+                }
+                for (c, v) in vote.iter_mut().enumerate() {
+                    *v += probabilities.get_at_index(i, c);
+                }
+            }
+        }
+        let mut evaluated = 0;
+        let mut errors = 0;
+        for i in 0..labels.len() {
+            if votes[i].iter().all(|&v| v == 0.0) {
+                continue;
+            }
+            evaluated += 1;
+            if argmax(&votes[i]) != labels[i] {
+                errors += 1;
+            }
+        }
+        self.oob_error = if evaluated > 0 { Some(errors as f64 / evaluated as f64) } else { None };
+        self.trees = trained.into_iter().map(|(tree, _)| tree).collect();
+    }
+
+    pub fn predict_proba(&self, inputs: &Matrix) -> Matrix {
+        if self.trees.is_empty() {
+            panic!("Model has not been fitted!");
+        }
+        let mut result = Matrix::new();
+        result.zero_fill(inputs.size().0, self.num_classes);
+        for tree in &self.trees {
+            result = result.add(&tree.predict_proba(inputs));
+        }
+        let num_trees = self.trees.len() as f64;
+        result.map(|p| p / num_trees);
+        return result;
+    }
+
+    pub fn predict(&self, inputs: &Matrix) -> Vec<usize> {
+        let probabilities = self.predict_proba(inputs);
+        return (0..inputs.size().0).map(|i| argmax(&probabilities.get_row(i))).collect();
+    }
+
+    // Mean of the per-tree impurity based importances
+    pub fn feature_importances(&self) -> Vec<f64> {
+        if self.trees.is_empty() {
+            panic!("Model has not been fitted!");
+        }
+        return mean_importances(self.trees.iter().map(|tree| tree.feature_importances()).collect());
+    }
+}
+
+pub struct RandomForestRegressor {
+    num_trees: usize,
+    max_features: MaxFeatures,
+    max_depth: Option<usize>,
+    min_samples_split: usize,
+    min_samples_leaf: usize,
+    bootstrap: bool,
+    num_threads: usize,
+    trees: Vec<DecisionTreeRegressor>,
+    oob_error: Option<f64>,
+}
+
+impl RandomForestRegressor {
+    pub fn new(num_trees: usize) -> RandomForestRegressor {
+        if num_trees == 0 {
+            panic!("A forest needs at least one tree!");
+        }
+        RandomForestRegressor {
+            num_trees,
+            max_features: MaxFeatures::All,
+            max_depth: None,
+            min_samples_split: 2,
+            min_samples_leaf: 1,
+            bootstrap: true,
+            num_threads: default_num_threads(),
+            trees: Vec::new(),
+            oob_error: None,
+        }
+    }
+
+    pub fn set_max_features(&mut self, max_features: MaxFeatures) {
+        self.max_features = max_features;
+    }
+
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = Some(max_depth);
+    }
+
+    pub fn set_min_samples_split(&mut self, min_samples_split: usize) {
+        self.min_samples_split = min_samples_split;
+    }
+
+    pub fn set_min_samples_leaf(&mut self, min_samples_leaf: usize) {
+        self.min_samples_leaf = min_samples_leaf;
+    }
+
+    pub fn set_bootstrap(&mut self, bootstrap: bool) {
+        self.bootstrap = bootstrap;
+    }
+
+    pub fn set_num_threads(&mut self, num_threads: usize) {
+        self.num_threads = num_threads.max(1);
+    }
+
+    pub fn get_trees(&self) -> &Vec<DecisionTreeRegressor> {
+        return &self.trees;
+    }
+
+    // Mean squared error on out-of-bag samples, if any sample was left out
+    pub fn get_oob_error(&self) -> Option<f64> {
+        return self.oob_error;
+    }
+
+    pub fn fit(&mut self, inputs: &Matrix, targets: &Vec<f64>) {
+        if inputs.size().0 != targets.len() {
+            panic!("Inputs and targets must have the same number of rows!");
+        }
+        let max_features = self.max_features.resolve(inputs.size().1);
+        let trained = train_trees(self.num_trees, self.num_threads, targets.len(), self.bootstrap, |indices| {
+            let mut tree = DecisionTreeRegressor::new();
+            if let Some(max_depth) = self.max_depth {
+                tree.set_max_depth(max_depth);
+            }
+            tree.set_min_samples_split(self.min_samples_split);
+            tree.set_min_samples_leaf(self.min_samples_leaf);
+            tree.set_max_features(max_features);
+            tree.fit_samples(inputs, targets, indices);
+            tree
+        });
+
+        let mut sums = vec![0.0; targets.len()];
+        let mut counts = vec![0usize; targets.len()];
+        for (tree, in_bag) in &trained {
+            let predictions = tree.predict(inputs);
+            for i in 0..targets.len() {
+                if !in_bag[i] {
+                    sums[i] += predictions[i];
+                    counts[i] += 1;
+                }
+            }
+        }
+        let mut evaluated = 0;
+        let mut squared_error = 0.0;
+        for i in 0..targets.len() {
+            if counts[i] == 0 {
+                continue;
+            }
+            evaluated += 1;
+            let error = sums[i] / counts[i] as f64 - targets[i];
+            squared_error += error * error;
+        }
+        self.oob_error = if evaluated > 0 { Some(squared_error / evaluated as f64) } else { None };
+        self.trees = trained.into_iter().map(|(tree, _)| tree).collect();
+    }
+
+    pub fn predict(&self, inputs: &Matrix) -> Vec<f64> {
+        if self.trees.is_empty() {
+            panic!("Model has not been fitted!");
+        }
+        let mut result = vec![0.0; inputs.size().0];
+        for tree in &self.trees {
+            for (total, value) in result.iter_mut().zip(tree.predict(inputs)) {
+                *total += value / self.trees.len() as f64;
+            }
+        }
+        return result;
+    }
+
+    // Mean of the per-tree impurity based importances
+    pub fn feature_importances(&self) -> Vec<f64> {
+        if self.trees.is_empty() {
+            panic!("Model has not been fitted!");
+        }
+        return mean_importances(self.trees.iter().map(|tree| tree.feature_importances()).collect());
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_random_forest.rs"]
+mod test;
@@ -0,0 +1,817 @@
+extern crate rand;
+
+use rand::{Rng, SeedableRng, FromEntropy};
+use rand::rngs::StdRng;
+
+use super::math::{sigmoid, softmax};
+use super::matrix::Matrix;
+use super::loss::Loss;
+
+// A batch of sequences stored time-major: one batch_size x features Matrix
+// per time step
+pub type Sequence = Vec<Matrix>;
+
+// Each sample is a sequence of input vectors and the target vector expected
+// at every step
+pub type SequenceData = Vec<(Vec<Vec<f64>>, Vec<Vec<f64>>)>;
+
+fn zeros(rows: usize, cols: usize) -> Matrix {
+    let mut result = Matrix::new();
+    result.zero_fill(rows, cols);
+    return result;
+}
+
+fn uniform(rows: usize, cols: usize, bound: f64, rng: &mut StdRng) -> Matrix {
+    let data: Vec<f64> = (0..(rows * cols)).map(|_| rng.gen_range(-bound, bound)).collect();
+    return Matrix::from_vec(&data, rows, cols);
+}
+
+fn mapped<F>(matrix: &Matrix, func: F) -> Matrix
+    where F: Fn(f64) -> f64 {
+    let mut result = matrix.clone();
+    result.map(func);
+    return result;
+}
+
+fn one_minus(matrix: &Matrix) -> Matrix {
+    return mapped(matrix, |x| 1.0 - x);
+}
+
+// Derivative of tanh and the sigmoid in terms of their outputs
+fn tanh_prime(outputs: &Matrix) -> Matrix {
+    return mapped(outputs, |y| 1.0 - y * y);
+}
+
+fn sigmoid_prime(outputs: &Matrix) -> Matrix {
+    return mapped(outputs, |y| y * (1.0 - y));
+}
+
+// Input weights, recurrent weights and biases for a number of gates stacked
+// side by side, so each projection is a single matrix product
+struct GateWeights {
+    // input_size x (gates * hidden_size)
+    input: Matrix,
+    // hidden_size x (gates * hidden_size)
+    recurrent: Matrix,
+    // 1 x (gates * hidden_size)
+    biases: Matrix,
+    input_gradients: Matrix,
+    recurrent_gradients: Matrix,
+    bias_gradients: Matrix,
+}
+
+impl GateWeights {
+    fn new(input_size: usize, hidden_size: usize, gates: usize) -> GateWeights {
+        if input_size == 0 || hidden_size == 0 {
+            panic!("Input and hidden sizes must be at least 1!");
+        }
+        let width = gates * hidden_size;
+        GateWeights {
+            input: zeros(input_size, width),
+            recurrent: zeros(hidden_size, width),
+            biases: zeros(1, width),
+            input_gradients: zeros(input_size, width),
+            recurrent_gradients: zeros(hidden_size, width),
+            bias_gradients: zeros(1, width),
+        }
+    }
+
+    // Weights uniform in +-1 / sqrt(hidden_size), biases zero
+    fn initialize(&mut self, rng: &mut StdRng) {
+        let (input_size, width) = self.input.size();
+        let hidden_size = self.recurrent.size().0;
+        let bound = 1.0 / (hidden_size as f64).sqrt();
+        self.input = uniform(input_size, width, bound, rng);
+        self.recurrent = uniform(hidden_size, width, bound, rng);
+        self.biases.map(|_| 0.0);
+    }
+
+    fn project_input(&self, inputs: &Matrix) -> Matrix {
+        return inputs.dot_prod(&self.input).add_row(&self.biases);
+    }
+
+    fn project_hidden(&self, hidden: &Matrix) -> Matrix {
+        return hidden.dot_prod(&self.recurrent);
+    }
+
+    // Accumulates parameter gradients given the gradients of both projections
+    // and returns the gradients of the inputs and the previous hidden state
+    fn backward(&mut self, inputs: &Matrix, hidden: &Matrix,
+                input_projection: &Matrix, hidden_projection: &Matrix) -> (Matrix, Matrix) {
+        self.input_gradients = self.input_gradients.add(&inputs.transpose().dot_prod(input_projection));
+        self.recurrent_gradients = self.recurrent_gradients.add(&hidden.transpose().dot_prod(hidden_projection));
+        self.bias_gradients = self.bias_gradients.add(&input_projection.sum_rows());
+        return (input_projection.dot_prod(&self.input.transpose()),
+                hidden_projection.dot_prod(&self.recurrent.transpose()));
+    }
+
+    fn parameters(&self) -> Vec<&Matrix> {
+        return vec![&self.input, &self.recurrent, &self.biases];
+    }
+
+    fn gradients(&self) -> Vec<&Matrix> {
+        return vec![&self.input_gradients, &self.recurrent_gradients, &self.bias_gradients];
+    }
+
+    fn set_parameters(&mut self, parameters: Vec<Matrix>) {
+        if parameters.len() != 3 ||
+           parameters.iter().zip(self.parameters()).any(|(new, old)| new.size() != old.size()) {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        let mut parameters = parameters.into_iter();
+        self.input = parameters.next().unwrap();
+        self.recurrent = parameters.next().unwrap();
+        self.biases = parameters.next().unwrap();
+    }
+
+    fn update(&mut self, learning_rate: f64) {
+        let step = |gradients: &Matrix| mapped(gradients, |g| learning_rate * g);
+        self.input = self.input.subtract(&step(&self.input_gradients));
+        self.recurrent = self.recurrent.subtract(&step(&self.recurrent_gradients));
+        self.biases = self.biases.subtract(&step(&self.bias_gradients));
+        self.input_gradients.map(|_| 0.0);
+        self.recurrent_gradients.map(|_| 0.0);
+        self.bias_gradients.map(|_| 0.0);
+    }
+}
+
+// The final state of each forward pass is carried over as the initial state
+// of the next one until `reset_state`, so a long sequence can be fed in
+// chunks. Backward only reaches back to the start of the last chunk, which is
+// what truncated backpropagation through time relies on.
+pub trait RecurrentLayer {
+    fn input_size(&self) -> usize;
+
+    fn hidden_size(&self) -> usize;
+
+    // The hidden output at every step
+    fn forward(&mut self, inputs: &Sequence) -> Sequence;
+
+    // Backpropagation through time over the last forward pass. Accumulates
+    // parameter gradients and returns the gradients of the inputs.
+    fn backward(&mut self, output_gradients: &Sequence) -> Sequence;
+
+    // Input weights, recurrent weights and biases, with gates side by side
+    fn parameters(&self) -> Vec<&Matrix>;
+
+    // Gradients accumulated by `backward` since the last `update`
+    fn gradients(&self) -> Vec<&Matrix>;
+
+    fn set_parameters(&mut self, parameters: Vec<Matrix>);
+
+    // Redraws the parameters from `rng`, so a seeded generator gives a
+    // reproducible layer
+    fn initialize(&mut self, rng: &mut StdRng);
+
+    fn update(&mut self, learning_rate: f64);
+
+    // Hidden state carried between forward passes (and the cell state for LSTM)
+    fn get_state(&self) -> Vec<Matrix>;
+
+    fn set_state(&mut self, state: Vec<Matrix>);
+
+    fn reset_state(&mut self);
+}
+
+// A stored state is only reused when it matches the incoming batch size
+fn initial_state(state: &Vec<Matrix>, count: usize, batch_size: usize, hidden_size: usize) -> Vec<Matrix> {
+    if state.len() == count && state[0].size() == (batch_size, hidden_size) {
+        return state.iter().map(|s| s.clone()).collect();
+    }
+    return (0..count).map(|_| zeros(batch_size, hidden_size)).collect();
+}
+
+fn check_inputs(inputs: &Sequence, input_size: usize) -> usize {
+    if inputs.is_empty() {
+        panic!("Sequence must have at least one step!");
+    }
+    let batch_size = inputs[0].size().0;
+    if inputs.iter().any(|step| step.size() != (batch_size, input_size)) {
+        panic!("Incompatible Matrix Dimensions!");
+    }
+    return batch_size;
+}
+
+fn check_gradients(gradients: &Sequence, steps: usize) {
+    if steps == 0 {
+        panic!("Forward must be called before backward!");
+    }
+    if gradients.len() != steps {
+        panic!("Incompatible Sequence Length!");
+    }
+}
+
+struct ElmanStep {
+    inputs: Matrix,
+    previous: Matrix,
+    hidden: Matrix,
+}
+
+// Elman network: h = tanh(x Wx + h_prev Wh + b)
+pub struct RNN {
+    input_size: usize,
+    hidden_size: usize,
+    weights: GateWeights,
+    state: Vec<Matrix>,
+    steps: Vec<ElmanStep>,
+}
+
+impl RNN {
+    pub fn new(input_size: usize, hidden_size: usize) -> RNN {
+        let mut rnn = RNN {
+            input_size,
+            hidden_size,
+            weights: GateWeights::new(input_size, hidden_size, 1),
+            state: Vec::new(),
+            steps: Vec::new(),
+        };
+        rnn.initialize(&mut StdRng::from_entropy());
+        return rnn;
+    }
+}
+
+impl RecurrentLayer for RNN {
+    fn input_size(&self) -> usize {
+        return self.input_size;
+    }
+
+    fn hidden_size(&self) -> usize {
+        return self.hidden_size;
+    }
+
+    fn forward(&mut self, inputs: &Sequence) -> Sequence {
+        let batch_size = check_inputs(inputs, self.input_size);
+        let mut hidden = initial_state(&self.state, 1, batch_size, self.hidden_size).remove(0);
+        self.steps.clear();
+        let mut outputs: Sequence = Vec::new();
+        for x in inputs {
+            let activation = self.weights.project_input(x).add(&self.weights.project_hidden(&hidden));
+            let next = mapped(&activation, f64::tanh);
+            self.steps.push(ElmanStep { inputs: x.clone(), previous: hidden, hidden: next.clone() });
+            outputs.push(next.clone());
+            hidden = next;
+        }
+        self.state = vec![hidden];
+        return outputs;
+    }
+
+    fn backward(&mut self, output_gradients: &Sequence) -> Sequence {
+        check_gradients(output_gradients, self.steps.len());
+        let mut input_gradients: Sequence = Vec::new();
+        let mut hidden_gradient = zeros(output_gradients[0].size().0, self.hidden_size);
+        for (step, gradient) in self.steps.iter().zip(output_gradients).rev() {
+            let total = gradient.add(&hidden_gradient);
+            let activation = total.hadamard(&tanh_prime(&step.hidden));
+            let (input_gradient, previous_gradient) =
+                self.weights.backward(&step.inputs, &step.previous, &activation, &activation);
+            input_gradients.push(input_gradient);
+            hidden_gradient = previous_gradient;
+        }
+        input_gradients.reverse();
+        return input_gradients;
+    }
+
+    fn parameters(&self) -> Vec<&Matrix> {
+        return self.weights.parameters();
+    }
+
+    fn gradients(&self) -> Vec<&Matrix> {
+        return self.weights.gradients();
+    }
+
+    fn set_parameters(&mut self, parameters: Vec<Matrix>) {
+        self.weights.set_parameters(parameters);
+    }
+
+    fn initialize(&mut self, rng: &mut StdRng) {
+        self.weights.initialize(rng);
+    }
+
+    fn update(&mut self, learning_rate: f64) {
+        self.weights.update(learning_rate);
+    }
+
+    fn get_state(&self) -> Vec<Matrix> {
+        return self.state.iter().map(|s| s.clone()).collect();
+    }
+
+    fn set_state(&mut self, state: Vec<Matrix>) {
+        if state.len() != 1 {
+            panic!("RNN state is a single hidden matrix!");
+        }
+        self.state = state;
+    }
+
+    fn reset_state(&mut self) {
+        self.state.clear();
+    }
+}
+
+struct LSTMStep {
+    inputs: Matrix,
+    previous_hidden: Matrix,
+    previous_cell: Matrix,
+    input_gate: Matrix,
+    forget_gate: Matrix,
+    candidate: Matrix,
+    output_gate: Matrix,
+    cell_tanh: Matrix,
+}
+
+// Long short-term memory with input, forget, candidate and output gates laid
+// out in that order
+pub struct LSTM {
+    input_size: usize,
+    hidden_size: usize,
+    weights: GateWeights,
+    state: Vec<Matrix>,
+    steps: Vec<LSTMStep>,
+}
+
+impl LSTM {
+    pub fn new(input_size: usize, hidden_size: usize) -> LSTM {
+        let mut lstm = LSTM {
+            input_size,
+            hidden_size,
+            weights: GateWeights::new(input_size, hidden_size, 4),
+            state: Vec::new(),
+            steps: Vec::new(),
+        };
+        lstm.initialize(&mut StdRng::from_entropy());
+        return lstm;
+    }
+}
+
+impl RecurrentLayer for LSTM {
+    fn input_size(&self) -> usize {
+        return self.input_size;
+    }
+
+    fn hidden_size(&self) -> usize {
+        return self.hidden_size;
+    }
+
+    fn forward(&mut self, inputs: &Sequence) -> Sequence {
+        let batch_size = check_inputs(inputs, self.input_size);
+        let size = self.hidden_size;
+        let mut state = initial_state(&self.state, 2, batch_size, size);
+        let mut cell = state.pop().unwrap();
+        let mut hidden = state.pop().unwrap();
+        self.steps.clear();
+        let mut outputs: Sequence = Vec::new();
+        for x in inputs {
+            let activation = self.weights.project_input(x).add(&self.weights.project_hidden(&hidden));
+            let input_gate = mapped(&activation.get_columns(0, size), sigmoid);
+            let forget_gate = mapped(&activation.get_columns(size, size), sigmoid);
+            let candidate = mapped(&activation.get_columns(2 * size, size), f64::tanh);
+            let output_gate = mapped(&activation.get_columns(3 * size, size), sigmoid);
+
+            let next_cell = forget_gate.hadamard(&cell).add(&input_gate.hadamard(&candidate));
+            let cell_tanh = mapped(&next_cell, f64::tanh);
+            let next_hidden = output_gate.hadamard(&cell_tanh);
+            self.steps.push(LSTMStep {
+                inputs: x.clone(),
+                previous_hidden: hidden,
+                previous_cell: cell,
+                input_gate,
+                forget_gate,
+                candidate,
+                output_gate,
+                cell_tanh,
+            });
+            outputs.push(next_hidden.clone());
+            hidden = next_hidden;
+            cell = next_cell;
+        }
+        self.state = vec![hidden, cell];
+        return outputs;
+    }
+
+    fn backward(&mut self, output_gradients: &Sequence) -> Sequence {
+        check_gradients(output_gradients, self.steps.len());
+        let batch_size = output_gradients[0].size().0;
+        let mut input_gradients: Sequence = Vec::new();
+        let mut hidden_gradient = zeros(batch_size, self.hidden_size);
+        let mut cell_gradient = zeros(batch_size, self.hidden_size);
+        for (step, gradient) in self.steps.iter().zip(output_gradients).rev() {
+            let total = gradient.add(&hidden_gradient);
+            let output_gate_gradient = total.hadamard(&step.cell_tanh);
+            let cell_total = cell_gradient.add(&total.hadamard(&step.output_gate).hadamard(&tanh_prime(&step.cell_tanh)));
+
+            let activation = Matrix::concat_columns(&vec![
+                cell_total.hadamard(&step.candidate).hadamard(&sigmoid_prime(&step.input_gate)),
+                cell_total.hadamard(&step.previous_cell).hadamard(&sigmoid_prime(&step.forget_gate)),
+                cell_total.hadamard(&step.input_gate).hadamard(&tanh_prime(&step.candidate)),
+                output_gate_gradient.hadamard(&sigmoid_prime(&step.output_gate)),
+            ]);
+            let (input_gradient, previous_gradient) =
+                self.weights.backward(&step.inputs, &step.previous_hidden, &activation, &activation);
+            input_gradients.push(input_gradient);
+            hidden_gradient = previous_gradient;
+            cell_gradient = cell_total.hadamard(&step.forget_gate);
+        }
+        input_gradients.reverse();
+        return input_gradients;
+    }
+
+    fn parameters(&self) -> Vec<&Matrix> {
+        return self.weights.parameters();
+    }
+
+    fn gradients(&self) -> Vec<&Matrix> {
+        return self.weights.gradients();
+    }
+
+    fn set_parameters(&mut self, parameters: Vec<Matrix>) {
+        self.weights.set_parameters(parameters);
+    }
+
+    fn initialize(&mut self, rng: &mut StdRng) {
+        self.weights.initialize(rng);
+        // A forget bias of one keeps the cell state flowing early in training
+        for c in self.hidden_size..(2 * self.hidden_size) {
+            self.weights.biases.set_at_index(0, c, 1.0);
+        }
+    }
+
+    fn update(&mut self, learning_rate: f64) {
+        self.weights.update(learning_rate);
+    }
+
+    // The hidden state followed by the cell state
+    fn get_state(&self) -> Vec<Matrix> {
+        return self.state.iter().map(|s| s.clone()).collect();
+    }
+
+    fn set_state(&mut self, state: Vec<Matrix>) {
+        if state.len() != 2 || state[0].size() != state[1].size() {
+            panic!("LSTM state is a hidden and a cell matrix of the same size!");
+        }
+        self.state = state;
+    }
+
+    fn reset_state(&mut self) {
+        self.state.clear();
+    }
+}
+
+struct GRUStep {
+    inputs: Matrix,
+    previous: Matrix,
+    reset_gate: Matrix,
+    update_gate: Matrix,
+    candidate: Matrix,
+    // Recurrent projection of the candidate before the reset gate is applied
+    candidate_recurrent: Matrix,
+}
+
+// Gated recurrent unit with reset, update and candidate gates laid out in
+// that order:
+//   n = tanh(x Wn + bn + r * (h_prev Un))
+//   h = (1 - z) * n + z * h_prev
+pub struct GRU {
+    input_size: usize,
+    hidden_size: usize,
+    weights: GateWeights,
+    state: Vec<Matrix>,
+    steps: Vec<GRUStep>,
+}
+
+impl GRU {
+    pub fn new(input_size: usize, hidden_size: usize) -> GRU {
+        let mut gru = GRU {
+            input_size,
+            hidden_size,
+            weights: GateWeights::new(input_size, hidden_size, 3),
+            state: Vec::new(),
+            steps: Vec::new(),
+        };
+        gru.initialize(&mut StdRng::from_entropy());
+        return gru;
+    }
+}
+
+impl RecurrentLayer for GRU {
+    fn input_size(&self) -> usize {
+        return self.input_size;
+    }
+
+    fn hidden_size(&self) -> usize {
+        return self.hidden_size;
+    }
+
+    fn forward(&mut self, inputs: &Sequence) -> Sequence {
+        let batch_size = check_inputs(inputs, self.input_size);
+        let size = self.hidden_size;
+        let mut hidden = initial_state(&self.state, 1, batch_size, size).remove(0);
+        self.steps.clear();
+        let mut outputs: Sequence = Vec::new();
+        for x in inputs {
+            let input_projection = self.weights.project_input(x);
+            let hidden_projection = self.weights.project_hidden(&hidden);
+            let gates = input_projection.get_columns(0, 2 * size).add(&hidden_projection.get_columns(0, 2 * size));
+            let reset_gate = mapped(&gates.get_columns(0, size), sigmoid);
+            let update_gate = mapped(&gates.get_columns(size, size), sigmoid);
+            let candidate_recurrent = hidden_projection.get_columns(2 * size, size);
+            let candidate = mapped(&input_projection.get_columns(2 * size, size)
+                .add(&reset_gate.hadamard(&candidate_recurrent)), f64::tanh);
+
+            let next = one_minus(&update_gate).hadamard(&candidate).add(&update_gate.hadamard(&hidden));
+            self.steps.push(GRUStep {
+                inputs: x.clone(),
+                previous: hidden,
+                reset_gate,
+                update_gate,
+                candidate,
+                candidate_recurrent,
+            });
+            outputs.push(next.clone());
+            hidden = next;
+        }
+        self.state = vec![hidden];
+        return outputs;
+    }
+
+    fn backward(&mut self, output_gradients: &Sequence) -> Sequence {
+        check_gradients(output_gradients, self.steps.len());
+        let mut input_gradients: Sequence = Vec::new();
+        let mut hidden_gradient = zeros(output_gradients[0].size().0, self.hidden_size);
+        for (step, gradient) in self.steps.iter().zip(output_gradients).rev() {
+            let total = gradient.add(&hidden_gradient);
+            let candidate_gradient = total.hadamard(&one_minus(&step.update_gate))
+                .hadamard(&tanh_prime(&step.candidate));
+            let update_gradient = total.hadamard(&step.previous.subtract(&step.candidate))
+                .hadamard(&sigmoid_prime(&step.update_gate));
+            let reset_gradient = candidate_gradient.hadamard(&step.candidate_recurrent)
+                .hadamard(&sigmoid_prime(&step.reset_gate));
+
+            // The reset gate only scales the recurrent half of the candidate
+            let input_projection = Matrix::concat_columns(&vec![reset_gradient.clone(), update_gradient.clone(), candidate_gradient.clone()]);
+            let hidden_projection = Matrix::concat_columns(&vec![reset_gradient, update_gradient, candidate_gradient.hadamard(&step.reset_gate)]);
+            let (input_gradient, previous_gradient) =
+                self.weights.backward(&step.inputs, &step.previous, &input_projection, &hidden_projection);
+            input_gradients.push(input_gradient);
+            hidden_gradient = previous_gradient.add(&total.hadamard(&step.update_gate));
+        }
+        input_gradients.reverse();
+        return input_gradients;
+    }
+
+    fn parameters(&self) -> Vec<&Matrix> {
+        return self.weights.parameters();
+    }
+
+    fn gradients(&self) -> Vec<&Matrix> {
+        return self.weights.gradients();
+    }
+
+    fn set_parameters(&mut self, parameters: Vec<Matrix>) {
+        self.weights.set_parameters(parameters);
+    }
+
+    fn initialize(&mut self, rng: &mut StdRng) {
+        self.weights.initialize(rng);
+    }
+
+    fn update(&mut self, learning_rate: f64) {
+        self.weights.update(learning_rate);
+    }
+
+    fn get_state(&self) -> Vec<Matrix> {
+        return self.state.iter().map(|s| s.clone()).collect();
+    }
+
+    fn set_state(&mut self, state: Vec<Matrix>) {
+        if state.len() != 1 {
+            panic!("GRU state is a single hidden matrix!");
+        }
+        self.state = state;
+    }
+
+    fn reset_state(&mut self) {
+        self.state.clear();
+    }
+}
+
+pub enum Cell {
+    RNN,
+    LSTM,
+    GRU,
+}
+
+// Stacks sequences of equal length into time-major batches
+pub fn to_batch(sequences: &Vec<Vec<Vec<f64>>>) -> Sequence {
+    if sequences.is_empty() || sequences[0].is_empty() {
+        panic!("Batch must have at least one non-empty sequence!");
+    }
+    let steps = sequences[0].len();
+    let features = sequences[0][0].len();
+    if sequences.iter().any(|sequence| sequence.len() != steps) {
+        panic!("Sequences in a batch must have the same length!");
+    }
+    let mut batch: Sequence = Vec::new();
+    for t in 0..steps {
+        let mut data: Vec<f64> = Vec::new();
+        for sequence in sequences {
+            if sequence[t].len() != features {
+                panic!("Incompatible Vector Dimensions!");
+            }
+            data.extend(&sequence[t]);
+        }
+        batch.push(Matrix::from_vec(&data, sequences.len(), features));
+    }
+    return batch;
+}
+
+// A recurrent layer followed by a dense readout applied at every step
+// (many-to-many), trained on the loss averaged over the steps
+pub struct RecurrentNetwork {
+    layer: Box<dyn RecurrentLayer>,
+    // hidden_size x num_outputs
+    output_weights: Matrix,
+    output_biases: Matrix,
+    output_weight_gradients: Matrix,
+    output_bias_gradients: Matrix,
+    loss: Loss,
+    batch_size: usize,
+    // Steps per truncated BPTT window; 0 backpropagates through whole sequences
+    truncation: usize,
+    // Carry the hidden state from one batch (or prediction) to the next, for
+    // data that is one long stream cut into consecutive pieces
+    stateful: bool,
+}
+
+impl RecurrentNetwork {
+    pub fn new(cell: Cell, num_inputs: usize, hidden_size: usize, num_outputs: usize) -> RecurrentNetwork {
+        if num_outputs == 0 {
+            panic!("Network must have at least one output!");
+        }
+        let layer: Box<dyn RecurrentLayer> = match cell {
+            Cell::RNN => Box::new(RNN::new(num_inputs, hidden_size)),
+            Cell::LSTM => Box::new(LSTM::new(num_inputs, hidden_size)),
+            Cell::GRU => Box::new(GRU::new(num_inputs, hidden_size)),
+        };
+        let mut network = RecurrentNetwork {
+            layer,
+            output_weights: zeros(hidden_size, num_outputs),
+            output_biases: zeros(1, num_outputs),
+            output_weight_gradients: zeros(hidden_size, num_outputs),
+            output_bias_gradients: zeros(1, num_outputs),
+            loss: Loss::MeanSquaredError,
+            batch_size: 1,
+            truncation: 0,
+            stateful: false,
+        };
+        network.initialize(&mut StdRng::from_entropy());
+        return network;
+    }
+
+    // Redraws the recurrent layer and the readout from `rng`
+    pub fn initialize(&mut self, rng: &mut StdRng) {
+        self.layer.initialize(rng);
+        let (hidden_size, num_outputs) = self.output_weights.size();
+        self.output_weights = uniform(hidden_size, num_outputs, 1.0 / (hidden_size as f64).sqrt(), rng);
+        self.output_biases.map(|_| 0.0);
+    }
+
+    // Redraws every weight from a generator seeded with `seed`, so the same
+    // seed and data give the same trained network
+    pub fn set_seed(&mut self, seed: u64) {
+        self.initialize(&mut StdRng::seed_from_u64(seed));
+    }
+
+    pub fn set_loss(&mut self, loss: Loss) {
+        self.loss = loss;
+    }
+
+    // Sequences within a batch must have the same length
+    pub fn set_batch_size(&mut self, batch_size: usize) {
+        if batch_size == 0 {
+            panic!("Batch size must be at least 1!");
+        }
+        self.batch_size = batch_size;
+    }
+
+    pub fn set_truncation(&mut self, steps: usize) {
+        self.truncation = steps;
+    }
+
+    pub fn set_stateful(&mut self, stateful: bool) {
+        self.stateful = stateful;
+    }
+
+    pub fn get_layer(&self) -> &dyn RecurrentLayer {
+        return self.layer.as_ref();
+    }
+
+    pub fn get_output_weights(&self) -> &Matrix {
+        return &self.output_weights;
+    }
+
+    pub fn reset_state(&mut self) {
+        self.layer.reset_state();
+    }
+
+    fn readout(&self, hidden: &Matrix) -> Matrix {
+        return hidden.dot_prod(&self.output_weights).add_row(&self.output_biases);
+    }
+
+    // Forward and backward over one window on the loss averaged over its
+    // steps, returning the summed step losses
+    fn train_window(&mut self, inputs: &[Matrix], targets: &[Matrix]) -> f64 {
+        let steps = inputs.len() as f64;
+        let hidden = self.layer.forward(&inputs.iter().map(|m| m.clone()).collect());
+        let mut total = 0.0;
+        let mut hidden_gradients: Sequence = Vec::new();
+        for (h, target) in hidden.iter().zip(targets) {
+            let outputs = self.readout(h);
+            total += self.loss.compute(&outputs, target);
+            let mut gradient = self.loss.gradient(&outputs, target);
+            gradient.map(|g| g / steps);
+            self.output_weight_gradients = self.output_weight_gradients.add(&h.transpose().dot_prod(&gradient));
+            self.output_bias_gradients = self.output_bias_gradients.add(&gradient.sum_rows());
+            hidden_gradients.push(gradient.dot_prod(&self.output_weights.transpose()));
+        }
+        self.layer.backward(&hidden_gradients);
+        return total;
+    }
+
+    fn update(&mut self, learning_rate: f64) {
+        self.layer.update(learning_rate);
+        self.output_weights = self.output_weights.subtract(&mapped(&self.output_weight_gradients, |g| learning_rate * g));
+        self.output_biases = self.output_biases.subtract(&mapped(&self.output_bias_gradients, |g| learning_rate * g));
+        self.output_weight_gradients.map(|_| 0.0);
+        self.output_bias_gradients.map(|_| 0.0);
+    }
+
+    // Returns the average loss per step for each epoch
+    pub fn train(&mut self, data: &SequenceData, learning_rate: f64, epochs: usize) -> Vec<f64> {
+        let mut losses: Vec<f64> = Vec::new();
+        for _ in 0..epochs {
+            let mut total = 0.0;
+            let mut count = 0;
+            self.layer.reset_state();
+            for batch in data.chunks(self.batch_size) {
+                let inputs = to_batch(&batch.iter().map(|pair| pair.0.clone()).collect());
+                let targets = to_batch(&batch.iter().map(|pair| pair.1.clone()).collect());
+                if inputs.len() != targets.len() {
+                    panic!("Inputs and targets must have the same number of steps!");
+                }
+                if !self.stateful {
+                    self.layer.reset_state();
+                }
+                let window = if self.truncation == 0 { inputs.len() } else { self.truncation };
+                let mut start = 0;
+                while start < inputs.len() {
+                    let end = (start + window).min(inputs.len());
+                    total += self.train_window(&inputs[start..end], &targets[start..end]);
+                    count += end - start;
+                    self.update(learning_rate);
+                    start = end;
+                }
+            }
+            losses.push(total / count as f64);
+        }
+        return losses;
+    }
+
+    fn outputs(&mut self, inputs: &Vec<Vec<f64>>) -> Sequence {
+        if !self.stateful {
+            self.layer.reset_state();
+        }
+        let hidden = self.layer.forward(&to_batch(&vec![inputs.clone()]));
+        return hidden.iter().map(|h| self.readout(h)).collect();
+    }
+
+    // Output at every step of a single sequence; class probabilities when
+    // training with cross-entropy
+    pub fn predict(&mut self, inputs: &Vec<Vec<f64>>) -> Vec<Vec<f64>> {
+        let outputs = self.outputs(inputs);
+        return outputs.iter().map(|o| match self.loss {
+            Loss::MeanSquaredError => o.as_vec(),
+            Loss::CrossEntropy => softmax(&o.as_vec()),
+        }).collect();
+    }
+
+    // Average loss per step over the data
+    pub fn evaluate(&mut self, data: &SequenceData) -> f64 {
+        let mut total = 0.0;
+        let mut count = 0;
+        for (inputs, targets) in data {
+            let outputs = self.outputs(inputs);
+            let targets = to_batch(&vec![targets.clone()]);
+            if outputs.len() != targets.len() {
+                panic!("Inputs and targets must have the same number of steps!");
+            }
+            for (output, target) in outputs.iter().zip(&targets) {
+                total += self.loss.compute(output, target);
+            }
+            count += outputs.len();
+        }
+        return total / count as f64;
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_recurrent.rs"]
+mod test;
@@ -0,0 +1,974 @@
+extern crate rand;
+
+use rand::{Rng, SeedableRng, FromEntropy};
+use rand::rngs::StdRng;
+
+use super::matrix::Matrix;
+use super::math::argmax;
+use super::neural_network::{NeuralNetwork, TrainingData};
+
+// Something an agent can act in. `step` returns the next state, the reward
+// for the transition, whether the episode reached a terminal state and
+// whether it was cut off before reaching one, e.g. by a step limit. Only a
+// terminal state ends the returns; a cut off episode could have gone on, so
+// agents still bootstrap from its last state.
+pub trait Environment {
+    type State;
+
+    fn num_actions(&self) -> usize;
+    fn reset(&mut self) -> Self::State;
+    fn step(&mut self, action: usize) -> (Self::State, f64, bool, bool);
+}
+
+// An environment with finitely many states numbered from zero, as needed by
+// the tabular agents
+pub trait DiscreteEnvironment: Environment<State = usize> {
+    fn num_states(&self) -> usize;
+}
+
+// Grid moves shared by the toy environments
+pub const UP: usize = 0;
+pub const RIGHT: usize = 1;
+pub const DOWN: usize = 2;
+pub const LEFT: usize = 3;
+
+const ARROWS: [char; 4] = ['^', '>', 'v', '<'];
+
+fn move_on_grid(x: usize, y: usize, action: usize, width: usize, height: usize) -> (usize, usize) {
+    return match action {
+        UP => (x, if y > 0 { y - 1 } else { y }),
+        RIGHT => (if x + 1 < width { x + 1 } else { x }, y),
+        DOWN => (x, if y + 1 < height { y + 1 } else { y }),
+        LEFT => (if x > 0 { x - 1 } else { x }, y),
+        _ => panic!("Action out of range!"),
+    };
+}
+
+// Rectangular maze: the agent pays 1 for every move and the episode ends on
+// reaching the goal. By default it starts in the top left corner and the goal
+// is the bottom right one. Moves into a wall or off the grid leave the agent
+// where it is.
+pub struct GridWorld {
+    width: usize,
+    height: usize,
+    walls: Vec<bool>,
+    start: usize,
+    goal: usize,
+    max_steps: usize,
+    position: usize,
+    steps: usize,
+}
+
+impl GridWorld {
+    pub fn new(width: usize, height: usize) -> GridWorld {
+        if width * height < 2 {
+            panic!("The grid needs at least two cells!");
+        }
+        GridWorld {
+            width,
+            height,
+            walls: vec![false; width * height],
+            start: 0,
+            goal: width * height - 1,
+            max_steps: 100,
+            position: 0,
+            steps: 0,
+        }
+    }
+
+    pub fn add_wall(&mut self, x: usize, y: usize) {
+        let cell = self.cell(x, y);
+        if cell == self.start || cell == self.goal {
+            panic!("Cannot place a wall on the start or the goal!");
+        }
+        self.walls[cell] = true;
+    }
+
+    pub fn set_start(&mut self, x: usize, y: usize) {
+        self.start = self.cell(x, y);
+    }
+
+    pub fn set_goal(&mut self, x: usize, y: usize) {
+        self.goal = self.cell(x, y);
+    }
+
+    // Episodes that have not reached the goal are cut off after this many moves
+    pub fn set_max_steps(&mut self, max_steps: usize) {
+        self.max_steps = max_steps;
+    }
+
+    fn cell(&self, x: usize, y: usize) -> usize {
+        if x >= self.width || y >= self.height {
+            panic!("Cell out of range!");
+        }
+        return y * self.width + x;
+    }
+
+    // Draws the grid with one arrow per cell for the given action choices
+    pub fn render_policy(&self, policy: &Vec<usize>) -> String {
+        let mut result = String::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let cell = y * self.width + x;
+                result.push(if self.walls[cell] {
+                    '#'
+                } else if cell == self.goal {
+                    'G'
+                } else {
+                    ARROWS[policy[cell]]
+                });
+            }
+            result.push('\n');
+        }
+        return result;
+    }
+}
+
+impl Environment for GridWorld {
+    type State = usize;
+
+    fn num_actions(&self) -> usize {
+        return 4;
+    }
+
+    fn reset(&mut self) -> usize {
+        self.position = self.start;
+        self.steps = 0;
+        return self.position;
+    }
+
+    fn step(&mut self, action: usize) -> (usize, f64, bool, bool) {
+        let (x, y) = move_on_grid(self.position % self.width, self.position / self.width, action, self.width, self.height);
+        let next = y * self.width + x;
+        if !self.walls[next] {
+            self.position = next;
+        }
+        self.steps += 1;
+        let terminated = self.position == self.goal;
+        let truncated = !terminated && self.steps >= self.max_steps;
+        return (self.position, -1.0, terminated, truncated);
+    }
+}
+
+impl DiscreteEnvironment for GridWorld {
+    fn num_states(&self) -> usize {
+        return self.width * self.height;
+    }
+}
+
+// Frozen lake: walk from S to G across frozen tiles (F) without falling into a
+// hole (H). Reaching the goal pays 1 and everything else pays nothing. On
+// slippery ice the agent only moves in the chosen direction a third of the
+// time and slides to either side otherwise.
+pub struct FrozenLake {
+    width: usize,
+    height: usize,
+    tiles: Vec<char>,
+    slippery: bool,
+    max_steps: usize,
+    position: usize,
+    steps: usize,
+    rng: StdRng,
+}
+
+impl FrozenLake {
+    // The standard 4x4 map
+    pub fn new() -> FrozenLake {
+        return FrozenLake::from_map(&vec!["SFFF", "FHFH", "FFFH", "HFFG"]);
+    }
+
+    pub fn from_map(rows: &Vec<&str>) -> FrozenLake {
+        let height = rows.len();
+        let width = if height > 0 { rows[0].len() } else { 0 };
+        let tiles: Vec<char> = rows.iter().flat_map(|row| row.chars()).collect();
+        if width == 0 || rows.iter().any(|row| row.len() != width) {
+            panic!("Map rows must all have the same length!");
+        }
+        if tiles.iter().any(|tile| !"SFHG".contains(*tile)) {
+            panic!("Map tiles must be one of S, F, H or G!");
+        }
+        if tiles.iter().filter(|&&tile| tile == 'S').count() != 1 {
+            panic!("Map must have exactly one start!");
+        }
+        let start = tiles.iter().position(|&tile| tile == 'S').unwrap();
+        FrozenLake {
+            width,
+            height,
+            tiles,
+            slippery: false,
+            max_steps: 100,
+            position: start,
+            steps: 0,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    pub fn set_slippery(&mut self, slippery: bool) {
+        self.slippery = slippery;
+    }
+
+    // Makes the slides on slippery ice reproducible
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    pub fn set_max_steps(&mut self, max_steps: usize) {
+        self.max_steps = max_steps;
+    }
+
+    pub fn get_tile(&self, state: usize) -> char {
+        return self.tiles[state];
+    }
+
+    pub fn render_policy(&self, policy: &Vec<usize>) -> String {
+        let mut result = String::new();
+        for (cell, &tile) in self.tiles.iter().enumerate() {
+            result.push(if tile == 'H' || tile == 'G' { tile } else { ARROWS[policy[cell]] });
+            if (cell + 1) % self.width == 0 {
+                result.push('\n');
+            }
+        }
+        return result;
+    }
+}
+
+impl Environment for FrozenLake {
+    type State = usize;
+
+    fn num_actions(&self) -> usize {
+        return 4;
+    }
+
+    fn reset(&mut self) -> usize {
+        self.position = self.tiles.iter().position(|&tile| tile == 'S').unwrap();
+        self.steps = 0;
+        return self.position;
+    }
+
+    fn step(&mut self, action: usize) -> (usize, f64, bool, bool) {
+        if action >= 4 {
+            panic!("Action out of range!");
+        }
+        let direction = if self.slippery {
+            match self.rng.gen_range(0, 3) {
+                0 => (action + 3) % 4,
+                1 => action,
+                _ => (action + 1) % 4,
+            }
+        } else {
+            action
+        };
+        let (x, y) = move_on_grid(self.position % self.width, self.position / self.width, direction, self.width, self.height);
+        self.position = y * self.width + x;
+        self.steps += 1;
+
+        let tile = self.tiles[self.position];
+        let reward = if tile == 'G' { 1.0 } else { 0.0 };
+        let terminated = tile == 'G' || tile == 'H';
+        let truncated = !terminated && self.steps >= self.max_steps;
+        return (self.position, reward, terminated, truncated);
+    }
+}
+
+impl DiscreteEnvironment for FrozenLake {
+    fn num_states(&self) -> usize {
+        return self.width * self.height;
+    }
+}
+
+// Cart-pole balancing with the classic dynamics (Barto, Sutton and Anderson,
+// 1983), integrated with Euler steps of 20ms. The state is the cart position
+// and velocity followed by the pole angle and angular velocity. Action 0
+// pushes the cart left and action 1 pushes it right. Every step the pole
+// stays up pays 1, and the episode ends once the pole tips past 12 degrees
+// or the cart leaves the track.
+pub struct CartPole {
+    state: Vec<f64>,
+    max_steps: usize,
+    steps: usize,
+}
+
+const GRAVITY: f64 = 9.8;
+const CART_MASS: f64 = 1.0;
+const POLE_MASS: f64 = 0.1;
+// Half the pole length
+const POLE_LENGTH: f64 = 0.5;
+const FORCE: f64 = 10.0;
+const TIME_STEP: f64 = 0.02;
+const ANGLE_LIMIT: f64 = 12.0 * 2.0 * std::f64::consts::PI / 360.0;
+const POSITION_LIMIT: f64 = 2.4;
+
+impl CartPole {
+    pub fn new() -> CartPole {
+        CartPole {
+            state: vec![0.0; 4],
+            max_steps: 500,
+            steps: 0,
+        }
+    }
+
+    pub fn set_max_steps(&mut self, max_steps: usize) {
+        self.max_steps = max_steps;
+    }
+
+    pub fn get_state(&self) -> &Vec<f64> {
+        return &self.state;
+    }
+
+    // Places the system in a given state, e.g. to replay a scenario
+    pub fn set_state(&mut self, state: Vec<f64>) {
+        if state.len() != 4 {
+            panic!("Incompatible Vector Dimensions!");
+        }
+        self.state = state;
+    }
+
+    fn has_fallen(&self) -> bool {
+        return self.state[0].abs() > POSITION_LIMIT || self.state[2].abs() > ANGLE_LIMIT;
+    }
+}
+
+impl Environment for CartPole {
+    type State = Vec<f64>;
+
+    fn num_actions(&self) -> usize {
+        return 2;
+    }
+
+    // Starts close to upright with small random perturbations
+    fn reset(&mut self) -> Vec<f64> {
+        let mut rng = rand::thread_rng();
+        self.state = (0..4).map(|_| rng.gen_range(-0.05, 0.05)).collect();
+        self.steps = 0;
+        return self.state.clone();
+    }
+
+    fn step(&mut self, action: usize) -> (Vec<f64>, f64, bool, bool) {
+        let force = match action {
+            0 => -FORCE,
+            1 => FORCE,
+            _ => panic!("Action out of range!"),
+        };
+        let (x, x_dot, theta, theta_dot) = (self.state[0], self.state[1], self.state[2], self.state[3]);
+        let (sin, cos) = (theta.sin(), theta.cos());
+        let total_mass = CART_MASS + POLE_MASS;
+        let temp = (force + POLE_MASS * POLE_LENGTH * theta_dot * theta_dot * sin) / total_mass;
+        let theta_acc = (GRAVITY * sin - cos * temp)
+            / (POLE_LENGTH * (4.0 / 3.0 - POLE_MASS * cos * cos / total_mass));
+        let x_acc = temp - POLE_MASS * POLE_LENGTH * theta_acc * cos / total_mass;
+
+        self.state = vec![
+            x + TIME_STEP * x_dot,
+            x_dot + TIME_STEP * x_acc,
+            theta + TIME_STEP * theta_dot,
+            theta_dot + TIME_STEP * theta_acc,
+        ];
+        self.steps += 1;
+        let terminated = self.has_fallen();
+        let truncated = !terminated && self.steps >= self.max_steps;
+        return (self.state.clone(), 1.0, terminated, truncated);
+    }
+}
+
+// Picks a random action with probability epsilon and a greedy one
+// otherwise. Epsilon can shrink geometrically after every episode.
+pub struct EpsilonGreedy {
+    epsilon: f64,
+    decay: f64,
+    min_epsilon: f64,
+}
+
+impl EpsilonGreedy {
+    pub fn new(epsilon: f64) -> EpsilonGreedy {
+        if !(0.0..=1.0).contains(&epsilon) {
+            panic!("Epsilon must be in [0, 1]!");
+        }
+        EpsilonGreedy {
+            epsilon,
+            decay: 1.0,
+            min_epsilon: epsilon,
+        }
+    }
+
+    // Multiply epsilon by `decay` on every call to `decay`, never going
+    // below `min_epsilon`
+    pub fn set_decay(&mut self, decay: f64, min_epsilon: f64) {
+        if decay <= 0.0 || decay > 1.0 {
+            panic!("Decay must be in (0, 1]!");
+        }
+        self.decay = decay;
+        self.min_epsilon = min_epsilon.min(self.epsilon);
+    }
+
+    pub fn get_epsilon(&self) -> f64 {
+        return self.epsilon;
+    }
+
+    pub fn decay(&mut self) {
+        self.epsilon = (self.epsilon * self.decay).max(self.min_epsilon);
+    }
+
+    pub fn choose<R: Rng>(&self, q_values: &Vec<f64>, rng: &mut R) -> usize {
+        if rng.gen_range(0.0, 1.0) < self.epsilon {
+            return rng.gen_range(0, q_values.len());
+        }
+        // Break ties at random, so an untrained table still wanders around
+        // instead of repeating the first action
+        let best = q_values[argmax(q_values)];
+        let ties: Vec<usize> = (0..q_values.len()).filter(|&a| q_values[a] == best).collect();
+        return ties[rng.gen_range(0, ties.len())];
+    }
+}
+
+// Q-table and hyperparameters shared by the tabular agents
+struct TabularAgent {
+    q_table: Matrix,
+    learning_rate: f64,
+    discount: f64,
+    exploration: EpsilonGreedy,
+    rng: StdRng,
+}
+
+impl TabularAgent {
+    fn new(num_states: usize, num_actions: usize) -> TabularAgent {
+        let mut q_table = Matrix::new();
+        q_table.zero_fill(num_states, num_actions);
+        let mut exploration = EpsilonGreedy::new(1.0);
+        exploration.set_decay(0.99, 0.05);
+        TabularAgent {
+            q_table,
+            learning_rate: 0.1,
+            discount: 0.99,
+            exploration,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    fn check_environment<E: DiscreteEnvironment>(&self, environment: &E) {
+        if (environment.num_states(), environment.num_actions()) != self.q_table.size() {
+            panic!("Environment does not match the Q-table!");
+        }
+    }
+
+    fn value(&self, state: usize, action: usize) -> f64 {
+        return self.q_table.get_at_index(state, action);
+    }
+
+    fn max_value(&self, state: usize) -> f64 {
+        return self.q_table.get_row(state).iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    }
+
+    // Moves Q(s, a) a step of size learning_rate towards the target
+    fn move_towards(&mut self, state: usize, action: usize, target: f64) {
+        let current = self.value(state, action);
+        self.q_table.set_at_index(state, action, current + self.learning_rate * (target - current));
+    }
+
+    fn explore(&mut self, state: usize) -> usize {
+        return self.exploration.choose(&self.q_table.get_row(state), &mut self.rng);
+    }
+
+    fn act(&self, state: usize) -> usize {
+        return argmax(&self.q_table.get_row(state));
+    }
+
+    fn policy(&self) -> Vec<usize> {
+        return (0..self.q_table.size().0).map(|state| self.act(state)).collect();
+    }
+
+    // Average undiscounted return of the greedy policy
+    fn evaluate<E: DiscreteEnvironment>(&self, environment: &mut E, episodes: usize) -> f64 {
+        self.check_environment(environment);
+        let mut total = 0.0;
+        for _ in 0..episodes {
+            let mut state = environment.reset();
+            loop {
+                let (next, reward, terminated, truncated) = environment.step(self.act(state));
+                total += reward;
+                state = next;
+                if terminated || truncated {
+                    break;
+                }
+            }
+        }
+        return total / episodes as f64;
+    }
+}
+
+// Off-policy TD control: bootstraps from the best action in the next state,
+// whatever the behaviour policy actually does there
+pub struct QLearning {
+    agent: TabularAgent,
+}
+
+impl QLearning {
+    pub fn new(num_states: usize, num_actions: usize) -> QLearning {
+        QLearning {
+            agent: TabularAgent::new(num_states, num_actions),
+        }
+    }
+
+    pub fn set_learning_rate(&mut self, learning_rate: f64) {
+        self.agent.learning_rate = learning_rate;
+    }
+
+    pub fn set_discount(&mut self, discount: f64) {
+        if !(0.0..=1.0).contains(&discount) {
+            panic!("Discount must be in [0, 1]!");
+        }
+        self.agent.discount = discount;
+    }
+
+    pub fn set_exploration(&mut self, exploration: EpsilonGreedy) {
+        self.agent.exploration = exploration;
+    }
+
+    // Makes the exploration during training reproducible
+    pub fn set_seed(&mut self, seed: u64) {
+        self.agent.rng = StdRng::seed_from_u64(seed);
+    }
+
+    pub fn get_exploration(&self) -> &EpsilonGreedy {
+        return &self.agent.exploration;
+    }
+
+    // num_states x num_actions
+    pub fn get_q_table(&self) -> &Matrix {
+        return &self.agent.q_table;
+    }
+
+    // Greedy action for a state
+    pub fn act(&self, state: usize) -> usize {
+        return self.agent.act(state);
+    }
+
+    // Greedy action for every state
+    pub fn policy(&self) -> Vec<usize> {
+        return self.agent.policy();
+    }
+
+    pub fn evaluate<E: DiscreteEnvironment>(&self, environment: &mut E, episodes: usize) -> f64 {
+        return self.agent.evaluate(environment, episodes);
+    }
+
+    // `terminated` is only set when next_state is terminal; a step that was
+    // merely cut off still bootstraps from next_state
+    pub fn update(&mut self, state: usize, action: usize, reward: f64, next_state: usize, terminated: bool) {
+        let future = if terminated { 0.0 } else { self.agent.discount * self.agent.max_value(next_state) };
+        self.agent.move_towards(state, action, reward + future);
+    }
+
+    // Runs epsilon-greedy episodes, decaying epsilon after each one, and
+    // returns the total reward of every episode
+    pub fn train<E: DiscreteEnvironment>(&mut self, environment: &mut E, episodes: usize) -> Vec<f64> {
+        self.agent.check_environment(environment);
+        let mut returns: Vec<f64> = Vec::new();
+        for _ in 0..episodes {
+            let mut state = environment.reset();
+            let mut total = 0.0;
+            loop {
+                let action = self.agent.explore(state);
+                let (next, reward, terminated, truncated) = environment.step(action);
+                self.update(state, action, reward, next, terminated);
+                total += reward;
+                state = next;
+                if terminated || truncated {
+                    break;
+                }
+            }
+            self.agent.exploration.decay();
+            returns.push(total);
+        }
+        return returns;
+    }
+}
+
+// On-policy TD control: bootstraps from the action the epsilon-greedy policy
+// takes next, so exploration risks are priced into the values
+pub struct SARSA {
+    agent: TabularAgent,
+}
+
+impl SARSA {
+    pub fn new(num_states: usize, num_actions: usize) -> SARSA {
+        SARSA {
+            agent: TabularAgent::new(num_states, num_actions),
+        }
+    }
+
+    pub fn set_learning_rate(&mut self, learning_rate: f64) {
+        self.agent.learning_rate = learning_rate;
+    }
+
+    pub fn set_discount(&mut self, discount: f64) {
+        if !(0.0..=1.0).contains(&discount) {
+            panic!("Discount must be in [0, 1]!");
+        }
+        self.agent.discount = discount;
+    }
+
+    pub fn set_exploration(&mut self, exploration: EpsilonGreedy) {
+        self.agent.exploration = exploration;
+    }
+
+    // Makes the exploration during training reproducible
+    pub fn set_seed(&mut self, seed: u64) {
+        self.agent.rng = StdRng::seed_from_u64(seed);
+    }
+
+    pub fn get_exploration(&self) -> &EpsilonGreedy {
+        return &self.agent.exploration;
+    }
+
+    // num_states x num_actions
+    pub fn get_q_table(&self) -> &Matrix {
+        return &self.agent.q_table;
+    }
+
+    // Greedy action for a state
+    pub fn act(&self, state: usize) -> usize {
+        return self.agent.act(state);
+    }
+
+    // Greedy action for every state
+    pub fn policy(&self) -> Vec<usize> {
+        return self.agent.policy();
+    }
+
+    pub fn evaluate<E: DiscreteEnvironment>(&self, environment: &mut E, episodes: usize) -> f64 {
+        return self.agent.evaluate(environment, episodes);
+    }
+
+    // As for QLearning::update, only a terminal next_state stops bootstrapping
+    pub fn update(&mut self, state: usize, action: usize, reward: f64, next_state: usize, next_action: usize, terminated: bool) {
+        let future = if terminated { 0.0 } else { self.agent.discount * self.agent.value(next_state, next_action) };
+        self.agent.move_towards(state, action, reward + future);
+    }
+
+    pub fn train<E: DiscreteEnvironment>(&mut self, environment: &mut E, episodes: usize) -> Vec<f64> {
+        self.agent.check_environment(environment);
+        let mut returns: Vec<f64> = Vec::new();
+        for _ in 0..episodes {
+            let mut state = environment.reset();
+            let mut action = self.agent.explore(state);
+            let mut total = 0.0;
+            loop {
+                let (next, reward, terminated, truncated) = environment.step(action);
+                let next_action = self.agent.explore(next);
+                self.update(state, action, reward, next, next_action, terminated);
+                total += reward;
+                state = next;
+                action = next_action;
+                if terminated || truncated {
+                    break;
+                }
+            }
+            self.agent.exploration.decay();
+            returns.push(total);
+        }
+        return returns;
+    }
+}
+
+// One step of experience
+#[derive(Clone)]
+pub struct Transition {
+    pub state: Vec<f64>,
+    pub action: usize,
+    pub reward: f64,
+    pub next_state: Vec<f64>,
+    // Whether next_state is terminal. Steps cut off by a time limit are not,
+    // so their targets still bootstrap from next_state.
+    pub terminated: bool,
+}
+
+// Fixed size memory of recent transitions; once full, the oldest entry is
+// overwritten. Sampling from it breaks up the correlation between
+// consecutive steps.
+pub struct ReplayBuffer {
+    capacity: usize,
+    transitions: Vec<Transition>,
+    // Slot the next transition goes into once the buffer is full
+    next: usize,
+}
+
+impl ReplayBuffer {
+    pub fn new(capacity: usize) -> ReplayBuffer {
+        if capacity == 0 {
+            panic!("Capacity must be at least 1!");
+        }
+        ReplayBuffer {
+            capacity,
+            transitions: Vec::new(),
+            next: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        return self.transitions.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.transitions.is_empty();
+    }
+
+    pub fn get_transitions(&self) -> &Vec<Transition> {
+        return &self.transitions;
+    }
+
+    pub fn push(&mut self, transition: Transition) {
+        if self.transitions.len() < self.capacity {
+            self.transitions.push(transition);
+        } else {
+            self.transitions[self.next] = transition;
+        }
+        self.next = (self.next + 1) % self.capacity;
+    }
+
+    // Uniform sample without replacement
+    pub fn sample<R: Rng>(&self, batch_size: usize, rng: &mut R) -> Vec<&Transition> {
+        let amount = batch_size.min(self.transitions.len());
+        return rand::seq::index::sample(rng, self.transitions.len(), amount)
+            .iter()
+            .map(|i| &self.transitions[i])
+            .collect();
+    }
+}
+
+// Deep Q-network: Q-learning with a NeuralNetwork approximating Q(s, a),
+// trained on minibatches from a replay buffer against a target network that
+// is only synced with the online one every few steps.
+//
+// NeuralNetwork layers are square and have no bias terms, so each state is
+// fed in followed by a constant 1.0 and zero padding, and the first
+// num_actions outputs are read as the Q-values. Those outputs are sigmoids,
+// so they are multiplied by a value scale that should bound the returns.
+pub struct DQN {
+    state_size: usize,
+    num_actions: usize,
+    online: NeuralNetwork,
+    target: NeuralNetwork,
+    replay: ReplayBuffer,
+    exploration: EpsilonGreedy,
+    learning_rate: f64,
+    discount: f64,
+    batch_size: usize,
+    // Transitions collected before learning starts
+    min_replay_size: usize,
+    target_sync_interval: usize,
+    value_scale: f64,
+    num_steps: usize,
+    steps_since_sync: usize,
+}
+
+impl DQN {
+    pub fn new(state_size: usize, num_actions: usize, num_layers: usize) -> DQN {
+        if state_size == 0 || num_actions == 0 || num_layers == 0 {
+            panic!("State size, actions and layers must be at least 1!");
+        }
+        let width = (state_size + 1).max(num_actions);
+        let mut online = NeuralNetwork::new(width, num_layers);
+        online.randomize_weights();
+        let mut target = NeuralNetwork::new(width, num_layers);
+        target.set_weights(online.get_weights());
+        let mut exploration = EpsilonGreedy::new(1.0);
+        exploration.set_decay(0.99, 0.05);
+        DQN {
+            state_size,
+            num_actions,
+            online,
+            target,
+            replay: ReplayBuffer::new(10000),
+            exploration,
+            learning_rate: 0.1,
+            discount: 0.99,
+            batch_size: 32,
+            min_replay_size: 100,
+            target_sync_interval: 100,
+            value_scale: 100.0,
+            num_steps: 0,
+            steps_since_sync: 0,
+        }
+    }
+
+    pub fn set_learning_rate(&mut self, learning_rate: f64) {
+        self.learning_rate = learning_rate;
+    }
+
+    pub fn set_discount(&mut self, discount: f64) {
+        if !(0.0..=1.0).contains(&discount) {
+            panic!("Discount must be in [0, 1]!");
+        }
+        self.discount = discount;
+    }
+
+    pub fn set_exploration(&mut self, exploration: EpsilonGreedy) {
+        self.exploration = exploration;
+    }
+
+    pub fn set_batch_size(&mut self, batch_size: usize) {
+        if batch_size == 0 {
+            panic!("Batch size must be at least 1!");
+        }
+        self.batch_size = batch_size;
+    }
+
+    // Replaces the replay buffer with an empty one of this capacity
+    pub fn set_replay_capacity(&mut self, capacity: usize) {
+        self.replay = ReplayBuffer::new(capacity);
+    }
+
+    pub fn set_min_replay_size(&mut self, min_replay_size: usize) {
+        self.min_replay_size = min_replay_size;
+    }
+
+    // Number of environment steps between copies of the online weights
+    // into the target network
+    pub fn set_target_sync_interval(&mut self, target_sync_interval: usize) {
+        if target_sync_interval == 0 {
+            panic!("Sync interval must be at least 1!");
+        }
+        self.target_sync_interval = target_sync_interval;
+    }
+
+    // Largest Q-value the network can represent
+    pub fn set_value_scale(&mut self, value_scale: f64) {
+        if value_scale <= 0.0 {
+            panic!("Value scale must be positive!");
+        }
+        self.value_scale = value_scale;
+    }
+
+    pub fn get_network(&self) -> &NeuralNetwork {
+        return &self.online;
+    }
+
+    pub fn get_target_network(&self) -> &NeuralNetwork {
+        return &self.target;
+    }
+
+    pub fn get_replay_buffer(&self) -> &ReplayBuffer {
+        return &self.replay;
+    }
+
+    pub fn get_exploration(&self) -> &EpsilonGreedy {
+        return &self.exploration;
+    }
+
+    // Environment steps taken during training so far
+    pub fn get_num_steps(&self) -> usize {
+        return self.num_steps;
+    }
+
+    fn network_inputs(&self, state: &Vec<f64>) -> Vec<f64> {
+        if state.len() != self.state_size {
+            panic!("Incompatible Vector Dimensions!");
+        }
+        let mut inputs = state.clone();
+        inputs.push(1.0);
+        inputs.resize((self.state_size + 1).max(self.num_actions), 0.0);
+        return inputs;
+    }
+
+    fn evaluate_network(&self, network: &NeuralNetwork, state: &Vec<f64>) -> Vec<f64> {
+        let outputs = network.execute(&self.network_inputs(state)).as_vec();
+        return outputs[..self.num_actions].iter().map(|q| q * self.value_scale).collect();
+    }
+
+    pub fn q_values(&self, state: &Vec<f64>) -> Vec<f64> {
+        return self.evaluate_network(&self.online, state);
+    }
+
+    // Greedy action for a state
+    pub fn act(&self, state: &Vec<f64>) -> usize {
+        return argmax(&self.q_values(state));
+    }
+
+    pub fn sync_target(&mut self) {
+        self.target.set_weights(self.online.get_weights());
+        self.steps_since_sync = 0;
+    }
+
+    // Stores a transition and, once enough have been collected, takes one
+    // gradient step on a sampled minibatch
+    pub fn observe<R: Rng>(&mut self, transition: Transition, rng: &mut R) {
+        self.replay.push(transition);
+        self.num_steps += 1;
+        if self.replay.len() >= self.min_replay_size.max(1) {
+            self.learn(rng);
+        }
+        self.steps_since_sync += 1;
+        if self.steps_since_sync >= self.target_sync_interval {
+            self.sync_target();
+        }
+    }
+
+    fn learn<R: Rng>(&mut self, rng: &mut R) {
+        let mut data: TrainingData = Vec::new();
+        for transition in self.replay.sample(self.batch_size, rng) {
+            let future = if transition.terminated {
+                0.0
+            } else {
+                let next_values = self.evaluate_network(&self.target, &transition.next_state);
+                self.discount * next_values.iter().cloned().fold(f64::NEG_INFINITY, f64::max)
+            };
+            let target_value = transition.reward + future;
+
+            // Only the output of the action taken gets an error signal
+            let inputs = self.network_inputs(&transition.state);
+            let mut targets = self.online.execute(&inputs).as_vec();
+            targets[transition.action] = (target_value / self.value_scale).clamp(0.0, 1.0);
+            data.push((inputs, targets));
+        }
+        self.online.train(&data, self.learning_rate, 1);
+    }
+
+    // Runs epsilon-greedy episodes, decaying epsilon after each one, and
+    // returns the total reward of every episode
+    pub fn train<E: Environment<State = Vec<f64>>>(&mut self, environment: &mut E, episodes: usize) -> Vec<f64> {
+        if environment.num_actions() != self.num_actions {
+            panic!("Environment does not match the network!");
+        }
+        let mut rng = rand::thread_rng();
+        let mut returns: Vec<f64> = Vec::new();
+        for _ in 0..episodes {
+            let mut state = environment.reset();
+            let mut total = 0.0;
+            loop {
+                let action = self.exploration.choose(&self.q_values(&state), &mut rng);
+                let (next_state, reward, terminated, truncated) = environment.step(action);
+                total += reward;
+                let transition = Transition { state, action, reward, next_state: next_state.clone(), terminated };
+                self.observe(transition, &mut rng);
+                state = next_state;
+                if terminated || truncated {
+                    break;
+                }
+            }
+            self.exploration.decay();
+            returns.push(total);
+        }
+        return returns;
+    }
+
+    // Average undiscounted return of the greedy policy
+    pub fn evaluate<E: Environment<State = Vec<f64>>>(&self, environment: &mut E, episodes: usize) -> f64 {
+        let mut total = 0.0;
+        for _ in 0..episodes {
+            let mut state = environment.reset();
+            loop {
+                let (next, reward, terminated, truncated) = environment.step(self.act(&state));
+                total += reward;
+                state = next;
+                if terminated || truncated {
+                    break;
+                }
+            }
+        }
+        return total / episodes as f64;
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_rl.rs"]
+mod test;
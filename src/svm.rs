@@ -0,0 +1,324 @@
+use super::matrix::Matrix;
+
+pub enum Kernel {
+    Linear,
+    // (gamma * <x, y> + coef0) ^ degree
+    Polynomial {
+        degree: i32,
+        gamma: f64,
+        coef0: f64,
+    },
+    // exp(-gamma * |x - y|^2)
+    RBF {
+        gamma: f64,
+    },
+}
+
+impl Kernel {
+    pub fn compute(&self, a: &Vec<f64>, b: &Vec<f64>) -> f64 {
+        if a.len() != b.len() {
+            panic!("Incompatible Vector Dimensions!");
+        }
+        match self {
+            Kernel::Linear => a.iter().zip(b).map(|(x, y)| x * y).sum(),
+            Kernel::Polynomial { degree, gamma, coef0 } => {
+                let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+                (gamma * dot + coef0).powi(*degree)
+            },
+            Kernel::RBF { gamma } => {
+                let distance: f64 = a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum();
+                (-gamma * distance).exp()
+            },
+        }
+    }
+}
+
+// Stand-in curvature when the kernel matrix is not positive definite
+const TAU: f64 = 1e-12;
+
+// Binary soft-margin support vector classifier, trained by Sequential Minimal
+// Optimization with second order working set selection (as in LIBSVM)
+pub struct SVC {
+    kernel: Kernel,
+    c: f64,
+    tolerance: f64,
+    max_iterations: usize,
+    support_vectors: Vec<Vec<f64>>,
+    support_indices: Vec<usize>,
+    // alpha_i * y_i for each support vector
+    dual_coefficients: Vec<f64>,
+    intercept: f64,
+    num_iterations: usize,
+}
+
+impl SVC {
+    pub fn new(kernel: Kernel) -> SVC {
+        SVC {
+            kernel,
+            c: 1.0,
+            tolerance: 1e-3,
+            max_iterations: 100000,
+            support_vectors: Vec::new(),
+            support_indices: Vec::new(),
+            dual_coefficients: Vec::new(),
+            intercept: 0.0,
+            num_iterations: 0,
+        }
+    }
+
+    // Penalty on margin violations; smaller values give a softer margin
+    pub fn set_c(&mut self, c: f64) {
+        if c <= 0.0 {
+            panic!("C must be positive!");
+        }
+        self.c = c;
+    }
+
+    // Stop once the maximal KKT violation falls below this value
+    pub fn set_tolerance(&mut self, tolerance: f64) {
+        self.tolerance = tolerance;
+    }
+
+    pub fn set_max_iterations(&mut self, max_iterations: usize) {
+        self.max_iterations = max_iterations;
+    }
+
+    pub fn get_support_vectors(&self) -> Matrix {
+        let cols = if self.support_vectors.is_empty() { 0 } else { self.support_vectors[0].len() };
+        let data: Vec<f64> = self.support_vectors.iter().flat_map(|v| v.clone()).collect();
+        return Matrix::from_vec(&data, self.support_vectors.len(), cols);
+    }
+
+    // Rows of the training data that became support vectors
+    pub fn get_support_indices(&self) -> &Vec<usize> {
+        return &self.support_indices;
+    }
+
+    pub fn get_dual_coefficients(&self) -> &Vec<f64> {
+        return &self.dual_coefficients;
+    }
+
+    pub fn get_intercept(&self) -> f64 {
+        return self.intercept;
+    }
+
+    pub fn get_num_iterations(&self) -> usize {
+        return self.num_iterations;
+    }
+
+    // Primal weights, only defined for the linear kernel
+    pub fn get_coefficients(&self) -> Vec<f64> {
+        if let Kernel::Linear = self.kernel {
+            let dims = if self.support_vectors.is_empty() { 0 } else { self.support_vectors[0].len() };
+            let mut result = vec![0.0; dims];
+            for (vector, coefficient) in self.support_vectors.iter().zip(&self.dual_coefficients) {
+                for d in 0..dims {
+                    result[d] += coefficient * vector[d];
+                }
+            }
+            return result;
+        }
+        panic!("Coefficients are only available for the linear kernel!");
+    }
+
+    pub fn fit(&mut self, inputs: &Matrix, labels: &Vec<usize>) {
+        if inputs.size().0 != labels.len() {
+            panic!("Inputs and labels must have the same number of rows!");
+        }
+        if labels.iter().any(|&label| label > 1) {
+            panic!("Only binary labels (0 or 1) are supported!");
+        }
+        let n = labels.len();
+        let rows = inputs.get_rows();
+        let y: Vec<f64> = labels.iter().map(|&label| if label == 1 { 1.0 } else { -1.0 }).collect();
+
+        let mut kernel = vec![vec![0.0; n]; n];
+        for i in 0..n {
+            for j in i..n {
+                let value = self.kernel.compute(&rows[i], &rows[j]);
+                kernel[i][j] = value;
+                kernel[j][i] = value;
+            }
+        }
+
+        // Dual: min 1/2 a'Qa - e'a  s.t.  0 <= a <= C, y'a = 0,
+        // with Q_ij = y_i y_j K_ij and gradient G = Qa - e
+        let c = self.c;
+        let mut alpha = vec![0.0; n];
+        let mut gradient = vec![-1.0; n];
+        let is_upper_bound = |a: f64| a >= c;
+        let is_lower_bound = |a: f64| a <= 0.0;
+
+        self.num_iterations = 0;
+        while self.num_iterations < self.max_iterations {
+            // Working set selection
+            let mut g_max = f64::NEG_INFINITY;
+            let mut g_max2 = f64::NEG_INFINITY;
+            let mut i_index: Option<usize> = None;
+            for t in 0..n {
+                if y[t] > 0.0 {
+                    if !is_upper_bound(alpha[t]) && -gradient[t] >= g_max {
+                        g_max = -gradient[t];
+                        i_index = Some(t);
+                    }
+                } else if !is_lower_bound(alpha[t]) && gradient[t] >= g_max {
+                    g_max = gradient[t];
+                    i_index = Some(t);
+                }
+            }
+            let i = match i_index {
+                Some(i) => i,
+                None => break,
+            };
+
+            let mut j_index: Option<usize> = None;
+            let mut objective_min = f64::INFINITY;
+            for t in 0..n {
+                let gradient_diff = if y[t] > 0.0 {
+                    if is_lower_bound(alpha[t]) {
+                        continue;
+                    }
+                    g_max2 = g_max2.max(gradient[t]);
+                    g_max + gradient[t]
+                } else {
+                    if is_upper_bound(alpha[t]) {
+                        continue;
+                    }
+                    g_max2 = g_max2.max(-gradient[t]);
+                    g_max - gradient[t]
+                };
+                if gradient_diff > 0.0 {
+                    // Moving along y_i e_i - y_t e_t, whatever the labels
+                    let curvature = kernel[i][i] + kernel[t][t] - 2.0 * kernel[i][t];
+                    let objective = -(gradient_diff * gradient_diff) / if curvature > 0.0 { curvature } else { TAU };
+                    if objective <= objective_min {
+                        objective_min = objective;
+                        j_index = Some(t);
+                    }
+                }
+            }
+            if g_max + g_max2 < self.tolerance {
+                break;
+            }
+            let j = match j_index {
+                Some(j) => j,
+                None => break,
+            };
+            self.num_iterations += 1;
+
+            // Analytic solution of the two variable sub-problem, clipped to the box
+            let q_ij = y[i] * y[j] * kernel[i][j];
+            let (old_i, old_j) = (alpha[i], alpha[j]);
+            if y[i] != y[j] {
+                let curvature = kernel[i][i] + kernel[j][j] + 2.0 * q_ij;
+                let delta = (-gradient[i] - gradient[j]) / if curvature > 0.0 { curvature } else { TAU };
+                let diff = alpha[i] - alpha[j];
+                alpha[i] += delta;
+                alpha[j] += delta;
+                if diff > 0.0 {
+                    if alpha[j] < 0.0 {
+                        alpha[j] = 0.0;
+                        alpha[i] = diff;
+                    }
+                } else if alpha[i] < 0.0 {
+                    alpha[i] = 0.0;
+                    alpha[j] = -diff;
+                }
+                if diff > 0.0 {
+                    if alpha[i] > c {
+                        alpha[i] = c;
+                        alpha[j] = c - diff;
+                    }
+                } else if alpha[j] > c {
+                    alpha[j] = c;
+                    alpha[i] = c + diff;
+                }
+            } else {
+                let curvature = kernel[i][i] + kernel[j][j] - 2.0 * q_ij;
+                let delta = (gradient[i] - gradient[j]) / if curvature > 0.0 { curvature } else { TAU };
+                let sum = alpha[i] + alpha[j];
+                alpha[i] -= delta;
+                alpha[j] += delta;
+                if sum > c {
+                    if alpha[i] > c {
+                        alpha[i] = c;
+                        alpha[j] = sum - c;
+                    }
+                    if alpha[j] > c {
+                        alpha[j] = c;
+                        alpha[i] = sum - c;
+                    }
+                } else {
+                    if alpha[j] < 0.0 {
+                        alpha[j] = 0.0;
+                        alpha[i] = sum;
+                    }
+                    if alpha[i] < 0.0 {
+                        alpha[i] = 0.0;
+                        alpha[j] = sum;
+                    }
+                }
+            }
+
+            let (delta_i, delta_j) = (alpha[i] - old_i, alpha[j] - old_j);
+            for k in 0..n {
+                gradient[k] += y[i] * y[k] * kernel[i][k] * delta_i + y[j] * y[k] * kernel[j][k] * delta_j;
+            }
+        }
+
+        // rho from the free support vectors, or the middle of the feasible
+        // range when every alpha sits on a bound
+        let mut upper = f64::INFINITY;
+        let mut lower = f64::NEG_INFINITY;
+        let mut free_count = 0;
+        let mut free_sum = 0.0;
+        for t in 0..n {
+            let y_gradient = y[t] * gradient[t];
+            if is_upper_bound(alpha[t]) {
+                if y[t] < 0.0 { upper = upper.min(y_gradient); } else { lower = lower.max(y_gradient); }
+            } else if is_lower_bound(alpha[t]) {
+                if y[t] > 0.0 { upper = upper.min(y_gradient); } else { lower = lower.max(y_gradient); }
+            } else {
+                free_count += 1;
+                free_sum += y_gradient;
+            }
+        }
+        let rho = if free_count > 0 { free_sum / free_count as f64 } else { (upper + lower) / 2.0 };
+
+        self.support_vectors = Vec::new();
+        self.support_indices = Vec::new();
+        self.dual_coefficients = Vec::new();
+        for t in 0..n {
+            if alpha[t] > 0.0 {
+                self.support_vectors.push(rows[t].clone());
+                self.support_indices.push(t);
+                self.dual_coefficients.push(alpha[t] * y[t]);
+            }
+        }
+        self.intercept = -rho;
+    }
+
+    // Signed distance-like score; positive values predict class 1
+    pub fn decision_function(&self, inputs: &Matrix) -> Vec<f64> {
+        if self.support_vectors.is_empty() {
+            panic!("Model has not been fitted!");
+        }
+        let mut result: Vec<f64> = Vec::new();
+        for i in 0..inputs.size().0 {
+            let row = inputs.get_row(i);
+            let score: f64 = self.support_vectors.iter().zip(&self.dual_coefficients)
+                .map(|(vector, coefficient)| coefficient * self.kernel.compute(vector, &row))
+                .sum();
+            result.push(score + self.intercept);
+        }
+        return result;
+    }
+
+    pub fn predict(&self, inputs: &Matrix) -> Vec<usize> {
+        return self.decision_function(inputs).iter().map(|&score| if score > 0.0 { 1 } else { 0 }).collect();
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_svm.rs"]
+mod test;
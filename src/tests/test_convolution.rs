@@ -0,0 +1,210 @@
+extern crate rand;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use super::super::matrix::Matrix;
+use super::super::convolution::{FeatureMaps, Conv2D, MaxPool2D, AvgPool2D, Flatten};
+
+fn to_strings(maps: &FeatureMaps) -> Vec<String> {
+    return maps.iter().map(|map| map.to_string_fmt(1)).collect();
+}
+
+fn ramp(height: usize, width: usize, offset: f64) -> Matrix {
+    let data: Vec<f64> = (0..(height * width)).map(|i| i as f64 + offset).collect();
+    return Matrix::from_vec(&data, height, width);
+}
+
+#[test]
+fn test_conv2d_forward() {
+    let mut conv = Conv2D::new(1, 2, 2);
+    conv.set_weights(Matrix::from_str("[[1, 1, 1, 1], [1, 0, 0, -1]]"));
+    conv.set_biases(vec![0.5, 0.0]);
+
+    let outputs = conv.forward(&vec![ramp(3, 3, 1.0)]);
+    assert_eq!(to_strings(&outputs), vec!["[[12.5,16.5],[24.5,28.5]]", "[[-4.0,-4.0],[-4.0,-4.0]]"]);
+}
+
+#[test]
+fn test_conv2d_stride_padding_dilation() {
+    let mut conv = Conv2D::new(1, 1, 2);
+    conv.set_weights(Matrix::from_str("[[1, 1, 1, 1]]"));
+
+    // Zero padding around a 3x3 input of ones gives corner sums of 1
+    conv.set_padding(1);
+    let ones = Matrix::from_str("[[1, 1, 1], [1, 1, 1], [1, 1, 1]]");
+    let outputs = conv.forward(&vec![ones.clone()]);
+    assert_eq!(outputs[0].to_string_fmt(0), "[[1,2,2,1],[2,4,4,2],[2,4,4,2],[1,2,2,1]]");
+
+    conv.set_stride(2);
+    let outputs = conv.forward(&vec![ones]);
+    assert_eq!(outputs[0].to_string_fmt(0), "[[1,2],[2,4]]");
+
+    // A dilated 2x2 kernel reads the corners of each 3x3 patch
+    let mut conv = Conv2D::new(1, 1, 2);
+    conv.set_weights(Matrix::from_str("[[1, 1, 1, 1]]"));
+    conv.set_dilation(2);
+    assert_eq!(conv.output_size(4, 4), (2, 2));
+    let outputs = conv.forward(&vec![ramp(4, 4, 0.0)]);
+    assert_eq!(outputs[0].to_string_fmt(0), "[[20,24],[36,40]]");
+}
+
+#[test]
+fn test_conv2d_multiple_channels() {
+    // Each output channel sums over every input channel
+    let mut conv = Conv2D::new(2, 1, 1);
+    conv.set_weights(Matrix::from_str("[[1, 10]]"));
+    let outputs = conv.forward(&vec![ramp(2, 2, 0.0), ramp(2, 2, 1.0)]);
+    assert_eq!(to_strings(&outputs), vec!["[[10.0,21.0],[32.0,43.0]]"]);
+}
+
+// Loss is the sum of outputs weighted by fixed random coefficients, so its
+// gradient with respect to the outputs is just those coefficients
+fn weighted_sum(maps: &FeatureMaps, coefficients: &FeatureMaps) -> f64 {
+    return maps.iter().zip(coefficients)
+        .map(|(map, c)| map.as_vec().iter().zip(c.as_vec()).map(|(x, y)| x * y).sum::<f64>())
+        .sum();
+}
+
+fn copy_maps(maps: &FeatureMaps) -> FeatureMaps {
+    return maps.iter().map(|map| map.clone()).collect();
+}
+
+fn random_maps(channels: usize, height: usize, width: usize) -> FeatureMaps {
+    let mut rng = rand::thread_rng();
+    return (0..channels).map(|_| {
+        let data: Vec<f64> = (0..(height * width)).map(|_| rng.gen_range(-1.0, 1.0)).collect();
+        Matrix::from_vec(&data, height, width)
+    }).collect();
+}
+
+#[test]
+fn test_conv2d_gradients() {
+    let mut conv = Conv2D::new(2, 3, 3);
+    conv.set_stride(2);
+    conv.set_padding(1);
+    conv.set_dilation(2);
+    let inputs = random_maps(2, 6, 5);
+    let outputs = conv.forward(&inputs);
+    let coefficients = random_maps(3, outputs[0].size().0, outputs[0].size().1);
+    let input_gradients = conv.backward(&coefficients);
+
+    let epsilon = 1e-6;
+    let loss = |conv: &mut Conv2D, inputs: &FeatureMaps| weighted_sum(&conv.forward(inputs), &coefficients);
+
+    // Input gradients against central differences
+    for c in 0..2 {
+        for (i, j) in [(0, 0), (2, 3), (5, 4), (3, 1)] {
+            let mut plus = copy_maps(&inputs);
+            plus[c].set_at_index(i, j, inputs[c].get_at_index(i, j) + epsilon);
+            let mut minus = copy_maps(&inputs);
+            minus[c].set_at_index(i, j, inputs[c].get_at_index(i, j) - epsilon);
+            let numeric = (loss(&mut conv, &plus) - loss(&mut conv, &minus)) / (2.0 * epsilon);
+            assert!((numeric - input_gradients[c].get_at_index(i, j)).abs() < 1e-6);
+        }
+    }
+
+    // Weight gradients against central differences
+    let weights = conv.get_weights().clone();
+    let weight_gradients = conv.get_weight_gradients().clone();
+    for (o, w) in [(0, 0), (1, 7), (2, 17), (2, 9)] {
+        let mut plus = weights.clone();
+        plus.set_at_index(o, w, weights.get_at_index(o, w) + epsilon);
+        conv.set_weights(plus);
+        let loss_plus = loss(&mut conv, &inputs);
+        let mut minus = weights.clone();
+        minus.set_at_index(o, w, weights.get_at_index(o, w) - epsilon);
+        conv.set_weights(minus);
+        let loss_minus = loss(&mut conv, &inputs);
+        let numeric = (loss_plus - loss_minus) / (2.0 * epsilon);
+        assert!((numeric - weight_gradients.get_at_index(o, w)).abs() < 1e-6);
+    }
+
+    // Bias gradients are the summed output gradients
+    for (o, coefficient) in coefficients.iter().enumerate() {
+        let expected: f64 = coefficient.as_vec().iter().sum();
+        assert!((conv.get_bias_gradients()[o] - expected).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn test_conv2d_learns_edge_filter() {
+    // Recover a horizontal edge detector from its responses
+    let mut target = Conv2D::new(1, 1, 3);
+    target.set_weights(Matrix::from_str("[[1, 1, 1, 0, 0, 0, -1, -1, -1]]"));
+    let mut conv = Conv2D::new(1, 1, 3);
+    conv.set_padding(1);
+    target.set_padding(1);
+
+    let samples: Vec<FeatureMaps> = (0..20).map(|_| random_maps(1, 6, 6)).collect();
+    for _ in 0..300 {
+        for sample in &samples {
+            let expected = target.forward(sample);
+            let outputs = conv.forward(sample);
+            let gradients: FeatureMaps = outputs.iter().zip(&expected).map(|(o, e)| o.subtract(e)).collect();
+            conv.backward(&gradients);
+        }
+        conv.update(0.01 / samples.len() as f64);
+    }
+    assert_eq!(conv.get_weights().to_string_fmt(2).replace("-0.00", "0.00"), "[[1.00,1.00,1.00,0.00,0.00,0.00,-1.00,-1.00,-1.00]]");
+    assert_eq!(format!("{:.2}", conv.get_biases()[0].abs()), "0.00");
+    assert_eq!(conv.get_weight_gradients().to_string_fmt(1).replace("-", ""), "[[0.0,0.0,0.0,0.0,0.0,0.0,0.0,0.0,0.0]]");
+}
+
+#[test]
+fn test_max_pool() {
+    let mut pool = MaxPool2D::new(2);
+    let inputs = vec![Matrix::from_str("[[1, 5, 2, 0], [3, 4, 8, 1], [0, 0, 1, 1], [9, 0, 1, 2]]")];
+    let outputs = pool.forward(&inputs);
+    assert_eq!(outputs[0].to_string_fmt(0), "[[5,8],[9,2]]");
+
+    // Only the winning positions receive gradient
+    let gradients = pool.backward(&vec![Matrix::from_str("[[1, 2], [3, 4]]")]);
+    assert_eq!(gradients[0].to_string_fmt(0), "[[0,1,0,0],[0,0,2,0],[0,0,0,0],[3,0,0,4]]");
+
+    // Overlapping windows route gradient to a shared maximum twice
+    let mut pool = MaxPool2D::new(2);
+    pool.set_stride(1);
+    let outputs = pool.forward(&vec![Matrix::from_str("[[1, 2, 1], [1, 9, 1]]")]);
+    assert_eq!(outputs[0].to_string_fmt(0), "[[9,9]]");
+    let gradients = pool.backward(&vec![Matrix::from_str("[[1, 1]]")]);
+    assert_eq!(gradients[0].to_string_fmt(0), "[[0,0,0],[0,2,0]]");
+}
+
+#[test]
+fn test_avg_pool() {
+    let mut pool = AvgPool2D::new(2);
+    let inputs = vec![ramp(2, 4, 0.0), ramp(2, 4, 8.0)];
+    let outputs = pool.forward(&inputs);
+    assert_eq!(to_strings(&outputs), vec!["[[2.5,4.5]]", "[[10.5,12.5]]"]);
+
+    let gradients = pool.backward(&vec![Matrix::from_str("[[4, 8]]"), Matrix::from_str("[[0, 4]]")]);
+    assert_eq!(to_strings(&gradients), vec!["[[1.0,1.0,2.0,2.0],[1.0,1.0,2.0,2.0]]", "[[0.0,0.0,1.0,1.0],[0.0,0.0,1.0,1.0]]"]);
+}
+
+#[test]
+fn test_flatten() {
+    let mut flatten = Flatten::new();
+    let inputs = vec![ramp(2, 2, 0.0), ramp(2, 2, 4.0)];
+    let outputs = flatten.forward(&inputs);
+    assert_eq!(outputs, vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0]);
+    assert_eq!(to_strings(&flatten.backward(&outputs)), to_strings(&inputs));
+}
+
+#[test]
+fn test_conv2d_initialize_with_seed() {
+    let mut first = Conv2D::new(2, 3, 3);
+    let mut second = Conv2D::new(2, 3, 3);
+    first.initialize(&mut StdRng::seed_from_u64(4));
+    second.initialize(&mut StdRng::seed_from_u64(4));
+    assert_eq!(first.get_weights().as_vec(), second.get_weights().as_vec());
+    let bound = 1.0 / 18f64.sqrt();
+    assert!(first.get_weights().as_vec().iter().all(|w| w.abs() <= bound));
+}
+
+#[should_panic]
+#[test]
+fn test_conv2d_wrong_channels() {
+    let mut conv = Conv2D::new(2, 1, 3);
+    conv.forward(&vec![ramp(4, 4, 0.0)]);
+}
@@ -0,0 +1,48 @@
+use super::super::matrix::Matrix;
+use super::super::dbscan::DBSCAN;
+
+#[test]
+fn test_dbscan_clusters_and_noise() {
+    let inputs = Matrix::from_str("[[0, 0], [0, 1], [1, 0], [1, 1], \
+        [10, 10], [10, 11], [11, 10], \
+        [5, 5]]");
+    let mut model = DBSCAN::new(1.5, 3);
+    let labels = model.fit_predict(&inputs);
+
+    assert_eq!(labels, vec![Some(0), Some(0), Some(0), Some(0), Some(1), Some(1), Some(1), None]);
+    assert_eq!(model.get_num_clusters(), 2);
+    assert_eq!(model.get_core_points(), &vec![0, 1, 2, 3, 4, 5, 6]);
+}
+
+#[test]
+fn test_dbscan_non_convex_clusters() {
+    // Two concentric rings that k-means would split down the middle
+    let mut data: Vec<f64> = Vec::new();
+    for radius in [1.0, 5.0] {
+        for step in 0..40 {
+            let angle = step as f64 * 2.0 * std::f64::consts::PI / 40.0;
+            data.push(radius * angle.cos());
+            data.push(radius * angle.sin());
+        }
+    }
+    let inputs = Matrix::from_vec(&data, 80, 2);
+    let mut model = DBSCAN::new(1.0, 2);
+    let labels = model.fit_predict(&inputs);
+
+    assert_eq!(model.get_num_clusters(), 2);
+    assert!(labels[..40].iter().all(|&label| label == labels[0]));
+    assert!(labels[40..].iter().all(|&label| label == labels[40]));
+    assert_ne!(labels[0], labels[40]);
+}
+
+#[test]
+fn test_dbscan_border_points() {
+    // The point at 2.5 is reachable from a core point but is not core itself,
+    // so it joins the cluster without extending it to the point at 3.4
+    let inputs = Matrix::from_str("[[0], [0.5], [1], [1.5], [2.5], [3.4]]");
+    let mut model = DBSCAN::new(1.0, 4);
+    let labels = model.fit_predict(&inputs);
+
+    assert_eq!(labels, vec![Some(0), Some(0), Some(0), Some(0), Some(0), None]);
+    assert_eq!(model.get_core_points(), &vec![1, 2, 3]);
+}
@@ -0,0 +1,131 @@
+use super::super::matrix::Matrix;
+use super::super::decision_tree::{DecisionTreeClassifier, DecisionTreeRegressor, Criterion};
+
+fn classification_data() -> (Matrix, Vec<usize>) {
+    // Feature 0 separates the classes, feature 1 is noise
+    let inputs = Matrix::from_str("[[1, 5], [2, 3], [3, 8], [4, 1], [6, 4], [7, 9], [8, 2], [9, 6]]");
+    let labels = vec![0, 0, 0, 0, 1, 1, 1, 1];
+    return (inputs, labels);
+}
+
+#[test]
+fn test_decision_tree_classifier_fit() {
+    let (inputs, labels) = classification_data();
+    let mut tree = DecisionTreeClassifier::new(2);
+    tree.fit(&inputs, &labels);
+
+    assert_eq!(tree.predict(&inputs), labels);
+    assert_eq!(tree.get_depth(), 1);
+    assert_eq!(tree.get_num_leaves(), 2);
+    assert_eq!(tree.feature_importances(), &vec![1.0, 0.0]);
+    assert_eq!(tree.to_text(), "|--- feature_0 <= 5.00\n\
+                                |   |--- class: 0\n\
+                                |--- feature_0 >  5.00\n\
+                                |   |--- class: 1\n");
+}
+
+#[test]
+fn test_decision_tree_classifier_xor() {
+    let inputs = Matrix::from_str("[[0, 0], [0, 1], [1, 0], [1, 1]]");
+    let labels = vec![0, 1, 1, 0];
+
+    for criterion in [Criterion::Gini, Criterion::Entropy] {
+        let mut tree = DecisionTreeClassifier::new(2);
+        tree.set_criterion(criterion);
+        tree.fit(&inputs, &labels);
+
+        assert_eq!(tree.predict(&inputs), labels);
+        assert_eq!(tree.get_depth(), 2);
+        assert_eq!(tree.get_num_leaves(), 4);
+    }
+}
+
+#[test]
+fn test_decision_tree_classifier_stopping_rules() {
+    let inputs = Matrix::from_str("[[0, 0], [0, 1], [1, 0], [1, 1]]");
+    let labels = vec![0, 1, 1, 0];
+
+    let mut shallow = DecisionTreeClassifier::new(2);
+    shallow.set_max_depth(1);
+    shallow.fit(&inputs, &labels);
+    assert_eq!(shallow.get_depth(), 1);
+
+    let mut stump = DecisionTreeClassifier::new(2);
+    stump.set_min_samples_split(5);
+    stump.fit(&inputs, &labels);
+    assert_eq!(stump.get_depth(), 0);
+    assert_eq!(stump.predict_proba(&inputs).get_row(0), vec![0.5, 0.5]);
+
+    let mut wide_leaves = DecisionTreeClassifier::new(2);
+    wide_leaves.set_min_samples_leaf(2);
+    wide_leaves.fit(&inputs, &labels);
+    assert_eq!(wide_leaves.get_depth(), 1);
+}
+
+#[test]
+fn test_decision_tree_classifier_predict_proba() {
+    let inputs = Matrix::from_str("[[0], [0], [0], [1], [1], [1]]");
+    let labels = vec![0, 0, 1, 1, 1, 2];
+    let mut tree = DecisionTreeClassifier::new(3);
+    tree.fit(&inputs, &labels);
+
+    let probabilities = tree.predict_proba(&Matrix::from_str("[[0], [1]]"));
+    assert_eq!(probabilities.to_string_fmt(2), "[[0.67,0.33,0.00],[0.00,0.67,0.33]]");
+    assert_eq!(tree.apply(&Matrix::from_str("[[0], [1]]")), vec![1, 2]);
+}
+
+#[should_panic]
+#[test]
+fn test_decision_tree_classifier_rejects_mse() {
+    let mut tree = DecisionTreeClassifier::new(2);
+    tree.set_criterion(Criterion::MSE);
+}
+
+#[should_panic(expected = "Model has not been fitted!")]
+#[test]
+fn test_decision_tree_classifier_depth_not_fitted() {
+    DecisionTreeClassifier::new(2).get_depth();
+}
+
+#[should_panic(expected = "Model has not been fitted!")]
+#[test]
+fn test_decision_tree_regressor_depth_not_fitted() {
+    DecisionTreeRegressor::new().get_depth();
+}
+
+#[test]
+fn test_decision_tree_regressor() {
+    let inputs = Matrix::from_str("[[1, 0], [2, 0], [3, 1], [4, 1], [5, 0], [6, 1]]");
+    let targets = vec![1.0, 1.2, 0.8, 5.0, 5.2, 4.8];
+    let mut tree = DecisionTreeRegressor::new();
+    tree.set_max_depth(1);
+    tree.fit(&inputs, &targets);
+
+    assert_eq!(tree.predict(&Matrix::from_str("[[0, 1], [10, 0]]")), vec![1.0, 5.0]);
+    assert_eq!(tree.feature_importances(), &vec![1.0, 0.0]);
+    assert_eq!(tree.to_text(), "|--- feature_0 <= 3.50\n\
+                                |   |--- value: 1.00\n\
+                                |--- feature_0 >  3.50\n\
+                                |   |--- value: 5.00\n");
+
+    let leaves = tree.apply(&Matrix::from_str("[[0, 1], [10, 0]]"));
+    tree.set_leaf_value(leaves[1], 7.5);
+    assert_eq!(tree.predict(&Matrix::from_str("[[0, 1], [10, 0]]")), vec![1.0, 7.5]);
+
+    let mut deep = DecisionTreeRegressor::new();
+    deep.fit(&inputs, &targets);
+    let predictions = deep.predict(&inputs);
+    for i in 0..targets.len() {
+        assert!((predictions[i] - targets[i]).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn test_decision_tree_fit_samples() {
+    let (inputs, labels) = classification_data();
+    let mut tree = DecisionTreeClassifier::new(2);
+    // Only the class 0 rows are used
+    tree.fit_samples(&inputs, &labels, vec![0, 1, 1, 2, 3]);
+    assert_eq!(tree.get_num_leaves(), 1);
+    assert_eq!(tree.predict(&inputs), vec![0; 8]);
+}
@@ -0,0 +1,115 @@
+use super::super::matrix::Matrix;
+use super::super::gaussian_mixture::{GaussianMixture, CovarianceType};
+
+fn two_blobs() -> Matrix {
+    return Matrix::from_str("[[0.0, 0.0], [0.4, 0.3], [-0.3, 0.5], [0.2, -0.4], [-0.5, -0.2], [0.1, 0.6], \
+        [6.0, 6.0], [6.5, 5.8], [5.7, 6.4], [6.2, 5.5], [5.6, 5.9], [6.3, 6.6]]");
+}
+
+#[test]
+fn test_gaussian_mixture_single_component() {
+    // One component is just the sample mean and (biased) covariance
+    let inputs = Matrix::from_str("[[1, 2], [3, 3], [2, 5], [4, 6]]");
+    let expected = vec![
+        (CovarianceType::Full, "[[1.25,1.25],[1.25,2.50]]", "-12.2441"),
+        (CovarianceType::Diagonal, "[[1.25,0.00],[0.00,2.50]]", "-13.6304"),
+        (CovarianceType::Spherical, "[[1.88,0.00],[0.00,1.88]]", "-13.8659"),
+    ];
+    for (covariance_type, covariance, log_likelihood) in expected {
+        let mut model = GaussianMixture::new(1);
+        model.set_covariance_type(covariance_type);
+        model.fit(&inputs);
+
+        assert_eq!(format!("{:.6}", model.get_weights()[0]), "1.000000");
+        assert_eq!(model.get_means().to_string_fmt(2), "[[2.50,4.00]]");
+        assert_eq!(model.get_covariances()[0].to_string_fmt(2), covariance);
+        assert_eq!(format!("{:.4}", model.log_likelihood(&inputs)), log_likelihood);
+        assert!(model.has_converged());
+    }
+}
+
+#[test]
+fn test_gaussian_mixture_two_blobs() {
+    let inputs = two_blobs();
+    let mut model = GaussianMixture::new(2);
+    model.fit(&inputs);
+
+    let labels = model.predict(&inputs);
+    assert!(labels[..6].iter().all(|&label| label == labels[0]));
+    assert!(labels[6..].iter().all(|&label| label == labels[6]));
+    assert_ne!(labels[0], labels[6]);
+
+    let means = model.get_means();
+    let mut means: Vec<String> = (0..2).map(|c| format!("{:.2},{:.2}", means.get_row(c)[0], means.get_row(c)[1])).collect();
+    means.sort();
+    assert_eq!(means, vec!["-0.02,0.13", "6.05,6.03"]);
+    for weight in model.get_weights() {
+        assert_eq!(format!("{:.3}", weight), "0.500");
+    }
+
+    // EM never decreases the likelihood
+    let log_likelihoods = model.get_log_likelihoods();
+    for i in 1..log_likelihoods.len() {
+        assert!(log_likelihoods[i] >= log_likelihoods[i - 1] - 1e-9);
+    }
+    assert!(model.has_converged());
+}
+
+#[test]
+fn test_gaussian_mixture_soft_assignments() {
+    let inputs = two_blobs();
+    let mut model = GaussianMixture::new(2);
+    model.set_covariance_type(CovarianceType::Spherical);
+    model.fit(&inputs);
+
+    let probabilities = model.predict_proba(&Matrix::from_str("[[0.1, 0.1], [6.1, 6.1], [3.0, 3.0]]"));
+    assert_eq!(probabilities.size(), (3, 2));
+    for i in 0..3 {
+        let row = probabilities.get_row(i);
+        assert!((row[0] + row[1] - 1.0).abs() < 1e-12);
+    }
+    assert!(probabilities.get_row(0).iter().any(|&p| p > 0.999));
+    assert!(probabilities.get_row(1).iter().any(|&p| p > 0.999));
+    // Halfway between the blobs neither component is certain
+    assert!(probabilities.get_row(2).iter().all(|&p| p > 0.01));
+
+    let scores = model.score_samples(&Matrix::from_str("[[0.0, 0.0], [3.0, 3.0]]"));
+    assert!(scores[0] > scores[1]);
+}
+
+#[test]
+fn test_gaussian_mixture_num_parameters() {
+    let inputs = two_blobs();
+    let expected = vec![(CovarianceType::Full, 11), (CovarianceType::Diagonal, 9), (CovarianceType::Spherical, 7)];
+    for (covariance_type, num_parameters) in expected {
+        let mut model = GaussianMixture::new(2);
+        model.set_covariance_type(covariance_type);
+        model.fit(&inputs);
+        assert_eq!(model.get_num_parameters(), num_parameters);
+
+        let log_likelihood = model.log_likelihood(&inputs);
+        assert!((model.aic(&inputs) - (-2.0 * log_likelihood + 2.0 * num_parameters as f64)).abs() < 1e-9);
+        assert!((model.bic(&inputs) - (-2.0 * log_likelihood + num_parameters as f64 * 12f64.ln())).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_gaussian_mixture_model_selection() {
+    // BIC prefers the true number of components
+    let inputs = two_blobs();
+    let bic: Vec<f64> = (1..4).map(|k| {
+        let mut model = GaussianMixture::new(k);
+        model.set_covariance_type(CovarianceType::Spherical);
+        model.fit(&inputs);
+        model.bic(&inputs)
+    }).collect();
+    assert!(bic[1] < bic[0]);
+    assert!(bic[1] < bic[2]);
+}
+
+#[should_panic]
+#[test]
+fn test_gaussian_mixture_not_fitted() {
+    let model = GaussianMixture::new(2);
+    model.predict(&two_blobs());
+}
@@ -0,0 +1,133 @@
+use super::super::matrix::Matrix;
+use super::super::gradient_boosting::{GradientBoostingRegressor, GradientBoostingClassifier, Loss};
+
+fn regression_data() -> (Matrix, Vec<f64>) {
+    let inputs: Vec<f64> = (0..20).map(|i| i as f64 / 2.0).collect();
+    let targets: Vec<f64> = inputs.iter().map(|x| x * x).collect();
+    return (Matrix::from_vec(&inputs, 20, 1), targets);
+}
+
+#[test]
+fn test_gradient_boosting_regressor_squared_error() {
+    let (inputs, targets) = regression_data();
+    let mut model = GradientBoostingRegressor::new(100);
+    model.fit(&inputs, &targets);
+
+    assert_eq!(model.get_num_estimators(), 100);
+    let losses = model.get_train_losses();
+    assert_eq!(losses.len(), 100);
+    assert!(losses[99] < losses[0]);
+    assert!(losses[99] < 0.5);
+
+    let predictions = model.predict(&inputs);
+    for i in 0..targets.len() {
+        assert!((predictions[i] - targets[i]).abs() < 1.5);
+    }
+}
+
+#[test]
+fn test_gradient_boosting_regressor_staged_predict() {
+    let (inputs, targets) = regression_data();
+    let mut model = GradientBoostingRegressor::new(10);
+    model.set_learning_rate(0.5);
+    model.set_max_depth(2);
+    model.fit(&inputs, &targets);
+
+    let stages = model.staged_predict(&inputs);
+    assert_eq!(stages.len(), 10);
+    assert_eq!(stages[9], model.predict(&inputs));
+}
+
+#[test]
+fn test_gradient_boosting_regressor_absolute_error() {
+    // A single huge outlier barely moves an absolute error model
+    let inputs = Matrix::from_str("[[0], [1], [2], [3], [4], [5], [6], [7], [8]]");
+    let targets = vec![1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1000.0];
+    let mut model = GradientBoostingRegressor::new(20);
+    model.set_loss(Loss::AbsoluteError);
+    model.set_max_depth(1);
+    model.fit(&inputs, &targets);
+
+    let predictions = model.predict(&Matrix::from_str("[[0], [4]]"));
+    assert!((predictions[0] - 1.0).abs() < 1e-9);
+    assert!((predictions[1] - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_absolute_error_gradient() {
+    assert_eq!(Loss::AbsoluteError.negative_gradient(3.0, 1.0), 1.0);
+    assert_eq!(Loss::AbsoluteError.negative_gradient(1.0, 3.0), -1.0);
+    assert_eq!(Loss::AbsoluteError.negative_gradient(2.0, 2.0), 0.0);
+}
+
+#[test]
+fn test_gradient_boosting_regressor_subsample() {
+    let (inputs, targets) = regression_data();
+    let mut model = GradientBoostingRegressor::new(200);
+    model.set_subsample(0.5);
+    model.fit(&inputs, &targets);
+    assert!(model.get_train_losses()[199] < 2.0);
+}
+
+#[test]
+fn test_gradient_boosting_early_stopping() {
+    let (inputs, targets) = regression_data();
+    // Validation targets disagree with the training targets, so the
+    // validation loss stops improving early on
+    let validation_targets = vec![0.0; 20];
+    let mut model = GradientBoostingRegressor::new(100);
+    model.set_early_stopping(3);
+    model.fit_with_validation(&inputs, &targets, &inputs, &validation_targets);
+
+    // The model and both histories end at the best validation loss
+    let losses = model.get_validation_losses();
+    let stages = model.get_num_estimators();
+    assert!(stages < 100);
+    assert_eq!(losses.len(), stages);
+    assert_eq!(model.get_train_losses().len(), stages);
+    assert_eq!(model.staged_predict(&inputs).len(), stages);
+    assert!(losses.iter().all(|&loss| loss >= losses[stages - 1]));
+}
+
+#[should_panic]
+#[test]
+fn test_gradient_boosting_regressor_rejects_log_loss() {
+    let mut model = GradientBoostingRegressor::new(10);
+    model.set_loss(Loss::LogLoss);
+}
+
+#[should_panic]
+#[test]
+fn test_gradient_boosting_rejects_zero_patience() {
+    let mut model = GradientBoostingRegressor::new(10);
+    model.set_early_stopping(0);
+}
+
+#[test]
+fn test_gradient_boosting_classifier() {
+    let inputs = Matrix::from_str("[[0, 1], [1, 0], [1, 1], [2, 2], [6, 5], [7, 8], [8, 6], [9, 9]]");
+    let labels = vec![0, 0, 0, 0, 1, 1, 1, 1];
+    let mut model = GradientBoostingClassifier::new(50);
+    model.fit(&inputs, &labels);
+
+    assert_eq!(model.predict(&inputs), labels);
+
+    let probabilities = model.predict_proba(&Matrix::from_str("[[0, 0], [9, 9]]"));
+    assert!(probabilities.get_at_index(0, 0) > 0.9);
+    assert!(probabilities.get_at_index(1, 1) > 0.9);
+
+    let staged = model.staged_predict_proba(&inputs);
+    assert_eq!(staged.len(), 50);
+    assert!(staged[0].get_at_index(7, 1) < staged[49].get_at_index(7, 1));
+    assert_eq!(model.staged_predict(&inputs)[49], labels);
+
+    let losses = model.get_train_losses();
+    assert!(losses[49] < losses[0]);
+}
+
+#[should_panic]
+#[test]
+fn test_gradient_boosting_classifier_rejects_multiclass() {
+    let mut model = GradientBoostingClassifier::new(10);
+    model.fit(&Matrix::from_str("[[0], [1], [2]]"), &vec![0, 1, 2]);
+}
@@ -0,0 +1,201 @@
+extern crate rand;
+
+use rand::Rng;
+
+use super::super::matrix::Matrix;
+use super::super::hidden_markov_model::{DiscreteHMM, GaussianHMM};
+
+// The classic healthy / fever example: states 0 = healthy, 1 = fever and
+// symbols 0 = normal, 1 = cold, 2 = dizzy
+fn doctor() -> DiscreteHMM {
+    let mut model = DiscreteHMM::new(2, 3);
+    model.set_start_probabilities(vec![0.6, 0.4]);
+    model.set_transitions(Matrix::from_str("[[0.7, 0.3], [0.4, 0.6]]"));
+    model.set_emissions(Matrix::from_str("[[0.5, 0.4, 0.1], [0.1, 0.3, 0.6]]"));
+    return model;
+}
+
+#[test]
+fn test_discrete_hmm_forward_backward() {
+    let model = doctor();
+    let observations = vec![0, 1, 2];
+
+    assert_eq!(format!("{:.6}", model.log_likelihood(&observations)), "-3.316489");
+    assert_eq!(format!("{:.4}", model.log_likelihood(&observations).exp()), "0.0363");
+
+    let alpha = model.forward(&observations);
+    assert_eq!(alpha.size(), (3, 2));
+    // alpha at t = 0 is start * emission
+    assert_eq!(format!("{:.2},{:.2}", alpha.get_at_index(0, 0).exp(), alpha.get_at_index(0, 1).exp()), "0.30,0.04");
+    let beta = model.backward(&observations);
+    assert_eq!(beta.get_row(2), vec![0.0, 0.0]);
+
+    let posteriors = model.posteriors(&observations);
+    assert_eq!(posteriors.to_string_fmt(4), "[[0.8765,0.1235],[0.6229,0.3771],[0.2121,0.7879]]");
+}
+
+#[test]
+fn test_discrete_hmm_viterbi() {
+    let model = doctor();
+    let (path, log_probability) = model.viterbi(&vec![0, 1, 2]);
+    assert_eq!(path, vec![0, 0, 1]);
+    assert_eq!(format!("{:.5}", log_probability.exp()), "0.01512");
+}
+
+#[test]
+fn test_discrete_hmm_long_sequence() {
+    // Raw probabilities of a sequence this long underflow to zero
+    let model = doctor();
+    let observations: Vec<usize> = (0..5000).map(|t| t % 3).collect();
+    let log_likelihood = model.log_likelihood(&observations);
+    assert!(log_likelihood.is_finite());
+    assert!(log_likelihood < -1000.0);
+
+    let (path, log_probability) = model.viterbi(&observations);
+    assert_eq!(path.len(), 5000);
+    assert!(log_probability.is_finite());
+    assert!(log_probability <= log_likelihood);
+}
+
+#[test]
+fn test_discrete_hmm_baum_welch() {
+    // Sample sequences from a sticky two state model and train a fresh one
+    let truth = doctor();
+    let mut rng = rand::thread_rng();
+    let mut sequences: Vec<Vec<usize>> = Vec::new();
+    for _ in 0..20 {
+        let mut state = if rng.gen_range(0.0, 1.0) < 0.6 { 0 } else { 1 };
+        let mut sequence: Vec<usize> = Vec::new();
+        for _ in 0..50 {
+            let emissions = truth.get_emissions().get_row(state);
+            let draw = rng.gen_range(0.0, 1.0);
+            let symbol = if draw < emissions[0] { 0 } else if draw < emissions[0] + emissions[1] { 1 } else { 2 };
+            sequence.push(symbol);
+            let stay = truth.get_transitions().get_at_index(state, state);
+            if rng.gen_range(0.0, 1.0) >= stay {
+                state = 1 - state;
+            }
+        }
+        sequences.push(sequence);
+    }
+
+    let mut model = DiscreteHMM::new(2, 3);
+    model.set_seed(3);
+    model.fit(&sequences);
+
+    // Each Baum-Welch iteration can only raise the likelihood
+    let log_likelihoods = model.get_log_likelihoods();
+    assert!(log_likelihoods.len() > 1);
+    for i in 1..log_likelihoods.len() {
+        assert!(log_likelihoods[i] >= log_likelihoods[i - 1] - 1e-6);
+    }
+
+    // The learned tables are still probability distributions
+    for table in [model.get_transitions(), model.get_emissions()] {
+        for i in 0..table.size().0 {
+            assert!((table.get_row(i).iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        }
+    }
+    assert!((model.get_start_probabilities().iter().sum::<f64>() - 1.0).abs() < 1e-9);
+}
+
+#[should_panic]
+#[test]
+fn test_discrete_hmm_symbol_out_of_range() {
+    doctor().log_likelihood(&vec![0, 3]);
+}
+
+#[should_panic]
+#[test]
+fn test_discrete_hmm_invalid_transitions() {
+    let mut model = DiscreteHMM::new(2, 3);
+    model.set_transitions(Matrix::from_str("[[0.5, 0.6], [0.4, 0.6]]"));
+}
+
+#[test]
+fn test_gaussian_hmm_recovers_regimes() {
+    // Alternating blocks of readings around 0 and around 5
+    let mut data: Vec<f64> = Vec::new();
+    let mut regimes: Vec<usize> = Vec::new();
+    for block in 0..6 {
+        for i in 0..15 {
+            let noise = ((block * 15 + i) as f64 * 1.7).sin() * 0.5;
+            data.push(if block % 2 == 0 { noise } else { 5.0 + noise });
+            regimes.push(block % 2);
+        }
+    }
+    let observations = Matrix::from_vec(&data, data.len(), 1);
+
+    let mut model = GaussianHMM::new(2, 1);
+    model.set_seed(7);
+    model.fit(&vec![observations.clone()]);
+
+    let log_likelihoods = model.get_log_likelihoods();
+    for i in 1..log_likelihoods.len() {
+        assert!(log_likelihoods[i] >= log_likelihoods[i - 1] - 1e-6);
+    }
+
+    // The state labels are arbitrary, so compare the path up to a swap
+    let (path, _) = model.viterbi(&observations);
+    let low_state = path[0];
+    for (state, regime) in path.iter().zip(&regimes) {
+        assert_eq!(*state == low_state, *regime == 0);
+    }
+
+    let means = model.get_means();
+    let mut means = [means.get_at_index(0, 0), means.get_at_index(1, 0)];
+    means.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert!(means[0].abs() < 0.3);
+    assert!((means[1] - 5.0).abs() < 0.3);
+
+    // Staying in a regime is far more likely than switching
+    let transitions = model.get_transitions();
+    assert!(transitions.get_at_index(0, 0) > 0.8);
+    assert!(transitions.get_at_index(1, 1) > 0.8);
+
+    let posteriors = model.posteriors(&observations);
+    assert!(posteriors.get_row(0)[low_state] > 0.99);
+}
+
+#[test]
+fn test_hmm_set_seed() {
+    let mut first = DiscreteHMM::new(2, 3);
+    let mut second = DiscreteHMM::new(2, 3);
+    first.set_seed(5);
+    second.set_seed(5);
+    assert_eq!(first.get_transitions().to_string_fmt(12), second.get_transitions().to_string_fmt(12));
+    assert_eq!(first.get_emissions().to_string_fmt(12), second.get_emissions().to_string_fmt(12));
+
+    let observations = Matrix::from_str("[[0.1], [0.3], [4.8], [5.2], [0.2], [5.1]]");
+    let fit = || -> (String, String) {
+        let mut model = GaussianHMM::new(2, 1);
+        model.set_seed(5);
+        model.set_max_iterations(3);
+        model.fit(&vec![observations.clone()]);
+        return (model.get_transitions().to_string_fmt(12), model.get_means().to_string_fmt(12));
+    };
+    assert_eq!(fit(), fit());
+}
+
+#[test]
+fn test_gaussian_hmm_fixed_parameters() {
+    let mut model = GaussianHMM::new(2, 1);
+    model.set_start_probabilities(vec![1.0, 0.0]);
+    model.set_transitions(Matrix::from_str("[[0.9, 0.1], [0.1, 0.9]]"));
+    model.set_means(Matrix::from_str("[[0], [10]]"));
+    model.set_variances(Matrix::from_str("[[1], [1]]"));
+
+    // A single observation at the mean of state 0: ln N(0 | 0, 1)
+    let observations = Matrix::from_str("[[0]]");
+    assert_eq!(format!("{:.6}", model.log_likelihood(&observations)), "-0.918939");
+
+    let (path, _) = model.viterbi(&Matrix::from_str("[[0.2], [-0.1], [9.8], [10.3], [10.1]]"));
+    assert_eq!(path, vec![0, 0, 1, 1, 1]);
+}
+
+#[should_panic]
+#[test]
+fn test_gaussian_hmm_not_fitted() {
+    let model = GaussianHMM::new(2, 1);
+    model.log_likelihood(&Matrix::from_str("[[0]]"));
+}
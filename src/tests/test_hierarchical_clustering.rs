@@ -0,0 +1,64 @@
+use super::super::matrix::Matrix;
+use super::super::hierarchical_clustering::{AgglomerativeClustering, Linkage, Merge};
+
+fn line_points() -> Matrix {
+    return Matrix::from_str("[[0], [1], [3], [7], [8]]");
+}
+
+#[test]
+fn test_hierarchical_single_linkage() {
+    let mut model = AgglomerativeClustering::new(Linkage::Single);
+    model.fit(&line_points());
+
+    let dendrogram = model.get_dendrogram();
+    assert_eq!(dendrogram.len(), 4);
+    assert_eq!(dendrogram[0], Merge { left: 0, right: 1, distance: 1.0, size: 2 });
+    assert_eq!(dendrogram[1], Merge { left: 3, right: 4, distance: 1.0, size: 2 });
+    assert_eq!(dendrogram[2], Merge { left: 2, right: 5, distance: 2.0, size: 3 });
+    assert_eq!(dendrogram[3], Merge { left: 6, right: 7, distance: 4.0, size: 5 });
+
+    assert_eq!(model.dendrogram_to_string(), "0: 0 + 1 -> 5 (distance 1.0000, size 2)\n\
+                                              1: 3 + 4 -> 6 (distance 1.0000, size 2)\n\
+                                              2: 2 + 5 -> 7 (distance 2.0000, size 3)\n\
+                                              3: 6 + 7 -> 8 (distance 4.0000, size 5)\n");
+}
+
+#[test]
+fn test_hierarchical_linkage_distances() {
+    let expected = vec![
+        (Linkage::Complete, vec![1.0, 1.0, 3.0, 8.0]),
+        (Linkage::Average, vec![1.0, 1.0, 2.5, 37.0 / 6.0]),
+        // sqrt(2 * n_a * n_b / (n_a + n_b)) * distance between centroids
+        (Linkage::Ward, vec![1.0, 1.0, 2.5 * (4.0f64 / 3.0).sqrt(), (7.5 - 4.0 / 3.0) * (12.0f64 / 5.0).sqrt()]),
+    ];
+    for (linkage, distances) in expected {
+        let mut model = AgglomerativeClustering::new(linkage);
+        model.fit(&line_points());
+        for (merge, distance) in model.get_dendrogram().iter().zip(distances) {
+            assert!((merge.distance - distance).abs() < 1e-9);
+        }
+    }
+}
+
+#[test]
+fn test_hierarchical_cut() {
+    let mut model = AgglomerativeClustering::new(Linkage::Average);
+    assert_eq!(model.fit_predict(&line_points(), 2), vec![0, 0, 0, 1, 1]);
+    assert_eq!(model.cut(3), vec![0, 0, 1, 2, 2]);
+    assert_eq!(model.cut(5), vec![0, 1, 2, 3, 4]);
+    assert_eq!(model.cut(1), vec![0, 0, 0, 0, 0]);
+    assert_eq!(model.cut_at_distance(1.5), vec![0, 0, 1, 2, 2]);
+}
+
+#[test]
+fn test_hierarchical_chaining() {
+    // Single linkage chains along the evenly spaced points, complete linkage
+    // prefers compact groups
+    let inputs = Matrix::from_str("[[0], [1], [2], [3], [4], [5], [6], [7], [8], [9.5]]");
+
+    let mut single = AgglomerativeClustering::new(Linkage::Single);
+    assert_eq!(single.fit_predict(&inputs, 2), vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+
+    let mut complete = AgglomerativeClustering::new(Linkage::Complete);
+    assert_eq!(complete.fit_predict(&inputs, 2), vec![0, 0, 0, 0, 1, 1, 1, 1, 1, 1]);
+}
@@ -0,0 +1,120 @@
+use super::super::matrix::Matrix;
+use super::super::k_means::{KMeans, MiniBatchKMeans, Initialization};
+
+fn blobs() -> Matrix {
+    return Matrix::from_str("[[0.0, 0.0], [0.2, 0.1], [-0.1, 0.2], [0.1, -0.3], \
+        [5.0, 5.0], [5.2, 4.9], [4.9, 5.3], [4.9, 4.8], \
+        [0.0, 8.0], [0.3, 8.1], [-0.2, 7.9], [-0.1, 8.0]]");
+}
+
+fn sorted_centroids(centroids: &Matrix) -> Vec<String> {
+    let mut result: Vec<String> = (0..centroids.size().0)
+        .map(|i| format!("{:.1},{:.1}", centroids.get_row(i)[0], centroids.get_row(i)[1]))
+        .collect();
+    result.sort();
+    return result;
+}
+
+#[test]
+fn test_k_means_fit() {
+    let inputs = blobs();
+    for initialization in [Initialization::KMeansPlusPlus, Initialization::Random] {
+        let mut model = KMeans::new(3);
+        model.set_initialization(initialization);
+        model.set_seed(1);
+        model.fit(&inputs);
+
+        assert_eq!(model.get_centroids().size(), (3, 2));
+        assert_eq!(sorted_centroids(model.get_centroids()), vec!["-0.0,8.0", "0.1,0.0", "5.0,5.0"]);
+        assert_eq!(format!("{:.2}", model.get_inertia()), "0.55");
+        assert!(model.get_num_iterations() >= 1);
+    }
+}
+
+#[test]
+fn test_k_means_predict() {
+    let inputs = blobs();
+    let mut model = KMeans::new(3);
+    model.set_seed(2);
+    model.fit(&inputs);
+
+    let labels = model.predict(&inputs);
+    for group in 0..3 {
+        for i in 0..4 {
+            assert_eq!(labels[group * 4 + i], labels[group * 4]);
+        }
+    }
+    assert_ne!(labels[0], labels[4]);
+    assert_ne!(labels[4], labels[8]);
+
+    let predicted = model.predict(&Matrix::from_str("[[0.5, 0.5], [4.0, 4.0], [1.0, 7.0]]"));
+    assert_eq!(predicted, vec![labels[0], labels[4], labels[8]]);
+}
+
+#[test]
+fn test_k_means_max_iterations() {
+    let mut model = KMeans::new(3);
+    model.set_num_restarts(1);
+    model.set_max_iterations(1);
+    model.fit(&blobs());
+    assert_eq!(model.get_num_iterations(), 1);
+}
+
+#[should_panic]
+#[test]
+fn test_k_means_more_clusters_than_samples() {
+    let mut model = KMeans::new(3);
+    model.fit(&Matrix::from_str("[[0, 0], [1, 1]]"));
+}
+
+#[test]
+fn test_mini_batch_k_means_fit() {
+    let inputs = blobs();
+    let mut model = MiniBatchKMeans::new(3, 6);
+    model.set_max_iterations(200);
+    model.set_tolerance(0.0);
+    model.set_seed(3);
+    model.fit(&inputs);
+
+    let labels = model.predict(&inputs);
+    assert_eq!(labels[0], labels[3]);
+    assert_eq!(labels[4], labels[7]);
+    assert_eq!(labels[8], labels[11]);
+    assert!(model.get_inertia() < 2.0);
+}
+
+#[test]
+fn test_mini_batch_k_means_partial_fit() {
+    let inputs = blobs();
+    let mut model = MiniBatchKMeans::new(3, 4);
+    model.set_seed(4);
+    for _ in 0..20 {
+        model.partial_fit(&inputs);
+    }
+    assert_eq!(model.get_centroids().size(), (3, 2));
+
+    let predicted = model.predict(&Matrix::from_str("[[0, 0], [5, 5], [0, 8]]"));
+    assert_ne!(predicted[0], predicted[1]);
+    assert_ne!(predicted[1], predicted[2]);
+    assert_ne!(predicted[0], predicted[2]);
+}
+
+#[test]
+fn test_k_means_set_seed() {
+    let inputs = blobs();
+    let fit = |seed: u64| -> (String, String) {
+        let mut model = KMeans::new(3);
+        model.set_initialization(Initialization::Random);
+        model.set_num_restarts(1);
+        model.set_max_iterations(1);
+        model.set_seed(seed);
+        model.fit(&inputs);
+
+        let mut mini_batch = MiniBatchKMeans::new(3, 2);
+        mini_batch.set_max_iterations(5);
+        mini_batch.set_seed(seed);
+        mini_batch.fit(&inputs);
+        return (model.get_centroids().to_string_fmt(6), mini_batch.get_centroids().to_string_fmt(6));
+    };
+    assert_eq!(fit(5), fit(5));
+}
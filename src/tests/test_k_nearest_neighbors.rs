@@ -0,0 +1,128 @@
+extern crate rand;
+
+use rand::Rng;
+
+use super::super::matrix::Matrix;
+use super::super::k_nearest_neighbors::{KNNClassifier, KNNRegressor, KdTree, Distance, Weighting, Algorithm};
+
+#[test]
+fn test_knn_distances() {
+    let a = vec![1.0, 2.0, 3.0];
+    let b = vec![4.0, 6.0, 3.0];
+
+    assert_eq!(Distance::Euclidean.compute(&a, &b), 5.0);
+    assert_eq!(Distance::Manhattan.compute(&a, &b), 7.0);
+    assert_eq!(format!("{:.*}", 10, Distance::Minkowski(3.0).compute(&a, &b)), "4.4979414453");
+    assert_eq!(format!("{:.*}", 10, Distance::Cosine.compute(&a, &b)), "0.1445176115");
+    assert!(Distance::Cosine.compute(&a, &vec![2.0, 4.0, 6.0]).abs() < 1e-12);
+}
+
+#[test]
+fn test_knn_kd_tree_matches_brute_force() {
+    let mut rng = rand::thread_rng();
+    let mut data: Vec<f64> = Vec::new();
+    for _ in 0..(200 * 3) {
+        data.push(rng.gen_range(-10.0, 10.0));
+    }
+    let points = Matrix::from_vec(&data, 200, 3);
+    let tree = KdTree::new(&points);
+
+    for distance in [Distance::Euclidean, Distance::Manhattan, Distance::Minkowski(3.0)] {
+        for _ in 0..20 {
+            let query = vec![rng.gen_range(-10.0, 10.0), rng.gen_range(-10.0, 10.0), rng.gen_range(-10.0, 10.0)];
+            let actual = tree.query(&query, 5, &distance);
+
+            let mut expected: Vec<(usize, f64)> = (0..200)
+                .map(|i| (i, distance.compute(&query, &points.get_row(i))))
+                .collect();
+            expected.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+            expected.truncate(5);
+
+            assert_eq!(actual, expected);
+        }
+    }
+}
+
+#[test]
+fn test_knn_classifier() {
+    let inputs = Matrix::from_str("[[0, 0], [0, 1], [1, 0], [5, 5], [5, 6], [6, 5]]");
+    let labels = vec![0, 0, 0, 1, 1, 1];
+
+    for algorithm in [Algorithm::BruteForce, Algorithm::KdTree] {
+        let mut model = KNNClassifier::new(3, 2);
+        model.set_algorithm(algorithm);
+        model.fit(&inputs, &labels);
+
+        let queries = Matrix::from_str("[[0.5, 0.5], [4.0, 4.5]]");
+        assert_eq!(model.predict(&queries), vec![0, 1]);
+        assert_eq!(model.predict_proba(&queries).to_string_fmt(2), "[[1.00,0.00],[0.00,1.00]]");
+    }
+}
+
+#[test]
+fn test_knn_classifier_distance_weighting() {
+    // Two far neighbors of class 1 outvote one close neighbor of class 0
+    // unless votes are weighted by distance
+    let inputs = Matrix::from_str("[[0.1], [2.0], [2.1]]");
+    let labels = vec![0, 1, 1];
+    let query = Matrix::from_str("[[0.0]]");
+
+    let mut uniform = KNNClassifier::new(3, 2);
+    uniform.fit(&inputs, &labels);
+    assert_eq!(uniform.predict(&query), vec![1]);
+
+    let mut weighted = KNNClassifier::new(3, 2);
+    weighted.set_weighting(Weighting::Distance);
+    weighted.fit(&inputs, &labels);
+    assert_eq!(weighted.predict(&query), vec![0]);
+}
+
+#[test]
+fn test_knn_classifier_cosine() {
+    let inputs = Matrix::from_str("[[1, 0], [10, 1], [0, 1], [1, 10]]");
+    let labels = vec![0, 0, 1, 1];
+    let mut model = KNNClassifier::new(1, 2);
+    model.set_distance(Distance::Cosine);
+    model.fit(&inputs, &labels);
+
+    assert_eq!(model.predict(&Matrix::from_str("[[100, 5], [0.1, 3]]")), vec![0, 1]);
+}
+
+#[test]
+fn test_knn_set_distance_after_fit() {
+    let inputs = Matrix::from_str("[[1, 0], [10, 1], [0, 1], [1, 10]]");
+    let labels = vec![0, 0, 1, 1];
+    let mut model = KNNClassifier::new(1, 2);
+    model.fit(&inputs, &labels);
+    assert_eq!(model.predict(&Matrix::from_str("[[6, 4]]")), vec![0]);
+
+    // The KD-tree built for Euclidean is replaced by a brute-force index
+    model.set_distance(Distance::Cosine);
+    assert_eq!(model.predict(&Matrix::from_str("[[100, 5], [0.1, 3]]")), vec![0, 1]);
+}
+
+#[should_panic]
+#[test]
+fn test_knn_kd_tree_rejects_cosine() {
+    let mut model = KNNClassifier::new(1, 2);
+    model.set_distance(Distance::Cosine);
+    model.set_algorithm(Algorithm::KdTree);
+    model.fit(&Matrix::from_str("[[1, 0], [0, 1]]"), &vec![0, 1]);
+}
+
+#[test]
+fn test_knn_regressor() {
+    let inputs = Matrix::from_str("[[0], [1], [2], [3]]");
+    let targets = vec![0.0, 1.0, 4.0, 9.0];
+
+    let mut uniform = KNNRegressor::new(2);
+    uniform.fit(&inputs, &targets);
+    assert_eq!(uniform.predict(&Matrix::from_str("[[0.4], [2.6]]")), vec![0.5, 6.5]);
+
+    let mut weighted = KNNRegressor::new(2);
+    weighted.set_weighting(Weighting::Distance);
+    weighted.fit(&inputs, &targets);
+    let actual = weighted.predict(&Matrix::from_str("[[0.25], [2.0]]"));
+    assert_eq!(format!("{:.*}", 4, actual[0]), "0.2500");
+    assert_eq!(actual[1], 4.0);
+}
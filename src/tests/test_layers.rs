@@ -0,0 +1,483 @@
+extern crate rand;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use super::super::matrix::Matrix;
+use super::super::loss::Loss;
+use super::super::layers::{Layer, Dense, Activation, ActivationFunction, Dropout, DropoutVariant};
+use super::super::layers::{LayerNorm, BatchNorm, Initializer};
+use super::super::neural_network::{Sequential, TrainingData};
+
+pub(crate) fn random_matrix(rows: usize, cols: usize) -> Matrix {
+    let mut rng = rand::thread_rng();
+    let data: Vec<f64> = (0..(rows * cols)).map(|_| rng.gen_range(-1.0, 1.0)).collect();
+    return Matrix::from_vec(&data, rows, cols);
+}
+
+// Compares analytic gradients of a weighted sum of the outputs against
+// central differences, in whatever mode the layer is in. Shared with the
+// tests of other modules' layers.
+pub(crate) fn check_gradients(layer: &mut dyn Layer, rows: usize, cols: usize) {
+    let inputs = random_matrix(rows, cols);
+    let outputs = layer.forward(&inputs);
+    let coefficients = random_matrix(outputs.size().0, outputs.size().1);
+    let input_gradients = layer.backward(&coefficients);
+
+    let epsilon = 1e-6;
+    let loss = |layer: &mut dyn Layer, inputs: &Matrix| -> f64 {
+        return layer.forward(inputs).hadamard(&coefficients).as_vec().iter().sum();
+    };
+
+    for r in 0..rows {
+        for c in 0..cols {
+            let mut plus = inputs.clone();
+            plus.set_at_index(r, c, inputs.get_at_index(r, c) + epsilon);
+            let mut minus = inputs.clone();
+            minus.set_at_index(r, c, inputs.get_at_index(r, c) - epsilon);
+            let numeric = (loss(layer, &plus) - loss(layer, &minus)) / (2.0 * epsilon);
+            assert!((numeric - input_gradients.get_at_index(r, c)).abs() < 1e-6);
+        }
+    }
+
+    let parameters: Vec<Matrix> = layer.parameters().into_iter().map(|p| p.clone()).collect();
+    let gradients: Vec<Matrix> = layer.gradients().into_iter().map(|g| g.clone()).collect();
+    for p in 0..parameters.len() {
+        let (rows, cols) = parameters[p].size();
+        for r in 0..rows {
+            for c in 0..cols {
+                let mut shifted: Vec<Matrix> = parameters.iter().map(|m| m.clone()).collect();
+                shifted[p].set_at_index(r, c, parameters[p].get_at_index(r, c) + epsilon);
+                layer.set_parameters(shifted);
+                let loss_plus = loss(layer, &inputs);
+                let mut shifted: Vec<Matrix> = parameters.iter().map(|m| m.clone()).collect();
+                shifted[p].set_at_index(r, c, parameters[p].get_at_index(r, c) - epsilon);
+                layer.set_parameters(shifted);
+                let loss_minus = loss(layer, &inputs);
+                let numeric = (loss_plus - loss_minus) / (2.0 * epsilon);
+                assert!((numeric - gradients[p].get_at_index(r, c)).abs() < 1e-6);
+            }
+        }
+    }
+    layer.set_parameters(parameters);
+}
+
+#[test]
+fn test_dense_forward() {
+    let mut dense = Dense::new(3, 2);
+    dense.set_parameters(vec![Matrix::from_str("[[1, 0, -1], [2, 1, 0]]"), Matrix::from_str("[[0.5, -1]]")]);
+    let outputs = dense.forward(&Matrix::from_str("[[1, 2, 3], [0, 1, 0]]"));
+    assert_eq!(outputs.to_string_fmt(1), "[[-1.5,3.0],[0.5,0.0]]");
+    assert_eq!(dense.get_weights().size(), (2, 3));
+    assert_eq!(dense.get_biases().size(), (1, 2));
+}
+
+#[test]
+fn test_dense_without_bias() {
+    let mut dense = Dense::new(2, 2);
+    dense.set_use_bias(false);
+    assert_eq!(dense.parameters().len(), 1);
+    dense.set_parameters(vec![Matrix::from_str("[[1, 2], [3, 4]]")]);
+    assert_eq!(dense.predict(&Matrix::from_str("[[1, 1]]")).to_string(), "[[3,7]]");
+}
+
+#[test]
+fn test_dense_gradients() {
+    let mut dense = Dense::new(4, 3);
+    check_gradients(&mut dense, 5, 4);
+}
+
+#[test]
+fn test_dense_update() {
+    let mut dense = Dense::new(2, 1);
+    dense.set_parameters(vec![Matrix::from_str("[[1, 1]]"), Matrix::from_str("[[0]]")]);
+    dense.forward(&Matrix::from_str("[[1, 2]]"));
+    dense.backward(&Matrix::from_str("[[1]]"));
+    dense.update(0.5);
+    assert_eq!(dense.get_weights().to_string_fmt(1), "[[0.5,0.0]]");
+    assert_eq!(dense.get_biases().to_string_fmt(1), "[[-0.5]]");
+    for gradients in dense.gradients() {
+        assert!(gradients.as_vec().iter().all(|&g| g == 0.0));
+    }
+}
+
+#[test]
+fn test_activation_values() {
+    let inputs = Matrix::from_str("[[-2, 0, 3]]");
+    let cases = vec![
+        (ActivationFunction::Identity, "[[-2.0000,0.0000,3.0000]]"),
+        (ActivationFunction::Sigmoid, "[[0.1192,0.5000,0.9526]]"),
+        (ActivationFunction::Tanh, "[[-0.9640,0.0000,0.9951]]"),
+        (ActivationFunction::ReLU, "[[0.0000,0.0000,3.0000]]"),
+        (ActivationFunction::LeakyReLU(0.1), "[[-0.2000,0.0000,3.0000]]"),
+        (ActivationFunction::Softmax, "[[0.0064,0.0471,0.9465]]"),
+    ];
+    for (function, expected) in cases {
+        assert_eq!(Activation::new(function).predict(&inputs).to_string_fmt(4), expected);
+    }
+}
+
+#[test]
+fn test_activation_gradients() {
+    // Random inputs keep ReLU away from its kink at zero
+    let functions = vec![
+        ActivationFunction::Identity,
+        ActivationFunction::Sigmoid,
+        ActivationFunction::Tanh,
+        ActivationFunction::ReLU,
+        ActivationFunction::LeakyReLU(0.2),
+        ActivationFunction::Softmax,
+    ];
+    for function in functions {
+        let mut activation = Activation::new(function);
+        assert!(activation.parameters().is_empty());
+        check_gradients(&mut activation, 3, 4);
+    }
+}
+
+#[should_panic]
+#[test]
+fn test_set_parameters_wrong_size() {
+    let mut dense = Dense::new(2, 3);
+    dense.set_parameters(vec![Matrix::from_str("[[1, 2, 3], [4, 5, 6]]"), Matrix::from_str("[[0, 0, 0]]")]);
+}
+
+#[should_panic]
+#[test]
+fn test_set_parameters_wrong_count() {
+    let mut dense = Dense::new(2, 3);
+    dense.set_parameters(vec![Matrix::from_str("[[1, 2], [3, 4], [5, 6]]")]);
+}
+
+fn multilayer_model() -> Sequential {
+    let mut model = Sequential::new();
+    model.add(Dense::new(3, 5));
+    model.add(Activation::new(ActivationFunction::Tanh));
+    model.add(Dense::new(5, 4));
+    model.add(Activation::new(ActivationFunction::Softmax));
+    return model;
+}
+
+#[test]
+fn test_sequential_gradients() {
+    let mut model = multilayer_model();
+    assert_eq!(model.num_layers(), 4);
+    assert_eq!(model.parameters().len(), 4);
+    check_gradients(&mut model, 2, 3);
+}
+
+#[test]
+fn test_nested_sequential() {
+    let mut inner = Sequential::new();
+    inner.add(Dense::new(2, 3));
+    inner.add(Activation::new(ActivationFunction::Sigmoid));
+    let mut model = Sequential::new();
+    model.add(inner);
+    model.add(Dense::new(3, 1));
+    assert_eq!(model.num_layers(), 2);
+    assert_eq!(model.parameters().len(), 4);
+    check_gradients(&mut model, 3, 2);
+}
+
+#[test]
+fn test_sequential_learns_xor() {
+    let data: TrainingData = vec![
+        (vec![0.0, 0.0], vec![0.0]),
+        (vec![0.0, 1.0], vec![1.0]),
+        (vec![1.0, 0.0], vec![1.0]),
+        (vec![1.0, 1.0], vec![0.0]),
+    ];
+    let mut model = Sequential::new();
+    model.add(Dense::new(2, 8));
+    model.add(Activation::new(ActivationFunction::Tanh));
+    model.add(Dense::new(8, 1));
+    model.add(Activation::new(ActivationFunction::Sigmoid));
+    model.set_batch_size(4);
+    let losses = model.train(&data, 2.0, 2000);
+    assert_eq!(losses.len(), 2000);
+    assert!(losses[1999] < losses[0]);
+    for (inputs, targets) in &data {
+        assert!((model.execute(inputs)[0] - targets[0]).abs() < 0.2);
+    }
+}
+
+#[test]
+fn test_sequential_cross_entropy() {
+    // Three well separated clusters, one per class
+    let mut rng = rand::thread_rng();
+    let centers = [(0.0, 2.0), (2.0, -1.0), (-2.0, -1.0)];
+    let mut data: TrainingData = Vec::new();
+    for _ in 0..20 {
+        for (class, center) in centers.iter().enumerate() {
+            let inputs = vec![center.0 + rng.gen_range(-0.5, 0.5), center.1 + rng.gen_range(-0.5, 0.5)];
+            let mut targets = vec![0.0; 3];
+            targets[class] = 1.0;
+            data.push((inputs, targets));
+        }
+    }
+    let mut model = Sequential::new();
+    model.add(Dense::new(2, 3));
+    model.set_loss(Loss::CrossEntropy);
+    model.set_batch_size(10);
+    model.train(&data, 0.5, 100);
+    assert!(model.evaluate(&data) < 0.1);
+}
+
+#[should_panic]
+#[test]
+fn test_sequential_empty_data() {
+    let mut model = multilayer_model();
+    model.train(&Vec::new(), 0.1, 1);
+}
+
+#[test]
+fn test_inverted_dropout() {
+    let mut dropout = Dropout::new(0.25);
+    let inputs = Matrix::from_vec(&vec![1.0; 2000], 2, 1000);
+    let outputs = dropout.forward(&inputs).as_vec();
+    let kept = outputs.iter().filter(|&&x| x != 0.0).count();
+    assert!(outputs.iter().all(|&x| x == 0.0 || format!("{:.6}", x) == "1.333333"));
+    assert!(kept > 1400 && kept < 1600);
+
+    // Gradients only flow through the kept outputs, with the same scaling
+    let gradients = dropout.backward(&inputs);
+    assert_eq!(gradients.as_vec(), outputs);
+
+    // Nothing is dropped or scaled at inference
+    assert_eq!(dropout.predict(&inputs).as_vec(), inputs.as_vec());
+}
+
+#[test]
+fn test_standard_dropout() {
+    let mut dropout = Dropout::new(0.5);
+    dropout.set_variant(DropoutVariant::Standard);
+    let inputs = Matrix::from_vec(&vec![2.0; 1000], 1, 1000);
+    let outputs = dropout.forward(&inputs).as_vec();
+    assert!(outputs.iter().all(|&x| x == 0.0 || x == 2.0));
+    assert_eq!(dropout.predict(&inputs).as_vec(), vec![1.0; 1000]);
+
+    // Outside training mode forward matches predict
+    dropout.set_training(false);
+    assert_eq!(dropout.forward(&inputs).as_vec(), vec![1.0; 1000]);
+    assert_eq!(dropout.backward(&inputs).as_vec(), vec![1.0; 1000]);
+}
+
+#[test]
+fn test_dropout_seed() {
+    let inputs = random_matrix(4, 10);
+    let mut first = Dropout::new(0.5);
+    first.set_seed(7);
+    let mut second = Dropout::new(0.5);
+    second.set_seed(7);
+    for _ in 0..3 {
+        assert_eq!(first.forward(&inputs).as_vec(), second.forward(&inputs).as_vec());
+    }
+    // Successive masks differ
+    let mask = first.get_mask().as_vec();
+    first.forward(&inputs);
+    assert_ne!(first.get_mask().as_vec(), mask);
+}
+
+#[should_panic]
+#[test]
+fn test_dropout_invalid_rate() {
+    Dropout::new(1.0);
+}
+
+#[test]
+fn test_sequential_training_mode() {
+    let mut model = Sequential::new();
+    model.add(Dense::new(4, 20));
+    model.add(Dropout::new(0.5));
+    model.add(Dense::new(20, 2));
+    let inputs = random_matrix(3, 4);
+    let predicted = model.predict(&inputs).as_vec();
+    assert_ne!(model.forward(&inputs).as_vec(), predicted);
+    assert_eq!(model.execute(&inputs.get_row(0)), predicted[0..2].to_vec());
+
+    model.set_training(false);
+    assert_eq!(model.forward(&inputs).as_vec(), predicted);
+}
+
+#[test]
+fn test_layer_norm_forward() {
+    let mut norm = LayerNorm::new(3);
+    norm.set_epsilon(0.0);
+    let outputs = norm.forward(&Matrix::from_str("[[1, 2, 3], [5, 5, 8]]"));
+    assert_eq!(outputs.to_string_fmt(4), "[[-1.2247,0.0000,1.2247],[-0.7071,-0.7071,1.4142]]");
+
+    norm.set_parameters(vec![Matrix::from_str("[[2, 1, 1]]"), Matrix::from_str("[[0, 0, 10]]")]);
+    let outputs = norm.forward(&Matrix::from_str("[[1, 2, 3]]"));
+    assert_eq!(outputs.to_string_fmt(4), "[[-2.4495,0.0000,11.2247]]");
+    assert_eq!(norm.predict(&Matrix::from_str("[[1, 2, 3]]")).to_string_fmt(4), "[[-2.4495,0.0000,11.2247]]");
+}
+
+#[test]
+fn test_layer_norm_gradients() {
+    let mut norm = LayerNorm::new(4);
+    norm.set_parameters(vec![random_matrix(1, 4), random_matrix(1, 4)]);
+    check_gradients(&mut norm, 3, 4);
+}
+
+#[test]
+fn test_batch_norm_forward() {
+    let mut norm = BatchNorm::new(2);
+    norm.set_epsilon(0.0);
+    norm.set_momentum(0.5);
+    let inputs = Matrix::from_str("[[1, 10], [3, 10], [5, 40]]");
+    let outputs = norm.forward(&inputs);
+    assert_eq!(outputs.to_string_fmt(4), "[[-1.2247,-0.7071],[0.0000,-0.7071],[1.2247,1.4142]]");
+
+    // Halfway from (0, 1) to the batch mean (3, 20) and variance (8/3, 200)
+    assert_eq!(norm.get_running_mean().to_string_fmt(4), "[[1.5000,10.0000]]");
+    assert_eq!(norm.get_running_variance().to_string_fmt(4), "[[1.8333,100.5000]]");
+
+    norm.set_parameters(vec![Matrix::from_str("[[2, 1]]"), Matrix::from_str("[[1, 0]]")]);
+    norm.set_running_statistics(Matrix::from_str("[[3, 20]]"), Matrix::from_str("[[4, 100]]"));
+    let expected = "[[-1.0000,-1.0000],[1.0000,-1.0000],[3.0000,2.0000]]";
+    assert_eq!(norm.predict(&inputs).to_string_fmt(4), expected);
+    norm.set_training(false);
+    assert_eq!(norm.forward(&inputs).to_string_fmt(4), expected);
+    assert_eq!(norm.get_running_mean().to_string_fmt(1), "[[3.0,20.0]]");
+}
+
+#[test]
+fn test_batch_norm_gradients() {
+    let mut norm = BatchNorm::new(3);
+    norm.set_parameters(vec![random_matrix(1, 3), random_matrix(1, 3)]);
+    check_gradients(&mut norm, 5, 3);
+
+    // With the running averages in evaluation mode
+    let mut norm = BatchNorm::new(3);
+    norm.set_parameters(vec![random_matrix(1, 3), random_matrix(1, 3)]);
+    norm.set_running_statistics(random_matrix(1, 3), Matrix::from_str("[[0.5, 1, 2]]"));
+    norm.set_training(false);
+    check_gradients(&mut norm, 5, 3);
+}
+
+#[test]
+fn test_batch_norm_running_statistics_converge() {
+    let mut rng = rand::thread_rng();
+    let mut norm = BatchNorm::new(1);
+    for _ in 0..200 {
+        let data: Vec<f64> = (0..50).map(|_| 5.0 + rng.gen_range(-3.0, 3.0)).collect();
+        norm.forward(&Matrix::from_vec(&data, 50, 1));
+    }
+    // Uniform on [2, 8] has mean 5 and variance 3
+    assert!((norm.get_running_mean().get_at_index(0, 0) - 5.0).abs() < 0.3);
+    assert!((norm.get_running_variance().get_at_index(0, 0) - 3.0).abs() < 0.5);
+}
+
+#[test]
+fn test_sequential_with_normalization() {
+    let mut model = Sequential::new();
+    model.add(Dense::new(3, 8));
+    model.add(BatchNorm::new(8));
+    model.add(Activation::new(ActivationFunction::ReLU));
+    model.add(Dense::new(8, 8));
+    model.add(LayerNorm::new(8));
+    model.add(Activation::new(ActivationFunction::Tanh));
+    model.add(Dense::new(8, 1));
+    assert_eq!(model.parameters().len(), 10);
+    check_gradients(&mut model, 4, 3);
+
+    // Fit y = x0 - 2 x1 + x2 on inputs far from zero mean
+    model.set_seed(7);
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut data: TrainingData = Vec::new();
+    for _ in 0..64 {
+        let inputs: Vec<f64> = (0..3).map(|_| 10.0 + rng.gen_range(-1.0, 1.0)).collect();
+        let target = inputs[0] - 2.0 * inputs[1] + inputs[2];
+        data.push((inputs, vec![target]));
+    }
+    model.set_batch_size(16);
+    let before = model.evaluate(&data);
+    model.train(&data, 0.02, 1000);
+    assert!(model.evaluate(&data) < 0.3 * before);
+}
+
+#[test]
+fn test_initializer_variances() {
+    let (fan_out, fan_in) = (200, 300);
+    let cases = vec![
+        (Initializer::FanInUniform, 1.0 / (3.0 * fan_in as f64), true),
+        (Initializer::XavierUniform, 2.0 / (fan_in + fan_out) as f64, true),
+        (Initializer::XavierNormal, 2.0 / (fan_in + fan_out) as f64, false),
+        (Initializer::HeUniform, 2.0 / fan_in as f64, true),
+        (Initializer::HeNormal, 2.0 / fan_in as f64, false),
+        (Initializer::LeCunUniform, 1.0 / fan_in as f64, true),
+        (Initializer::LeCunNormal, 1.0 / fan_in as f64, false),
+    ];
+    let mut rng = StdRng::seed_from_u64(42);
+    for (initializer, variance, uniform) in cases {
+        let weights = initializer.sample(fan_out, fan_in, &mut rng);
+        assert_eq!(weights.size(), (fan_out, fan_in));
+        let values = weights.as_vec();
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let sample_variance = values.iter().map(|w| (w - mean).powi(2)).sum::<f64>() / values.len() as f64;
+        assert!(mean.abs() < 0.1 * variance.sqrt());
+        assert!((sample_variance / variance - 1.0).abs() < 0.05);
+        if uniform {
+            let bound = (3.0 * variance).sqrt();
+            assert!(values.iter().all(|w| w.abs() <= bound));
+        }
+    }
+    let zeros = Initializer::Zeros.sample(3, 4, &mut rng);
+    assert!(zeros.as_vec().iter().all(|&w| w == 0.0));
+}
+
+fn assert_identity(matrix: &Matrix) {
+    let (rows, cols) = matrix.size();
+    assert_eq!(rows, cols);
+    for r in 0..rows {
+        for c in 0..cols {
+            let expected = if r == c { 1.0 } else { 0.0 };
+            assert!((matrix.get_at_index(r, c) - expected).abs() < 1e-10);
+        }
+    }
+}
+
+#[test]
+fn test_orthogonal_initializer() {
+    let mut rng = StdRng::seed_from_u64(1);
+    // Orthonormal rows when there are fewer outputs than inputs
+    let weights = Initializer::Orthogonal.sample(3, 5, &mut rng);
+    assert_eq!(weights.size(), (3, 5));
+    assert_identity(&weights.dot_prod(&weights.transpose()));
+    // Orthonormal columns otherwise
+    let weights = Initializer::Orthogonal.sample(5, 3, &mut rng);
+    assert_eq!(weights.size(), (5, 3));
+    assert_identity(&weights.transpose().dot_prod(&weights));
+    let weights = Initializer::Orthogonal.sample(4, 4, &mut rng);
+    assert_identity(&weights.dot_prod(&weights.transpose()));
+}
+
+#[test]
+fn test_dense_initialize_with_seed() {
+    let mut first = Dense::new(4, 3);
+    first.set_initializer(Initializer::HeNormal);
+    first.set_parameters(vec![random_matrix(3, 4), random_matrix(1, 3)]);
+    first.initialize(&mut StdRng::seed_from_u64(9));
+    assert!(first.get_biases().as_vec().iter().all(|&b| b == 0.0));
+
+    let mut second = Dense::new(4, 3);
+    second.set_initializer(Initializer::HeNormal);
+    second.initialize(&mut StdRng::seed_from_u64(9));
+    assert_eq!(first.get_weights().as_vec(), second.get_weights().as_vec());
+
+    second.initialize(&mut StdRng::seed_from_u64(10));
+    assert_ne!(first.get_weights().as_vec(), second.get_weights().as_vec());
+}
+
+#[test]
+fn test_dense_set_initializer_applies_on_initialize() {
+    let mut dense = Dense::new(4, 3);
+    let before = dense.get_weights().as_vec();
+    dense.set_initializer(Initializer::Zeros);
+    assert_eq!(dense.get_weights().as_vec(), before);
+
+    let mut model = Sequential::new();
+    model.add(dense);
+    model.set_seed(3);
+    assert!(model.parameters()[0].as_vec().iter().all(|&w| w == 0.0));
+}
@@ -0,0 +1,118 @@
+use super::super::matrix::Matrix;
+use super::super::logistic_regression::{LogisticRegression, Solver, Penalty, ClassWeight};
+
+fn binary_data() -> (Matrix, Vec<usize>) {
+    let inputs = Matrix::from_str("[[0.0, 0.2], [0.3, 0.1], [0.5, 0.6], [0.2, 0.9], [2.0, 2.1], [2.5, 1.8], [1.9, 2.6], [2.8, 2.2]]");
+    let labels = vec![0, 0, 0, 0, 1, 1, 1, 1];
+    return (inputs, labels);
+}
+
+fn multiclass_data() -> (Matrix, Vec<usize>) {
+    let inputs = Matrix::from_str("[[0.0, 0.0], [0.3, 0.2], [0.1, 0.4], [3.0, 0.0], [3.2, 0.3], [2.8, 0.1], [0.0, 3.0], [0.2, 3.3], [0.4, 2.9]]");
+    let labels = vec![0, 0, 0, 1, 1, 1, 2, 2, 2];
+    return (inputs, labels);
+}
+
+#[test]
+fn test_logistic_regression_binary_gradient_descent() {
+    let (inputs, labels) = binary_data();
+    let mut model = LogisticRegression::new(2, 2);
+    model.set_learning_rate(0.5);
+    model.fit(&inputs, &labels);
+
+    assert_eq!(model.predict(&inputs), labels);
+    assert_eq!(model.get_weights().size(), (3, 1));
+
+    let probabilities = model.predict_proba(&Matrix::from_str("[[0.0, 0.0], [3.0, 3.0]]"));
+    assert_eq!(probabilities.size(), (2, 2));
+    assert!(probabilities.get_at_index(0, 0) > 0.9);
+    assert!(probabilities.get_at_index(1, 1) > 0.9);
+}
+
+#[test]
+fn test_logistic_regression_newton_matches_gradient_descent() {
+    let (inputs, labels) = binary_data();
+
+    let mut newton = LogisticRegression::new(2, 2);
+    newton.set_solver(Solver::Newton);
+    newton.set_penalty(Penalty::L2(0.1));
+    newton.set_max_iterations(50);
+    newton.fit(&inputs, &labels);
+
+    let mut descent = LogisticRegression::new(2, 2);
+    descent.set_penalty(Penalty::L2(0.1));
+    descent.set_learning_rate(1.0);
+    descent.set_max_iterations(20000);
+    descent.set_tolerance(1e-10);
+    descent.fit(&inputs, &labels);
+
+    assert_eq!(newton.predict(&inputs), labels);
+    assert_eq!(newton.get_weights().to_string_fmt(3), descent.get_weights().to_string_fmt(3));
+    assert!(newton.loss(&inputs, &labels) <= descent.loss(&inputs, &labels) + 1e-9);
+}
+
+#[test]
+fn test_logistic_regression_multinomial() {
+    let (inputs, labels) = multiclass_data();
+    for solver in [Solver::GradientDescent, Solver::Newton] {
+        let mut model = LogisticRegression::new(2, 3);
+        model.set_solver(solver);
+        model.set_penalty(Penalty::L2(0.01));
+        model.fit(&inputs, &labels);
+
+        assert_eq!(model.predict(&inputs), labels);
+
+        let probabilities = model.predict_proba(&inputs);
+        assert_eq!(probabilities.size(), (9, 3));
+        for i in 0..9 {
+            let sum: f64 = probabilities.get_row(i).iter().sum();
+            assert!((sum - 1.0).abs() < 1e-10);
+        }
+    }
+}
+
+#[test]
+fn test_logistic_regression_l1_zeroes_irrelevant_feature() {
+    // The second feature carries no information about the label
+    let inputs = Matrix::from_str("[[0.0, 1.0], [0.5, -1.0], [1.0, 1.0], [3.0, -1.0], [3.5, 1.0], [4.0, -1.0]]");
+    let labels = vec![0, 0, 0, 1, 1, 1];
+    let mut model = LogisticRegression::new(2, 2);
+    model.set_penalty(Penalty::L1(0.05));
+    model.fit(&inputs, &labels);
+
+    assert_eq!(model.get_weights().get_at_index(2, 0), 0.0);
+    assert!(model.get_weights().get_at_index(1, 0) > 0.0);
+}
+
+#[test]
+fn test_logistic_regression_class_weights() {
+    // One positive sample among many negatives overlapping with it
+    let inputs = Matrix::from_str("[[0.0], [0.1], [0.2], [0.3], [0.4], [0.5], [0.6], [0.7], [0.6]]");
+    let labels = vec![0, 0, 0, 0, 0, 0, 0, 0, 1];
+    let query = Matrix::from_str("[[0.6]]");
+
+    let mut uniform = LogisticRegression::new(1, 2);
+    uniform.fit(&inputs, &labels);
+
+    let mut balanced = LogisticRegression::new(1, 2);
+    balanced.set_class_weight(ClassWeight::Balanced);
+    balanced.fit(&inputs, &labels);
+
+    let mut custom = LogisticRegression::new(1, 2);
+    custom.set_class_weight(ClassWeight::Custom(vec![1.0, 8.0]));
+    custom.fit(&inputs, &labels);
+
+    assert_eq!(uniform.predict(&query), vec![0]);
+    assert_eq!(balanced.predict(&query), vec![1]);
+    assert_eq!(custom.predict(&query), vec![1]);
+}
+
+#[should_panic]
+#[test]
+fn test_logistic_regression_newton_rejects_l1() {
+    let (inputs, labels) = binary_data();
+    let mut model = LogisticRegression::new(2, 2);
+    model.set_solver(Solver::Newton);
+    model.set_penalty(Penalty::L1(0.1));
+    model.fit(&inputs, &labels);
+}
@@ -0,0 +1,38 @@
+use super::super::matrix::Matrix;
+use super::super::loss::Loss;
+
+#[test]
+fn test_mean_squared_error() {
+    let outputs = Matrix::from_str("[[1, 2], [3, 4]]");
+    let targets = Matrix::from_str("[[0, 2], [3, 2]]");
+
+    assert_eq!(Loss::MeanSquaredError.compute(&outputs, &targets), 1.25);
+    assert_eq!(Loss::MeanSquaredError.gradient(&outputs, &targets).to_string_fmt(1), "[[0.5,0.0],[0.0,1.0]]");
+}
+
+#[test]
+fn test_cross_entropy() {
+    let outputs = Matrix::from_str("[[1, 2, 3], [0, 0, 0]]");
+    let targets = Matrix::from_str("[[0, 0, 1], [1, 0, 0]]");
+
+    // Average of -ln(softmax) at the target classes
+    let expected = (0.4076059644 + 3f64.ln()) / 2.0;
+    assert_eq!(format!("{:.8}", Loss::CrossEntropy.compute(&outputs, &targets)), format!("{:.8}", expected));
+
+    // softmax - targets, divided by the number of rows
+    let gradient = Loss::CrossEntropy.gradient(&outputs, &targets);
+    assert_eq!(gradient.to_string_fmt(4), "[[0.0450,0.1224,-0.1674],[-0.3333,0.1667,0.1667]]");
+}
+
+#[test]
+fn test_cross_entropy_large_scores() {
+    let outputs = Matrix::from_str("[[1000, 0]]");
+    let targets = Matrix::from_str("[[1, 0]]");
+    assert_eq!(Loss::CrossEntropy.compute(&outputs, &targets), 0.0);
+}
+
+#[should_panic]
+#[test]
+fn test_loss_mismatched_sizes() {
+    Loss::MeanSquaredError.compute(&Matrix::from_str("[[1, 2]]"), &Matrix::from_str("[[1]]"));
+}
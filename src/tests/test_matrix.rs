@@ -130,6 +130,46 @@ fn test_matrix_subtract_invalid_dimensions() {
     matrix_a.subtract(&matrix_b);
 }
 
+#[test]
+fn test_matrix_hadamard() {
+    let matrix_a = Matrix::from_str("[[1, 2], [3, 4]]");
+    let matrix_b = Matrix::from_str("[[2, 0], [-1, 3]]");
+
+    assert_eq!(matrix_a.hadamard(&matrix_b).to_string(), "[[2,0],[-3,12]]");
+}
+
+#[should_panic]
+#[test]
+fn test_matrix_hadamard_invalid_dimensions() {
+    let matrix_a = Matrix::from_str("[[1, 2], [3, 4]]");
+    let matrix_b = Matrix::from_str("[[1, 2]]");
+
+    matrix_a.hadamard(&matrix_b);
+}
+
+#[test]
+fn test_matrix_columns() {
+    let matrix = Matrix::from_str("[[1, 2, 3], [4, 5, 6]]");
+    let left = matrix.get_columns(0, 1);
+    let right = matrix.get_columns(1, 2);
+    assert_eq!(left.to_string(), "[[1],[4]]");
+    assert_eq!(right.to_string(), "[[2,3],[5,6]]");
+    assert_eq!(Matrix::concat_columns(&vec![right, left]).to_string(), "[[2,3,1],[5,6,4]]");
+}
+
+#[should_panic]
+#[test]
+fn test_matrix_columns_out_of_bound() {
+    Matrix::from_str("[[1, 2, 3]]").get_columns(2, 2);
+}
+
+#[test]
+fn test_matrix_row_broadcast() {
+    let matrix = Matrix::from_str("[[1, 2], [3, 4], [5, 6]]");
+    assert_eq!(matrix.add_row(&Matrix::from_str("[[10, 20]]")).to_string(), "[[11,22],[13,24],[15,26]]");
+    assert_eq!(matrix.sum_rows().to_string(), "[[9,12]]");
+}
+
 #[should_panic]
 #[test]
 fn test_matrix_dot_prod_invalid_dimensions() {
@@ -198,4 +238,82 @@ fn test_matrix_map_with_index() {
     let mut matrix_a = Matrix::from_vec(&vec![1.0, 2.0, 3.0], 3, 1);
     matrix_a.map_with_index(|x, i| x + i as f64);
     assert_eq!(matrix_a.to_string(), "[[1],[3],[5]]");
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_matrix_identity() {
+    let matrix_a = Matrix::identity(3);
+    assert_eq!(matrix_a.to_string(), "[[1,0,0],[0,1,0],[0,0,1]]");
+}
+
+#[test]
+fn test_matrix_get_row() {
+    let matrix_a = Matrix::from_vec(&vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 2, 3);
+    assert_eq!(matrix_a.get_row(1), vec![4.0, 5.0, 6.0]);
+}
+
+#[test]
+fn test_matrix_rows() {
+    let matrix_a = Matrix::from_str("[[1, 2, 3], [4, 5, 6]]");
+    let rows = matrix_a.get_rows();
+    assert_eq!(rows, vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]]);
+    assert_eq!(Matrix::from_rows(&rows).to_string(), matrix_a.to_string());
+    assert_eq!(Matrix::from_rows(&Vec::new()).size(), (0, 0));
+}
+
+#[should_panic]
+#[test]
+fn test_matrix_from_rows_rejects_ragged_rows() {
+    Matrix::from_rows(&vec![vec![1.0, 2.0], vec![3.0]]);
+}
+
+#[test]
+fn test_matrix_inverse() {
+    let matrix_a = Matrix::from_str("[[4, 7], [2, 6]]");
+    let matrix_b = matrix_a.inverse();
+    assert_eq!(matrix_b.to_string_fmt(1), "[[0.6,-0.7],[-0.2,0.4]]");
+    assert_eq!(matrix_a.dot_prod(&matrix_b).to_string_fmt(5), "[[1.00000,0.00000],[0.00000,1.00000]]");
+}
+
+#[should_panic]
+#[test]
+fn test_matrix_inverse_singular() {
+    let matrix_a = Matrix::from_str("[[1, 2], [2, 4]]");
+    matrix_a.inverse();
+}
+
+#[test]
+fn test_matrix_symmetric_eigen() {
+    let matrix_a = Matrix::from_str("[[2, 1, 0], [1, 2, 0], [0, 0, 5]]");
+    let (values, vectors) = matrix_a.symmetric_eigen();
+    let formatted: Vec<String> = values.iter().map(|v| format!("{:.6}", v)).collect();
+    assert_eq!(formatted, vec!["5.000000", "3.000000", "1.000000"]);
+
+    // A v = lambda v for every column
+    let product = matrix_a.dot_prod(&vectors);
+    for (col, value) in values.iter().enumerate() {
+        for row in 0..3 {
+            let expected = value * vectors.get_at_index(row, col);
+            assert!((product.get_at_index(row, col) - expected).abs() < 1e-9);
+        }
+    }
+
+    // The eigenvectors are orthonormal
+    let gram = vectors.transpose().dot_prod(&vectors);
+    assert_eq!(gram.to_string_fmt(6).replace("-", ""), Matrix::identity(3).to_string_fmt(6));
+}
+
+#[test]
+fn test_matrix_cholesky() {
+    let matrix_a = Matrix::from_str("[[4, 2], [2, 3]]");
+    let lower = matrix_a.cholesky();
+    assert_eq!(lower.to_string_fmt(5), "[[2.00000,0.00000],[1.00000,1.41421]]");
+    assert_eq!(lower.dot_prod(&lower.transpose()).to_string_fmt(5), "[[4.00000,2.00000],[2.00000,3.00000]]");
+}
+
+#[should_panic]
+#[test]
+fn test_matrix_cholesky_not_positive_definite() {
+    let matrix_a = Matrix::from_str("[[1, 2], [2, 1]]");
+    matrix_a.cholesky();
+}
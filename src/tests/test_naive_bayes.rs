@@ -0,0 +1,128 @@
+use super::super::matrix::Matrix;
+use super::super::naive_bayes::{GaussianNB, MultinomialNB, BernoulliNB};
+
+fn gaussian_data() -> (Matrix, Vec<usize>) {
+    let inputs = Matrix::from_str("[[1.0, 2.0], [2.0, 3.0], [3.0, 1.0], [6.0, 8.0], [7.0, 9.0], [8.0, 7.0]]");
+    let labels = vec![0, 0, 0, 1, 1, 1];
+    return (inputs, labels);
+}
+
+#[test]
+fn test_gaussian_nb_fit() {
+    let (inputs, labels) = gaussian_data();
+    let mut model = GaussianNB::new(2, 2);
+    model.set_var_smoothing(0.0);
+    model.fit(&inputs, &labels);
+
+    assert_eq!(model.get_means(), &vec![vec![2.0, 2.0], vec![7.0, 8.0]]);
+    let variances: Vec<String> = model.get_variances().iter()
+        .map(|v| format!("{:.4},{:.4}", v[0], v[1]))
+        .collect();
+    assert_eq!(variances, vec!["0.6667,0.6667", "0.6667,0.6667"]);
+
+    assert_eq!(model.predict(&inputs), labels);
+    assert_eq!(model.predict(&Matrix::from_str("[[2.5, 2.5], [6.5, 7.5]]")), vec![0, 1]);
+
+    // Equidistant from both means with equal priors and variances
+    let probabilities = model.predict_proba(&Matrix::from_str("[[4.5, 5.0]]"));
+    assert_eq!(probabilities.to_string_fmt(4), "[[0.5000,0.5000]]");
+}
+
+#[test]
+fn test_gaussian_nb_partial_fit_matches_fit() {
+    let (inputs, labels) = gaussian_data();
+    let mut full = GaussianNB::new(2, 2);
+    full.fit(&inputs, &labels);
+
+    let mut streamed = GaussianNB::new(2, 2);
+    streamed.partial_fit(&Matrix::from_str("[[1.0, 2.0], [6.0, 8.0]]"), &vec![0, 1]);
+    streamed.partial_fit(&Matrix::from_str("[[2.0, 3.0], [3.0, 1.0]]"), &vec![0, 0]);
+    streamed.partial_fit(&Matrix::from_str("[[7.0, 9.0], [8.0, 7.0]]"), &vec![1, 1]);
+
+    let query = Matrix::from_str("[[3.0, 4.0], [5.0, 5.0]]");
+    assert_eq!(streamed.predict_proba(&query).to_string_fmt(10), full.predict_proba(&query).to_string_fmt(10));
+    for c in 0..2 {
+        for j in 0..2 {
+            assert!((streamed.get_variances()[c][j] - full.get_variances()[c][j]).abs() < 1e-12);
+        }
+    }
+}
+
+#[test]
+fn test_multinomial_nb() {
+    // Word counts for [ball, goal, vote, law]
+    let inputs = Matrix::from_str("[[3, 2, 0, 0], [2, 3, 1, 0], [0, 0, 3, 2], [0, 1, 2, 3]]");
+    let labels = vec![0, 0, 1, 1];
+    let mut model = MultinomialNB::new(4, 2);
+    model.fit(&inputs, &labels);
+
+    // (count + 1) / (total + 4) for class 0: ball 6/15, goal 6/15, vote 2/15, law 1/15
+    let expected = ["0.400000", "0.400000", "0.133333", "0.066667"];
+    for (j, &expected) in expected.iter().enumerate() {
+        assert_eq!(format!("{:.6}", model.feature_log_proba()[0][j].exp()), expected);
+    }
+
+    assert_eq!(model.predict(&Matrix::from_str("[[1, 1, 0, 0], [0, 0, 1, 1]]")), vec![0, 1]);
+
+    // Laplace smoothing keeps probabilities away from zero for unseen words
+    let probabilities = model.predict_proba(&Matrix::from_str("[[0, 0, 0, 5]]"));
+    assert!(probabilities.get_at_index(0, 0) > 0.0);
+    assert!(probabilities.get_at_index(0, 1) > 0.9);
+}
+
+#[test]
+fn test_multinomial_nb_log_proba_and_partial_fit() {
+    let inputs = Matrix::from_str("[[3, 2, 0, 0], [2, 3, 1, 0], [0, 0, 3, 2], [0, 1, 2, 3]]");
+    let labels = vec![0, 0, 1, 1];
+    let mut model = MultinomialNB::new(4, 2);
+    model.set_alpha(0.5);
+    model.partial_fit(&Matrix::from_str("[[3, 2, 0, 0], [0, 0, 3, 2]]"), &vec![0, 1]);
+    model.partial_fit(&Matrix::from_str("[[2, 3, 1, 0], [0, 1, 2, 3]]"), &vec![0, 1]);
+
+    let mut full = MultinomialNB::new(4, 2);
+    full.set_alpha(0.5);
+    full.fit(&inputs, &labels);
+
+    let log_proba = model.predict_log_proba(&inputs);
+    assert_eq!(log_proba.to_string_fmt(10), full.predict_log_proba(&inputs).to_string_fmt(10));
+    for i in 0..4 {
+        let total: f64 = log_proba.get_row(i).iter().map(|x| x.exp()).sum();
+        assert!((total - 1.0).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn test_bernoulli_nb() {
+    let inputs = Matrix::from_str("[[1, 1, 0], [1, 0, 0], [0, 0, 1], [0, 1, 1]]");
+    let labels = vec![0, 0, 1, 1];
+    let mut model = BernoulliNB::new(3, 2);
+    model.fit(&inputs, &labels);
+
+    // (count + 1) / (class_count + 2): feature 0 is present in both class 0 rows
+    assert_eq!(format!("{:.4}", model.feature_log_proba()[0][0].exp()), "0.7500");
+    assert_eq!(format!("{:.4}", model.feature_log_proba()[1][0].exp()), "0.2500");
+
+    assert_eq!(model.predict(&inputs), labels);
+    assert_eq!(model.predict(&Matrix::from_str("[[1, 0, 0], [0, 0, 1]]")), vec![0, 1]);
+
+    // Absent features count as evidence too
+    let probabilities = model.predict_proba(&Matrix::from_str("[[0, 0, 0]]"));
+    assert_eq!(probabilities.to_string_fmt(4), "[[0.5000,0.5000]]");
+}
+
+#[test]
+fn test_bernoulli_nb_binarize() {
+    let inputs = Matrix::from_str("[[0.9, 0.1], [0.8, 0.2], [0.1, 0.7], [0.2, 0.9]]");
+    let labels = vec![0, 0, 1, 1];
+    let mut model = BernoulliNB::new(2, 2);
+    model.set_binarize(0.5);
+    model.fit(&inputs, &labels);
+    assert_eq!(model.predict(&Matrix::from_str("[[0.6, 0.4], [0.3, 0.6]]")), vec![0, 1]);
+}
+
+#[should_panic]
+#[test]
+fn test_multinomial_nb_rejects_negative_counts() {
+    let mut model = MultinomialNB::new(2, 2);
+    model.fit(&Matrix::from_str("[[1, -1], [0, 1]]"), &vec![0, 1]);
+}
@@ -1,5 +1,11 @@
+extern crate rand;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
 use super::super::matrix::Matrix;
-use super::super::neural_network::NeuralNetwork;
+use super::super::layers::{Layer, Dense, Initializer, Activation, ActivationFunction, BatchNorm, Dropout};
+use super::super::neural_network::{NeuralNetwork, Sequential, TrainingData, GradientClipping, EarlyStopping, Metric};
 
 #[test]
 fn test_nn_set_weights() {
@@ -21,6 +27,20 @@ fn test_nn_set_weights() {
     }
 }
 
+#[test]
+fn test_nn_get_weights_tracks_changes() {
+    let mut nn = NeuralNetwork::new(2, 1);
+    nn.set_weights(&vec![Matrix::from_str("[[1, 0], [0, 1]]")]);
+    let weights: &Vec<Matrix> = nn.get_weights();
+    assert_eq!(weights[0].to_string(), "[[1,0],[0,1]]");
+
+    // Changes made through the model show up in the borrowed weights
+    nn.get_model_mut().set_parameters(vec![Matrix::from_str("[[0, 2], [2, 0]]")]);
+    assert_eq!(nn.get_weights()[0].to_string(), "[[0,2],[2,0]]");
+    nn.train(&vec![(vec![1.0, 0.0], vec![1.0, 0.0])], 0.5, 1);
+    assert_ne!(nn.get_weights()[0].as_vec(), vec![0.0, 2.0, 2.0, 0.0]);
+}
+
 #[test]
 fn test_nn_randomize_weights() {
     let num_inputs = 2;
@@ -28,7 +48,7 @@ fn test_nn_randomize_weights() {
     let mut nn = NeuralNetwork::new(num_inputs, num_layers);
     nn.randomize_weights();
 
-    let bound: f64 = 0.70710678118654752440084436210485; // 1 / (sqrt(2)
+    let bound: f64 = std::f64::consts::FRAC_1_SQRT_2; // 1 / (sqrt(2)
 
     for weights in nn.get_weights() {
         for i in 0..num_inputs {
@@ -54,7 +74,7 @@ fn test_nn_get_outputs_for_one_layer() {
     let outputs = nn.get_outputs(&inputs);
     assert_eq!(outputs.len(), 2);
 
-    let expected = vec![
+    let expected = [
         String::from("[[1.0000000000],[1.0000000000]]"),
         String::from("[[0.8519528020],[0.6791786992]]")
     ];
@@ -79,7 +99,7 @@ fn test_nn_get_outputs_for_more_than_one_layer() {
     let outputs = nn.get_outputs(&inputs);
     assert_eq!(outputs.len(), 3);
     
-    let expected = vec![
+    let expected = [
         String::from("[[1.0000000000],[1.0000000000]]"),
         String::from("[[0.8519528020],[0.6791786992]]"),
         String::from("[[0.6347333953],[0.7888726343]]"),
@@ -91,6 +111,7 @@ fn test_nn_get_outputs_for_more_than_one_layer() {
 }
 
 #[test]
+#[allow(deprecated)]
 fn test_nn_get_errors() {
     let num_inputs = 2;
     let num_layers = 2;
@@ -104,7 +125,7 @@ fn test_nn_get_errors() {
     let targets = vec![0.0, 1.0];
     let errors = nn.get_errors(&inputs, &targets);
 
-    let expected = vec![
+    let expected = [
         String::from("[[-0.2518116765],[-0.0927905032]]"),
         String::from("[[-0.0531196660],[-0.2649226808]]"),
         String::from("[[-0.6347333953],[0.2111273657]]"),
@@ -116,6 +137,7 @@ fn test_nn_get_errors() {
 }
 
 #[test]
+#[allow(deprecated)]
 fn test_nn_get_deltas() {
     let num_inputs = 2;
     let num_layers = 2;
@@ -133,7 +155,7 @@ fn test_nn_get_deltas() {
 
     assert_eq!(deltas.len(), 2);
 
-    let expected = vec![
+    let expected = [
         String::from("[[-0.0006699942,-0.0006699942],[-0.0057725326,-0.0057725326]]"),
         String::from("[[-0.0125374207,-0.0099948601],[0.0029957908,0.0023882512]]"),
     ];
@@ -141,4 +163,339 @@ fn test_nn_get_deltas() {
     for i in 0..deltas.len() {
         assert_eq!(deltas[i].to_string_fmt(10), expected[i]);
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_nn_randomize_weights_replaces_layers() {
+    let mut nn = NeuralNetwork::new(3, 2);
+    nn.randomize_weights();
+    let first: Vec<Matrix> = nn.get_weights().iter().map(Matrix::clone).collect();
+    nn.randomize_weights();
+    let second = nn.get_weights();
+    assert_eq!(second.len(), 2);
+    assert_eq!(nn.get_model().num_layers(), 4);
+    assert_ne!(first[0].to_string(), second[0].to_string());
+}
+
+fn single_dense_model(weights: &str, bias: &str) -> Sequential {
+    let mut dense = Dense::new(2, 1);
+    dense.set_parameters(vec![Matrix::from_str(weights), Matrix::from_str(bias)]);
+    let mut model = Sequential::new();
+    model.add(dense);
+    return model;
+}
+
+#[test]
+fn test_weight_penalties() {
+    let mut model = single_dense_model("[[1, -2]]", "[[5]]");
+    model.set_l1_penalty(0.1);
+    model.set_l2_penalty(0.2);
+    // Only the weights count: 0.1 * 3 + 0.5 * 0.2 * 5
+    assert_eq!(format!("{:.6}", model.penalty()), "0.800000");
+
+    // The data gradient is zero, leaving only the penalty gradients
+    // [0.1 + 0.2 * 1, -0.1 + 0.2 * -2]
+    let data: TrainingData = vec![(vec![0.0, 0.0], vec![5.0])];
+    let losses = model.train(&data, 0.5, 1);
+    assert_eq!(format!("{:.6}", losses[0]), "0.800000");
+    assert_eq!(model.parameters()[0].to_string_fmt(4), "[[0.8500,-1.7500]]");
+    assert_eq!(model.parameters()[1].to_string_fmt(4), "[[5.0000]]");
+    assert_eq!(format!("{:.6}", model.evaluate(&data)), "0.000000");
+}
+
+#[test]
+fn test_l2_penalty_shrinks_weights() {
+    let mut rng = StdRng::seed_from_u64(21);
+    let data: TrainingData = (0..20)
+        .map(|_| {
+            let inputs = vec![rng.gen_range(-1.0, 1.0), rng.gen_range(-1.0, 1.0)];
+            let target = 3.0 * inputs[0] - 2.0 * inputs[1];
+            (inputs, vec![target])
+        })
+        .collect();
+    let norm = |model: &Sequential| -> f64 { model.parameters()[0].as_vec().iter().map(|w| w * w).sum::<f64>() };
+
+    // Same seed, so both models start from the same weights and see the
+    // samples in the same order; only the penalty differs
+    let seeded_model = || -> Sequential {
+        let mut model = Sequential::new();
+        model.add(Dense::new(2, 1));
+        model.set_seed(13);
+        return model;
+    };
+    let mut plain = seeded_model();
+    plain.train(&data, 0.1, 200);
+    let mut penalized = seeded_model();
+    penalized.set_l2_penalty(1.0);
+    penalized.train(&data, 0.1, 200);
+    assert!(norm(&penalized) < 0.5 * norm(&plain));
+    assert!(penalized.evaluate(&data) > plain.evaluate(&data));
+}
+
+#[test]
+fn test_gradient_clipping() {
+    // Gradients are [[3, 6]] for the weights and [[3]] for the bias
+    let data: TrainingData = vec![(vec![1.0, 2.0], vec![0.0])];
+
+    let mut model = single_dense_model("[[1, 1]]", "[[0]]");
+    model.set_gradient_clipping(GradientClipping::Value(0.5));
+    model.train(&data, 1.0, 1);
+    assert_eq!(model.parameters()[0].to_string_fmt(4), "[[0.5000,0.5000]]");
+    assert_eq!(model.parameters()[1].to_string_fmt(4), "[[-0.5000]]");
+
+    // Scaled by 1 / sqrt(54) to unit norm
+    let mut model = single_dense_model("[[1, 1]]", "[[0]]");
+    model.set_gradient_clipping(GradientClipping::GlobalNorm(1.0));
+    model.train(&data, 1.0, 1);
+    assert_eq!(model.parameters()[0].to_string_fmt(4), "[[0.5918,0.1835]]");
+    assert_eq!(model.parameters()[1].to_string_fmt(4), "[[-0.4082]]");
+
+    // Below the threshold nothing changes
+    let mut model = single_dense_model("[[1, 1]]", "[[0]]");
+    model.set_gradient_clipping(GradientClipping::GlobalNorm(10.0));
+    model.train(&data, 1.0, 1);
+    assert_eq!(model.parameters()[0].to_string_fmt(4), "[[-2.0000,-5.0000]]");
+}
+
+#[test]
+fn test_max_norm() {
+    let mut dense = Dense::new(2, 2);
+    dense.set_parameters(vec![Matrix::from_str("[[3, 4], [0.3, 0.4]]"), Matrix::from_str("[[10, 0]]")]);
+    let mut model = Sequential::new();
+    model.add(dense);
+    model.set_max_norm(1.0);
+    let data: TrainingData = vec![(vec![0.0, 0.0], vec![10.0, 0.0])];
+    model.train(&data, 0.1, 1);
+    // Only rows above the limit are rescaled, and biases are untouched
+    assert_eq!(model.parameters()[0].to_string_fmt(4), "[[0.6000,0.8000],[0.3000,0.4000]]");
+    assert_eq!(model.parameters()[1].to_string_fmt(4), "[[10.0000,0.0000]]");
+}
+
+#[should_panic]
+#[test]
+fn test_invalid_gradient_clipping() {
+    Sequential::new().set_gradient_clipping(GradientClipping::Value(0.0));
+}
+
+#[test]
+fn test_nn_initialize_weights() {
+    let mut nn = NeuralNetwork::new(4, 3);
+    let initializers = vec![Initializer::Orthogonal, Initializer::XavierUniform, Initializer::Zeros];
+    nn.initialize_weights(&initializers, 5);
+    let weights = nn.get_weights();
+    assert_eq!(weights.len(), 3);
+    assert!(weights[2].as_vec().iter().all(|&w| w == 0.0));
+
+    let mut other = NeuralNetwork::new(4, 3);
+    other.initialize_weights(&initializers, 5);
+    for (a, b) in weights.iter().zip(other.get_weights().iter()) {
+        assert_eq!(a.as_vec(), b.as_vec());
+    }
+
+    // The schemes stay with the layers
+    other.randomize_weights();
+    assert!(other.get_weights()[2].as_vec().iter().all(|&w| w == 0.0));
+    other.set_seed(5);
+    for (a, b) in weights.iter().zip(other.get_weights().iter()) {
+        assert_eq!(a.as_vec(), b.as_vec());
+    }
+}
+
+#[test]
+fn test_train_runs_in_training_mode() {
+    let data: TrainingData = (0..8).map(|i| (vec![10.0 + i as f64], vec![0.0])).collect();
+    let mut model = Sequential::new();
+    model.add(BatchNorm::new(1));
+    model.set_batch_size(4);
+    model.set_training(false);
+    let before = model.execute(&vec![10.0]);
+
+    // Running statistics only move in training mode, and with a zero
+    // learning rate they are all that changes
+    model.train(&data, 0.0, 1);
+    assert_ne!(model.execute(&vec![10.0]), before);
+    assert!(!model.is_training());
+}
+
+#[should_panic]
+#[test]
+fn test_sequential_set_layer_out_of_bound() {
+    let mut model = Sequential::new();
+    model.add(Dense::new(2, 2));
+    model.set_layer(1, Dense::new(2, 2));
+}
+
+#[should_panic]
+#[test]
+fn test_nn_initialize_weights_wrong_count() {
+    let mut nn = NeuralNetwork::new(2, 2);
+    nn.initialize_weights(&vec![Initializer::HeNormal], 0);
+}
+
+fn seeded_training(seed: u64, shuffle: bool) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let data: TrainingData = (0..20)
+        .map(|i| {
+            let x = i as f64 / 10.0 - 1.0;
+            (vec![x, x * x, (3.0 * x).sin()], vec![x.cos(), x * 0.5])
+        })
+        .collect();
+    let mut model = Sequential::new();
+    model.add(Dense::new(3, 8));
+    model.add(BatchNorm::new(8));
+    model.add(Activation::new(ActivationFunction::ReLU));
+    model.add(Dropout::new(0.3));
+    model.add(Dense::new(8, 2));
+    model.set_seed(seed);
+    model.set_shuffle(shuffle);
+    model.set_batch_size(5);
+    let losses = model.train(&data, 0.05, 20);
+    let parameters = model.parameters().iter().map(|p| p.as_vec()).collect();
+    return (losses, parameters);
+}
+
+#[test]
+fn test_seeded_training_is_reproducible() {
+    let (losses, parameters) = seeded_training(7, true);
+    let (same_losses, same_parameters) = seeded_training(7, true);
+    assert_eq!(losses, same_losses);
+    assert_eq!(parameters, same_parameters);
+
+    let (other_losses, other_parameters) = seeded_training(8, true);
+    assert_ne!(losses, other_losses);
+    assert_ne!(parameters, other_parameters);
+
+    // Shuffling draws from the same generator and changes the result
+    let (unshuffled_losses, _) = seeded_training(7, false);
+    assert_ne!(losses, unshuffled_losses);
+}
+
+#[test]
+fn test_nn_set_seed() {
+    let data: TrainingData = vec![
+        (vec![1.0, 0.0], vec![1.0, 0.0]),
+        (vec![0.0, 1.0], vec![0.0, 1.0]),
+    ];
+    let run = || -> Vec<Vec<f64>> {
+        let mut nn = NeuralNetwork::new(2, 2);
+        nn.set_seed(11);
+        let initial = nn.get_weights()[0].as_vec();
+        nn.randomize_weights();
+        assert_ne!(initial, nn.get_weights()[0].as_vec());
+        nn.train(&data, 0.5, 10);
+        return nn.get_weights().iter().map(|w| w.as_vec()).collect();
+    };
+    assert_eq!(run(), run());
+}
+
+fn line_data(slope: f64) -> TrainingData {
+    return (0..10).map(|i| {
+        let x = i as f64 / 5.0 - 1.0;
+        (vec![x, 1.0], vec![slope * x])
+    }).collect();
+}
+
+#[test]
+fn test_validation_history() {
+    let mut model = single_dense_model("[[0, 0]]", "[[0]]");
+    model.set_validation_data(&line_data(2.0));
+    let losses = model.train(&line_data(2.0), 0.1, 5);
+    assert_eq!(losses.len(), 5);
+    assert_eq!(model.get_validation_losses().len(), 5);
+    assert_eq!(model.get_validation_accuracies().len(), 5);
+    assert!(model.get_validation_losses()[4] < model.get_validation_losses()[0]);
+    assert_eq!(model.get_best_epoch(), None);
+}
+
+#[test]
+fn test_early_stopping_restores_best_weights() {
+    // Fitting y = x makes the loss on y = -x worse every epoch, so the first
+    // epoch is the best and training stops `patience` epochs later
+    let mut model = single_dense_model("[[0, 0]]", "[[0]]");
+    model.set_validation_data(&line_data(-1.0));
+    model.set_early_stopping(EarlyStopping::new(Metric::ValidationLoss, 3));
+    let losses = model.train(&line_data(1.0), 0.1, 100);
+    assert_eq!(losses.len(), 4);
+    assert_eq!(model.get_best_epoch(), Some(0));
+    assert!(model.get_validation_losses()[3] > model.get_validation_losses()[0]);
+
+    let mut one_epoch = single_dense_model("[[0, 0]]", "[[0]]");
+    one_epoch.train(&line_data(1.0), 0.1, 1);
+    assert_eq!(model.parameters()[0].as_vec(), one_epoch.parameters()[0].as_vec());
+    assert_eq!(model.parameters()[1].as_vec(), one_epoch.parameters()[1].as_vec());
+
+    // Without restoring, the weights from the last epoch are kept
+    let mut model = single_dense_model("[[0, 0]]", "[[0]]");
+    model.set_validation_data(&line_data(-1.0));
+    let mut early_stopping = EarlyStopping::new(Metric::ValidationLoss, 3);
+    early_stopping.set_restore_best_weights(false);
+    model.set_early_stopping(early_stopping);
+    model.train(&line_data(1.0), 0.1, 100);
+    let mut four_epochs = single_dense_model("[[0, 0]]", "[[0]]");
+    four_epochs.train(&line_data(1.0), 0.1, 4);
+    assert_eq!(model.parameters()[0].as_vec(), four_epochs.parameters()[0].as_vec());
+}
+
+#[test]
+fn test_early_stopping_min_delta() {
+    // The training loss keeps falling, but never by more than min_delta
+    let mut model = single_dense_model("[[0, 0]]", "[[0]]");
+    let mut early_stopping = EarlyStopping::new(Metric::TrainingLoss, 2);
+    early_stopping.set_min_delta(10.0);
+    model.set_early_stopping(early_stopping);
+    assert_eq!(model.train(&line_data(1.0), 0.1, 100).len(), 3);
+
+    let mut model = single_dense_model("[[0, 0]]", "[[0]]");
+    model.set_early_stopping(EarlyStopping::new(Metric::TrainingLoss, 2));
+    assert_eq!(model.train(&line_data(1.0), 0.1, 100).len(), 100);
+}
+
+#[test]
+fn test_early_stopping_restores_lowest_training_loss() {
+    // With a step this large the epoch averages and the losses of the weights
+    // left at the end of each epoch disagree about the best epoch
+    let data = line_data(1.0);
+    let mut model = single_dense_model("[[0, 0]]", "[[0]]");
+    model.set_early_stopping(EarlyStopping::new(Metric::TrainingLoss, 3));
+    let losses = model.train(&data, 0.9, 100);
+    assert!(losses[2] < losses[0]);
+    assert_eq!(model.get_best_epoch(), Some(0));
+    assert_eq!(losses.len(), 4);
+
+    let mut one_epoch = single_dense_model("[[0, 0]]", "[[0]]");
+    one_epoch.train(&data, 0.9, 1);
+    assert_eq!(model.parameters()[0].as_vec(), one_epoch.parameters()[0].as_vec());
+    assert_eq!(model.evaluate(&data), one_epoch.evaluate(&data));
+}
+
+#[test]
+fn test_early_stopping_on_accuracy() {
+    let data: TrainingData = vec![
+        (vec![1.0, 0.0], vec![1.0, 0.0]),
+        (vec![0.0, 1.0], vec![0.0, 1.0]),
+        (vec![0.9, 0.2], vec![1.0, 0.0]),
+        (vec![0.1, 0.8], vec![0.0, 1.0]),
+    ];
+    let mut dense = Dense::new(2, 2);
+    dense.set_parameters(vec![Matrix::from_str("[[0, 1], [1, 0]]"), Matrix::from_str("[[0, 0]]")]);
+    let mut model = Sequential::new();
+    model.add(dense);
+    model.set_validation_data(&data);
+    model.set_early_stopping(EarlyStopping::new(Metric::ValidationAccuracy, 5));
+    let losses = model.train(&data, 0.5, 500);
+
+    // Accuracy reaches 1 and cannot improve further
+    let accuracies = model.get_validation_accuracies();
+    let best = model.get_best_epoch().unwrap();
+    assert_eq!(accuracies[best], 1.0);
+    assert_eq!(losses.len(), best + 6);
+    assert!(accuracies[..best].iter().all(|&a| a < 1.0));
+    assert_eq!(model.accuracy(&data), 1.0);
+}
+
+#[should_panic]
+#[test]
+fn test_early_stopping_requires_validation_data() {
+    let mut model = single_dense_model("[[0, 0]]", "[[0]]");
+    model.set_early_stopping(EarlyStopping::new(Metric::ValidationAccuracy, 2));
+    model.train(&line_data(1.0), 0.1, 10);
+}
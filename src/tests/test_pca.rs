@@ -0,0 +1,77 @@
+use super::super::matrix::Matrix;
+use super::super::pca::{PCA, Components};
+
+// Points spread along the line y = x with a little noise across it
+fn line_data() -> Matrix {
+    return Matrix::from_str("[[1.0, 1.1], [2.0, 1.9], [3.0, 3.1], [4.0, 3.9], [5.0, 5.0]]");
+}
+
+#[test]
+fn test_pca_fit() {
+    let mut pca = PCA::new(Components::All);
+    pca.fit(&line_data());
+
+    assert_eq!(pca.get_num_components(), 2);
+    assert_eq!(pca.get_mean().iter().map(|m| format!("{:.2}", m)).collect::<Vec<String>>(), vec!["3.00", "3.00"]);
+
+    // The first axis follows the diagonal
+    let first = pca.get_components().get_row(0);
+    assert_eq!(format!("{:.2},{:.2}", first[0], first[1]), "0.71,0.70");
+
+    let ratios = pca.get_explained_variance_ratio();
+    assert!(ratios[0] > 0.99);
+    assert!((ratios.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    assert!(pca.get_explained_variance()[0] > pca.get_explained_variance()[1]);
+}
+
+#[test]
+fn test_pca_transform_and_inverse() {
+    let inputs = line_data();
+    let mut full = PCA::new(Components::All);
+    let projected = full.fit_transform(&inputs);
+    assert_eq!(projected.size(), (5, 2));
+
+    // With every component kept, the round trip is lossless
+    let restored = full.inverse_transform(&projected);
+    assert_eq!(restored.to_string_fmt(6), inputs.to_string_fmt(6));
+
+    // The projections are uncorrelated and centered
+    let covariance = projected.transpose().dot_prod(&projected);
+    assert!(covariance.get_at_index(0, 1).abs() < 1e-9);
+    let column_sum: f64 = (0..5).map(|i| projected.get_at_index(i, 0)).sum();
+    assert!(column_sum.abs() < 1e-9);
+
+    let mut reduced = PCA::new(Components::Count(1));
+    let projected = reduced.fit_transform(&inputs);
+    assert_eq!(projected.size(), (5, 1));
+    let restored = reduced.inverse_transform(&projected);
+    for i in 0..5 {
+        for j in 0..2 {
+            assert!((restored.get_at_index(i, j) - inputs.get_at_index(i, j)).abs() < 0.1);
+        }
+    }
+
+    assert_eq!(reduced.transform_vec(&vec![3.0, 3.0]), vec![0.0]);
+}
+
+#[test]
+fn test_pca_variance_threshold() {
+    // Third feature is constant, second is a small perturbation
+    let inputs = Matrix::from_str("[[1, 0.1, 7], [2, -0.1, 7], [3, 0.2, 7], [4, -0.2, 7], [5, 0.0, 7]]");
+
+    let mut pca = PCA::new(Components::VarianceRatio(0.95));
+    pca.fit(&inputs);
+    assert_eq!(pca.get_num_components(), 1);
+
+    let mut pca = PCA::new(Components::VarianceRatio(1.0));
+    pca.fit(&inputs);
+    assert_eq!(pca.get_num_components(), 2);
+    assert_eq!(pca.get_components().size(), (2, 3));
+}
+
+#[should_panic]
+#[test]
+fn test_pca_too_many_components() {
+    let mut pca = PCA::new(Components::Count(3));
+    pca.fit(&line_data());
+}
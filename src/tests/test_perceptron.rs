@@ -0,0 +1,70 @@
+use super::super::matrix::Matrix;
+use super::super::perceptron::{Perceptron, Adaline};
+
+fn gate_inputs() -> Matrix {
+    return Matrix::from_str("[[0, 0], [0, 1], [1, 0], [1, 1]]");
+}
+
+#[test]
+fn test_perceptron_learns_and_gate() {
+    let labels = vec![0, 0, 0, 1];
+    let mut perceptron = Perceptron::new(2);
+    perceptron.fit(&gate_inputs(), &labels);
+
+    assert!(perceptron.has_converged());
+    assert_eq!(perceptron.predict(&gate_inputs()), labels);
+    assert_eq!(perceptron.get_weights(), &vec![2.0, 1.0]);
+    assert_eq!(perceptron.get_bias(), -3.0);
+    assert_eq!(perceptron.get_errors_per_epoch(), &vec![2, 3, 3, 2, 1, 0]);
+}
+
+#[test]
+fn test_perceptron_fails_on_xor() {
+    let labels = vec![0, 1, 1, 0];
+    let mut perceptron = Perceptron::new(2);
+    perceptron.set_max_epochs(50);
+    perceptron.fit(&gate_inputs(), &labels);
+
+    // XOR is not linearly separable, so some sample is always wrong
+    assert!(!perceptron.has_converged());
+    assert_eq!(perceptron.get_errors_per_epoch().len(), 50);
+    assert!(perceptron.get_errors_per_epoch().iter().all(|&errors| errors > 0));
+    assert_ne!(perceptron.predict(&gate_inputs()), labels);
+}
+
+#[test]
+fn test_adaline_learns_or_gate() {
+    let labels = vec![0, 1, 1, 1];
+    let mut adaline = Adaline::new(2);
+    adaline.set_learning_rate(0.1);
+    adaline.set_epochs(100);
+    adaline.fit(&gate_inputs(), &labels);
+
+    assert_eq!(adaline.predict(&gate_inputs()), labels);
+
+    // The delta rule steadily lowers the squared error
+    let costs = adaline.get_costs();
+    assert_eq!(costs.len(), 100);
+    assert!(costs[99] < costs[0]);
+    for epoch in 1..costs.len() {
+        assert!(costs[epoch] <= costs[epoch - 1] + 1e-12);
+    }
+
+    // The least squares solution is w = (1, 1), b = -0.5
+    assert_eq!(format!("{:.2},{:.2}", adaline.get_weights()[0], adaline.get_weights()[1]), "1.00,1.00");
+    assert_eq!(format!("{:.2}", adaline.get_bias()), "-0.50");
+}
+
+#[test]
+fn test_adaline_fails_on_xor() {
+    let labels = vec![0, 1, 1, 0];
+    let mut adaline = Adaline::new(2);
+    adaline.set_learning_rate(0.1);
+    adaline.set_epochs(200);
+    adaline.fit(&gate_inputs(), &labels);
+
+    // The best linear fit to XOR is flat: zero weights and zero bias
+    for activation in adaline.activation(&gate_inputs()) {
+        assert!(activation.abs() < 1e-3);
+    }
+}
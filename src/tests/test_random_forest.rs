@@ -0,0 +1,85 @@
+extern crate rand;
+
+use rand::Rng;
+
+use super::super::matrix::Matrix;
+use super::super::random_forest::{RandomForestClassifier, RandomForestRegressor, MaxFeatures};
+
+// Feature 0 decides the class, features 1 and 2 are noise
+fn classification_data() -> (Matrix, Vec<usize>) {
+    let mut rng = rand::thread_rng();
+    let mut data: Vec<f64> = Vec::new();
+    let mut labels: Vec<usize> = Vec::new();
+    for i in 0..60 {
+        let label = i % 2;
+        data.push(label as f64 * 4.0 + rng.gen_range(-1.0, 1.0));
+        data.push(rng.gen_range(-1.0, 1.0));
+        data.push(rng.gen_range(-1.0, 1.0));
+        labels.push(label);
+    }
+    return (Matrix::from_vec(&data, 60, 3), labels);
+}
+
+#[test]
+fn test_random_forest_classifier() {
+    let (inputs, labels) = classification_data();
+    let mut forest = RandomForestClassifier::new(25, 2);
+    forest.set_num_threads(4);
+    forest.fit(&inputs, &labels);
+
+    assert_eq!(forest.get_trees().len(), 25);
+    assert_eq!(forest.predict(&inputs), labels);
+    assert_eq!(forest.predict(&Matrix::from_str("[[-0.5, 0, 0], [4.5, 0, 0]]")), vec![0, 1]);
+
+    let probabilities = forest.predict_proba(&inputs);
+    for i in 0..labels.len() {
+        let sum: f64 = probabilities.get_row(i).iter().sum();
+        assert!((sum - 1.0).abs() < 1e-10);
+    }
+
+    assert!(forest.get_oob_error().unwrap() < 0.1);
+
+    let importances = forest.feature_importances();
+    assert!((importances.iter().sum::<f64>() - 1.0).abs() < 1e-10);
+    assert!(importances[0] > importances[1]);
+    assert!(importances[0] > importances[2]);
+}
+
+#[test]
+fn test_random_forest_bagging_without_bootstrap() {
+    let (inputs, labels) = classification_data();
+    let mut bagging = RandomForestClassifier::new(5, 2);
+    bagging.set_max_features(MaxFeatures::All);
+    bagging.set_bootstrap(false);
+    bagging.set_num_threads(1);
+    bagging.set_max_depth(1);
+    bagging.fit(&inputs, &labels);
+
+    // Every tree sees every row, so there is nothing out of bag
+    assert_eq!(bagging.get_oob_error(), None);
+    for tree in bagging.get_trees() {
+        assert_eq!(tree.get_depth(), 1);
+        assert_eq!(tree.feature_importances()[0], 1.0);
+    }
+}
+
+#[test]
+fn test_random_forest_regressor() {
+    let mut data: Vec<f64> = Vec::new();
+    let mut targets: Vec<f64> = Vec::new();
+    for i in 0..50 {
+        data.push(i as f64);
+        data.push((i % 7) as f64);
+        targets.push(if i < 25 { 1.0 } else { 3.0 });
+    }
+    let inputs = Matrix::from_vec(&data, 50, 2);
+
+    let mut forest = RandomForestRegressor::new(20);
+    forest.fit(&inputs, &targets);
+
+    let predictions = forest.predict(&Matrix::from_str("[[5, 0], [45, 0]]"));
+    assert!((predictions[0] - 1.0).abs() < 0.1);
+    assert!((predictions[1] - 3.0).abs() < 0.1);
+    assert!(forest.get_oob_error().unwrap() < 0.5);
+    assert!(forest.feature_importances()[0] > 0.9);
+}
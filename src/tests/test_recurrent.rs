@@ -0,0 +1,262 @@
+extern crate rand;
+
+use rand::Rng;
+
+use super::super::matrix::Matrix;
+use super::super::loss::Loss;
+use super::super::recurrent::{Sequence, SequenceData, RecurrentLayer, RNN, LSTM, GRU, Cell, RecurrentNetwork, to_batch};
+
+fn random_matrix(rows: usize, cols: usize) -> Matrix {
+    let mut rng = rand::thread_rng();
+    let data: Vec<f64> = (0..(rows * cols)).map(|_| rng.gen_range(-1.0, 1.0)).collect();
+    return Matrix::from_vec(&data, rows, cols);
+}
+
+fn random_sequence(steps: usize, rows: usize, cols: usize) -> Sequence {
+    return (0..steps).map(|_| random_matrix(rows, cols)).collect();
+}
+
+fn copy_sequence(sequence: &Sequence) -> Sequence {
+    return sequence.iter().map(|m| m.clone()).collect();
+}
+
+// Loss is the sum of outputs weighted by fixed random coefficients, so its
+// gradient with respect to the outputs is just those coefficients
+fn weighted_sum(outputs: &Sequence, coefficients: &Sequence) -> f64 {
+    return outputs.iter().zip(coefficients)
+        .map(|(o, c)| o.hadamard(c).as_vec().iter().sum::<f64>())
+        .sum();
+}
+
+fn check_gradients(layer: &mut dyn RecurrentLayer, state_size: usize) {
+    let (batch_size, steps) = (2, 4);
+    let inputs = random_sequence(steps, batch_size, layer.input_size());
+    let state: Sequence = random_sequence(state_size, batch_size, layer.hidden_size());
+    let coefficients = random_sequence(steps, batch_size, layer.hidden_size());
+
+    layer.set_state(copy_sequence(&state));
+    layer.forward(&inputs);
+    let input_gradients = layer.backward(&coefficients);
+
+    let epsilon = 1e-6;
+    let loss = |layer: &mut dyn RecurrentLayer, inputs: &Sequence| -> f64 {
+        layer.set_state(copy_sequence(&state));
+        return weighted_sum(&layer.forward(inputs), &coefficients);
+    };
+
+    // Input gradients against central differences, including the first step
+    // whose effect flows through every later step
+    for t in 0..steps {
+        for (r, c) in [(0, 0), (1, 1)] {
+            let mut plus = copy_sequence(&inputs);
+            plus[t].set_at_index(r, c, inputs[t].get_at_index(r, c) + epsilon);
+            let mut minus = copy_sequence(&inputs);
+            minus[t].set_at_index(r, c, inputs[t].get_at_index(r, c) - epsilon);
+            let numeric = (loss(layer, &plus) - loss(layer, &minus)) / (2.0 * epsilon);
+            assert!((numeric - input_gradients[t].get_at_index(r, c)).abs() < 1e-6);
+        }
+    }
+
+    // Parameter gradients against central differences
+    let parameters: Vec<Matrix> = layer.parameters().into_iter().map(|p| p.clone()).collect();
+    let gradients: Vec<Matrix> = layer.gradients().into_iter().map(|g| g.clone()).collect();
+    for p in 0..parameters.len() {
+        let (rows, cols) = parameters[p].size();
+        for (r, c) in [(0, 0), (rows - 1, cols - 1), (rows / 2, cols / 2), (0, cols - 2)] {
+            let mut shifted: Vec<Matrix> = parameters.iter().map(|m| m.clone()).collect();
+            shifted[p].set_at_index(r, c, parameters[p].get_at_index(r, c) + epsilon);
+            layer.set_parameters(shifted);
+            let loss_plus = loss(layer, &inputs);
+            let mut shifted: Vec<Matrix> = parameters.iter().map(|m| m.clone()).collect();
+            shifted[p].set_at_index(r, c, parameters[p].get_at_index(r, c) - epsilon);
+            layer.set_parameters(shifted);
+            let loss_minus = loss(layer, &inputs);
+            let numeric = (loss_plus - loss_minus) / (2.0 * epsilon);
+            assert!((numeric - gradients[p].get_at_index(r, c)).abs() < 1e-6);
+        }
+    }
+}
+
+#[test]
+fn test_rnn_gradients() {
+    let mut rnn = RNN::new(3, 4);
+    assert_eq!(rnn.parameters().iter().map(|p| p.size()).collect::<Vec<_>>(), vec![(3, 4), (4, 4), (1, 4)]);
+    check_gradients(&mut rnn, 1);
+}
+
+#[test]
+fn test_lstm_gradients() {
+    let mut lstm = LSTM::new(3, 4);
+    assert_eq!(lstm.parameters().iter().map(|p| p.size()).collect::<Vec<_>>(), vec![(3, 16), (4, 16), (1, 16)]);
+    check_gradients(&mut lstm, 2);
+}
+
+#[test]
+fn test_gru_gradients() {
+    let mut gru = GRU::new(3, 4);
+    assert_eq!(gru.parameters().iter().map(|p| p.size()).collect::<Vec<_>>(), vec![(3, 12), (4, 12), (1, 12)]);
+    check_gradients(&mut gru, 1);
+}
+
+#[test]
+fn test_rnn_forward() {
+    let mut rnn = RNN::new(1, 1);
+    rnn.set_parameters(vec![Matrix::from_str("[[1]]"), Matrix::from_str("[[0.5]]"), Matrix::from_str("[[0]]")]);
+    let outputs = rnn.forward(&vec![Matrix::from_str("[[1], [0]]"), Matrix::from_str("[[0], [1]]")]);
+
+    // h1 = tanh(x1), h2 = tanh(x2 + 0.5 * h1), one row per sequence
+    let first = 1f64.tanh();
+    let second = (0.5 * first).tanh();
+    assert_eq!(outputs[0].to_string_fmt(6), format!("[[{:.6}],[{:.6}]]", first, 0.0));
+    assert_eq!(outputs[1].to_string_fmt(6), format!("[[{:.6}],[{:.6}]]", second, 1f64.tanh()));
+}
+
+fn check_state_carry_over(layer: &mut dyn RecurrentLayer) {
+    let inputs = random_sequence(6, 2, layer.input_size());
+    let whole = layer.forward(&inputs);
+
+    // Feeding the same sequence in two chunks continues from the carried state
+    layer.reset_state();
+    let mut chunked = layer.forward(&inputs[..4].iter().map(|m| m.clone()).collect());
+    chunked.extend(layer.forward(&inputs[4..].iter().map(|m| m.clone()).collect()));
+    let format = |sequence: &Sequence| -> Vec<String> { sequence.iter().map(|m| m.to_string_fmt(10)).collect() };
+    assert_eq!(format(&whole), format(&chunked));
+
+    // Without a reset the sequence starts from a different state
+    let again = layer.forward(&inputs);
+    assert_ne!(format(&whole)[0], format(&again)[0]);
+    layer.reset_state();
+    let again = layer.forward(&inputs);
+    assert_eq!(format(&whole), format(&again));
+}
+
+#[test]
+fn test_state_carry_over() {
+    check_state_carry_over(&mut RNN::new(2, 3));
+    check_state_carry_over(&mut LSTM::new(2, 3));
+    check_state_carry_over(&mut GRU::new(2, 3));
+
+    // A different batch size starts from zeros instead of the carried state
+    let mut gru = GRU::new(2, 3);
+    gru.forward(&random_sequence(2, 4, 2));
+    let state = gru.get_state();
+    assert_eq!(state[0].size(), (4, 3));
+    let outputs = gru.forward(&vec![random_matrix(1, 2)]);
+    assert_eq!(outputs[0].size(), (1, 3));
+}
+
+#[test]
+fn test_lstm_forget_bias() {
+    let lstm = LSTM::new(2, 2);
+    assert_eq!(lstm.parameters()[2].to_string(), "[[0,0,1,1,0,0,0,0]]");
+    assert_eq!(lstm.get_state().len(), 0);
+}
+
+#[test]
+fn test_recurrent_network_set_seed() {
+    let data = echo_data(8, 4);
+    let mut first = RecurrentNetwork::new(Cell::LSTM, 1, 3, 1);
+    let mut second = RecurrentNetwork::new(Cell::LSTM, 1, 3, 1);
+    first.set_seed(5);
+    second.set_seed(5);
+    // Redrawing keeps the forget bias
+    assert_eq!(first.get_layer().parameters()[2].to_string(), "[[0,0,0,1,1,1,0,0,0,0,0,0]]");
+    assert_eq!(first.train(&data, 0.5, 3), second.train(&data, 0.5, 3));
+    assert_eq!(first.get_output_weights().as_vec(), second.get_output_weights().as_vec());
+}
+
+#[test]
+fn test_to_batch() {
+    let batch = to_batch(&vec![
+        vec![vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0]],
+        vec![vec![7.0, 8.0], vec![9.0, 10.0], vec![11.0, 12.0]],
+    ]);
+    assert_eq!(batch.len(), 3);
+    assert_eq!(batch[1].to_string(), "[[3,4],[9,10]]");
+}
+
+#[should_panic]
+#[test]
+fn test_to_batch_unequal_lengths() {
+    to_batch(&vec![vec![vec![1.0], vec![2.0]], vec![vec![1.0]]]);
+}
+
+#[should_panic]
+#[test]
+fn test_backward_before_forward() {
+    let mut rnn = RNN::new(1, 1);
+    rnn.backward(&vec![random_matrix(1, 1)]);
+}
+
+// Random bit strings where the target at each step is the bit seen on the
+// previous step, which the network can only produce by remembering it
+fn echo_data(count: usize, steps: usize) -> SequenceData {
+    let mut rng = rand::thread_rng();
+    return (0..count).map(|_| {
+        let bits: Vec<f64> = (0..steps).map(|_| if rng.gen::<bool>() { 1.0 } else { 0.0 }).collect();
+        let inputs: Vec<Vec<f64>> = bits.iter().map(|b| vec![*b]).collect();
+        let targets: Vec<Vec<f64>> = (0..steps).map(|t| vec![if t == 0 { 0.0 } else { bits[t - 1] }]).collect();
+        (inputs, targets)
+    }).collect();
+}
+
+fn check_learns_echo(cell: Cell, learning_rate: f64, epochs: usize) {
+    let data = echo_data(32, 8);
+    let mut network = RecurrentNetwork::new(cell, 1, 6, 1);
+    network.set_batch_size(4);
+    let losses = network.train(&data, learning_rate, epochs);
+    assert_eq!(losses.len(), epochs);
+    assert!(losses[epochs - 1] < losses[0]);
+
+    let test = echo_data(10, 8);
+    assert!(network.evaluate(&test) < 0.01);
+    for (inputs, targets) in &test {
+        let outputs = network.predict(inputs);
+        for (output, target) in outputs.iter().zip(targets) {
+            assert!((output[0] - target[0]).abs() < 0.3);
+        }
+    }
+}
+
+#[test]
+fn test_rnn_learns_echo() {
+    check_learns_echo(Cell::RNN, 0.5, 200);
+}
+
+#[test]
+fn test_lstm_learns_echo() {
+    check_learns_echo(Cell::LSTM, 1.0, 300);
+}
+
+#[test]
+fn test_gru_learns_echo() {
+    check_learns_echo(Cell::GRU, 1.0, 300);
+}
+
+#[test]
+fn test_truncated_stateful_training() {
+    // One long stream split into consecutive windows, predicting which of
+    // two classes comes next in a repeating a a b pattern
+    let pattern = [0, 0, 1];
+    let stream: Vec<usize> = (0..61).map(|t| pattern[t % 3]).collect();
+    let one_hot = |class: usize| if class == 0 { vec![1.0, 0.0] } else { vec![0.0, 1.0] };
+    let data: SequenceData = vec![(
+        stream[..60].iter().map(|c| one_hot(*c)).collect(),
+        stream[1..].iter().map(|c| one_hot(*c)).collect(),
+    )];
+
+    let mut network = RecurrentNetwork::new(Cell::GRU, 2, 8, 2);
+    network.set_loss(Loss::CrossEntropy);
+    network.set_truncation(5);
+    network.set_stateful(true);
+    let losses = network.train(&data, 0.1, 100);
+    assert!(losses[99] < 0.1);
+
+    // After the first step, the next class follows from the two steps before
+    network.reset_state();
+    let probabilities = network.predict(&data[0].0);
+    for (t, p) in probabilities.iter().enumerate().skip(1) {
+        assert_eq!(format!("{:.4}", p[0] + p[1]), "1.0000");
+        assert_eq!(if p[1] > 0.5 { 1 } else { 0 }, stream[t + 1]);
+    }
+}
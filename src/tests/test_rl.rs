@@ -0,0 +1,365 @@
+extern crate rand;
+
+use super::super::rl::{Environment, DiscreteEnvironment, GridWorld, FrozenLake, EpsilonGreedy, QLearning, SARSA};
+use super::super::rl::{CartPole, ReplayBuffer, Transition, DQN};
+use super::super::neural_network::NeuralNetwork;
+use super::super::rl::{UP, RIGHT, DOWN, LEFT};
+
+#[test]
+fn test_grid_world_moves() {
+    let mut grid = GridWorld::new(3, 2);
+    grid.add_wall(1, 0);
+    assert_eq!(grid.num_states(), 6);
+    assert_eq!(grid.num_actions(), 4);
+
+    assert_eq!(grid.reset(), 0);
+    // Off the grid and into the wall both leave the agent in place
+    assert_eq!(grid.step(UP), (0, -1.0, false, false));
+    assert_eq!(grid.step(LEFT), (0, -1.0, false, false));
+    assert_eq!(grid.step(RIGHT), (0, -1.0, false, false));
+    assert_eq!(grid.step(DOWN), (3, -1.0, false, false));
+    assert_eq!(grid.step(RIGHT), (4, -1.0, false, false));
+    assert_eq!(grid.step(RIGHT), (5, -1.0, true, false));
+}
+
+#[test]
+fn test_grid_world_max_steps() {
+    let mut grid = GridWorld::new(3, 3);
+    grid.set_max_steps(2);
+    grid.reset();
+    assert_eq!(grid.step(UP), (0, -1.0, false, false));
+    // Running out of moves cuts the episode off without making it terminal
+    assert_eq!(grid.step(UP), (0, -1.0, false, true));
+
+    // Reaching the goal on the last allowed move is terminal
+    let mut grid = GridWorld::new(2, 1);
+    grid.set_max_steps(1);
+    grid.reset();
+    assert_eq!(grid.step(RIGHT), (1, -1.0, true, false));
+}
+
+#[test]
+fn test_frozen_lake_moves() {
+    let mut lake = FrozenLake::new();
+    assert_eq!(lake.num_states(), 16);
+    assert_eq!(lake.reset(), 0);
+    assert_eq!(lake.get_tile(5), 'H');
+
+    // Falling into a hole ends the episode without reward
+    assert_eq!(lake.step(RIGHT), (1, 0.0, false, false));
+    assert_eq!(lake.step(DOWN), (5, 0.0, true, false));
+
+    lake.reset();
+    for &action in &[DOWN, DOWN, RIGHT, DOWN, RIGHT] {
+        assert!(!lake.step(action).2);
+    }
+    assert_eq!(lake.step(RIGHT), (15, 1.0, true, false));
+
+    lake.set_max_steps(1);
+    lake.reset();
+    assert_eq!(lake.step(UP), (0, 0.0, false, true));
+}
+
+#[test]
+fn test_frozen_lake_slippery() {
+    // Pushing right from the top left corner slides up (stay), right or down
+    let mut lake = FrozenLake::from_map(&vec!["SFF", "FFF", "FFG"]);
+    lake.set_slippery(true);
+    lake.set_seed(1);
+    let mut seen = vec![false; 9];
+    for _ in 0..200 {
+        lake.reset();
+        let (state, _, _, _) = lake.step(RIGHT);
+        seen[state] = true;
+    }
+    assert_eq!(seen, vec![true, true, false, true, false, false, false, false, false]);
+}
+
+#[should_panic]
+#[test]
+fn test_frozen_lake_invalid_map() {
+    FrozenLake::from_map(&vec!["SFX", "FFG"]);
+}
+
+#[test]
+fn test_epsilon_greedy() {
+    let mut rng = rand::thread_rng();
+    let q_values = vec![0.1, 0.7, 0.3];
+    let greedy = EpsilonGreedy::new(0.0);
+    for _ in 0..20 {
+        assert_eq!(greedy.choose(&q_values, &mut rng), 1);
+    }
+
+    let random = EpsilonGreedy::new(1.0);
+    let mut counts = [0; 3];
+    for _ in 0..3000 {
+        counts[random.choose(&q_values, &mut rng)] += 1;
+    }
+    assert!(counts.iter().all(|&count| count > 800));
+
+    let mut decaying = EpsilonGreedy::new(1.0);
+    decaying.set_decay(0.5, 0.2);
+    decaying.decay();
+    assert_eq!(decaying.get_epsilon(), 0.5);
+    decaying.decay();
+    decaying.decay();
+    assert_eq!(decaying.get_epsilon(), 0.2);
+}
+
+#[test]
+fn test_q_learning_update() {
+    let mut agent = QLearning::new(2, 2);
+    agent.set_learning_rate(0.5);
+    agent.set_discount(0.9);
+
+    agent.update(1, 0, 2.0, 1, true);
+    assert_eq!(agent.get_q_table().get_at_index(1, 0), 1.0);
+
+    // Bootstraps from max_a Q(1, a) = 1.0: 0.5 * (1 + 0.9 * 1.0)
+    agent.update(0, 1, 1.0, 1, false);
+    assert_eq!(format!("{:.4}", agent.get_q_table().get_at_index(0, 1)), "0.9500");
+}
+
+#[test]
+fn test_sarsa_update() {
+    let mut agent = SARSA::new(2, 2);
+    agent.set_learning_rate(0.5);
+    agent.set_discount(0.9);
+
+    agent.update(1, 0, 2.0, 1, 0, true);
+    assert_eq!(agent.get_q_table().get_at_index(1, 0), 1.0);
+
+    // Bootstraps from the action actually taken next, Q(1, 1) = 0.0
+    agent.update(0, 1, 1.0, 1, 1, false);
+    assert_eq!(format!("{:.4}", agent.get_q_table().get_at_index(0, 1)), "0.5000");
+}
+
+#[test]
+fn test_time_limit_is_not_terminal() {
+    // The goal is walled off, so every episode is cut off after five moves
+    // and each move costs 1. Only bootstrapping through the cut-off lets the
+    // values approach the infinite horizon return -1 / (1 - 0.9) = -10
+    // instead of treating the time limit as an escape.
+    let mut grid = GridWorld::new(3, 1);
+    grid.add_wall(1, 0);
+    grid.set_max_steps(5);
+
+    let mut q_learning = QLearning::new(grid.num_states(), grid.num_actions());
+    q_learning.set_learning_rate(0.5);
+    q_learning.set_discount(0.9);
+    q_learning.set_seed(1);
+    q_learning.train(&mut grid, 200);
+    assert!(q_learning.get_q_table().get_row(0).iter().all(|&q| q < -9.5));
+
+    let mut sarsa = SARSA::new(grid.num_states(), grid.num_actions());
+    sarsa.set_learning_rate(0.5);
+    sarsa.set_discount(0.9);
+    sarsa.set_seed(1);
+    sarsa.train(&mut grid, 200);
+    assert!(sarsa.get_q_table().get_row(0).iter().all(|&q| q < -9.5));
+}
+
+fn walled_grid() -> GridWorld {
+    // ...
+    // .#.
+    // ..G
+    let mut grid = GridWorld::new(3, 3);
+    grid.add_wall(1, 1);
+    return grid;
+}
+
+#[test]
+fn test_q_learning_grid_world() {
+    let mut grid = walled_grid();
+    let mut agent = QLearning::new(grid.num_states(), grid.num_actions());
+    agent.set_learning_rate(0.5);
+    agent.set_seed(2);
+    let returns = agent.train(&mut grid, 300);
+    assert_eq!(returns.len(), 300);
+
+    // The shortest path to the goal takes four moves
+    assert_eq!(agent.evaluate(&mut grid, 1), -4.0);
+    assert!(agent.get_exploration().get_epsilon() < 1.0);
+}
+
+#[test]
+fn test_sarsa_grid_world() {
+    let mut grid = walled_grid();
+    let mut agent = SARSA::new(grid.num_states(), grid.num_actions());
+    agent.set_learning_rate(0.5);
+    agent.set_seed(3);
+    agent.train(&mut grid, 300);
+    assert_eq!(agent.evaluate(&mut grid, 1), -4.0);
+
+    let policy = grid.render_policy(&agent.policy());
+    assert_eq!(policy.lines().nth(1).unwrap().chars().nth(1), Some('#'));
+    assert!(policy.ends_with("G\n"));
+}
+
+#[test]
+fn test_q_learning_frozen_lake() {
+    let mut lake = FrozenLake::new();
+    let mut agent = QLearning::new(lake.num_states(), lake.num_actions());
+    agent.set_learning_rate(0.5);
+    agent.set_discount(0.95);
+    agent.set_seed(4);
+    agent.train(&mut lake, 1000);
+    assert_eq!(agent.evaluate(&mut lake, 1), 1.0);
+}
+
+#[test]
+fn test_tabular_set_seed() {
+    // Seeding both the slippery lake and the agents' exploration makes
+    // training repeat exactly
+    let train = |seed: u64| -> (String, String) {
+        let mut lake = FrozenLake::new();
+        lake.set_slippery(true);
+        lake.set_seed(seed);
+        let mut q_learning = QLearning::new(lake.num_states(), lake.num_actions());
+        q_learning.set_seed(seed);
+        q_learning.train(&mut lake, 50);
+        let mut sarsa = SARSA::new(lake.num_states(), lake.num_actions());
+        sarsa.set_seed(seed);
+        sarsa.train(&mut lake, 50);
+        return (q_learning.get_q_table().to_string_fmt(12), sarsa.get_q_table().to_string_fmt(12));
+    };
+    assert_eq!(train(6), train(6));
+}
+
+#[should_panic]
+#[test]
+fn test_q_learning_mismatched_environment() {
+    let mut agent = QLearning::new(4, 4);
+    agent.train(&mut FrozenLake::new(), 1);
+}
+
+#[test]
+fn test_cart_pole_dynamics() {
+    let mut cart_pole = CartPole::new();
+    assert_eq!(cart_pole.num_actions(), 2);
+    let state = cart_pole.reset();
+    assert!(state.iter().all(|x| x.abs() <= 0.05));
+
+    // Pushing right from rest accelerates the cart right and tips the pole left
+    cart_pole.set_state(vec![0.0, 0.0, 0.0, 0.0]);
+    let (state, reward, terminated, truncated) = cart_pole.step(1);
+    let formatted: Vec<String> = state.iter().map(|x| format!("{:.5}", x)).collect();
+    assert_eq!(formatted, vec!["0.00000", "0.19512", "0.00000", "-0.29268"]);
+    assert_eq!(reward, 1.0);
+    assert!(!terminated && !truncated);
+
+    // Always pushing the same way soon drops the pole
+    cart_pole.reset();
+    let mut steps = 1;
+    while !cart_pole.step(0).2 {
+        steps += 1;
+    }
+    assert!(steps < 50);
+    assert!(cart_pole.get_state()[2] > 0.2);
+}
+
+#[test]
+fn test_cart_pole_max_steps() {
+    let mut cart_pole = CartPole::new();
+    cart_pole.set_max_steps(1);
+    cart_pole.reset();
+    let (_, _, terminated, truncated) = cart_pole.step(0);
+    assert!(!terminated && truncated);
+}
+
+fn transition(reward: f64) -> Transition {
+    return Transition { state: vec![0.0], action: 0, reward, next_state: vec![0.0], terminated: true };
+}
+
+#[test]
+fn test_replay_buffer() {
+    let mut buffer = ReplayBuffer::new(3);
+    assert!(buffer.is_empty());
+    for i in 0..5 {
+        buffer.push(transition(i as f64));
+    }
+
+    // The two oldest transitions were overwritten
+    assert_eq!(buffer.len(), 3);
+    let mut rewards: Vec<f64> = buffer.get_transitions().iter().map(|t| t.reward).collect();
+    rewards.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(rewards, vec![2.0, 3.0, 4.0]);
+
+    let mut rng = rand::thread_rng();
+    let sample = buffer.sample(2, &mut rng);
+    assert_eq!(sample.len(), 2);
+    assert_ne!(sample[0].reward, sample[1].reward);
+    assert_eq!(buffer.sample(10, &mut rng).len(), 3);
+}
+
+#[test]
+fn test_dqn_target_sync() {
+    let mut rng = rand::thread_rng();
+    let mut agent = DQN::new(1, 2, 2);
+    agent.set_min_replay_size(1);
+    agent.set_target_sync_interval(3);
+    let weights = |network: &NeuralNetwork| -> Vec<String> {
+        return network.get_weights().iter().map(|w| w.to_string_fmt(12)).collect();
+    };
+    assert_eq!(weights(agent.get_network()), weights(agent.get_target_network()));
+
+    agent.observe(transition(1.0), &mut rng);
+    agent.observe(transition(1.0), &mut rng);
+    assert_ne!(weights(agent.get_network()), weights(agent.get_target_network()));
+    agent.observe(transition(1.0), &mut rng);
+    assert_eq!(weights(agent.get_network()), weights(agent.get_target_network()));
+    assert_eq!(agent.get_num_steps(), 3);
+}
+
+#[test]
+fn test_dqn_keeps_truncated_transitions_open() {
+    // One move per episode is too short for the pole to fall, so every
+    // episode is cut off and no stored transition may claim a terminal state
+    let mut cart_pole = CartPole::new();
+    cart_pole.set_max_steps(1);
+    let mut agent = DQN::new(4, 2, 2);
+    agent.set_min_replay_size(1);
+    agent.train(&mut cart_pole, 10);
+    assert_eq!(agent.get_replay_buffer().len(), 10);
+    assert!(agent.get_replay_buffer().get_transitions().iter().all(|t| !t.terminated));
+}
+
+// One step episodes where action 1 pays 1 and action 0 pays nothing
+struct Bandit;
+
+impl Environment for Bandit {
+    type State = Vec<f64>;
+
+    fn num_actions(&self) -> usize {
+        return 2;
+    }
+
+    fn reset(&mut self) -> Vec<f64> {
+        return vec![1.0];
+    }
+
+    fn step(&mut self, action: usize) -> (Vec<f64>, f64, bool, bool) {
+        return (vec![1.0], if action == 1 { 1.0 } else { 0.0 }, true, false);
+    }
+}
+
+#[test]
+fn test_dqn_learns_bandit() {
+    let mut agent = DQN::new(1, 2, 1);
+    agent.set_value_scale(1.0);
+    agent.set_min_replay_size(10);
+    agent.set_batch_size(8);
+    agent.set_learning_rate(0.5);
+    let returns = agent.train(&mut Bandit, 300);
+    assert_eq!(returns.len(), 300);
+    assert_eq!(agent.get_replay_buffer().len(), 300);
+
+    // Epsilon decays once per episode down to its floor
+    assert_eq!(format!("{:.2}", agent.get_exploration().get_epsilon()), "0.05");
+
+    let q_values = agent.q_values(&vec![1.0]);
+    assert!(q_values[1] > 0.8);
+    assert!(q_values[0] < 0.2);
+    assert_eq!(agent.act(&vec![1.0]), 1);
+    assert_eq!(agent.evaluate(&mut Bandit, 5), 1.0);
+}
@@ -0,0 +1,105 @@
+use super::super::matrix::Matrix;
+use super::super::svm::{SVC, Kernel};
+
+#[test]
+fn test_svm_kernels() {
+    let a = vec![1.0, 2.0];
+    let b = vec![3.0, 0.0];
+    assert_eq!(Kernel::Linear.compute(&a, &b), 3.0);
+    assert_eq!(Kernel::Polynomial { degree: 2, gamma: 1.0, coef0: 1.0 }.compute(&a, &b), 16.0);
+    assert_eq!(format!("{:.10}", Kernel::RBF { gamma: 0.5 }.compute(&a, &b)), "0.0183156389");
+}
+
+#[test]
+fn test_svm_linear_hard_margin() {
+    let inputs = Matrix::from_str("[[0, 0], [2, 2]]");
+    let labels = vec![0, 1];
+    let mut model = SVC::new(Kernel::Linear);
+    model.set_c(100.0);
+    model.fit(&inputs, &labels);
+
+    // The maximum margin hyperplane is x + y = 2
+    let weights = model.get_coefficients();
+    assert_eq!(format!("{:.4},{:.4}", weights[0], weights[1]), "0.5000,0.5000");
+    assert_eq!(format!("{:.4}", model.get_intercept()), "-1.0000");
+    assert_eq!(model.get_support_indices(), &vec![0, 1]);
+
+    let scores = model.decision_function(&inputs);
+    assert_eq!(format!("{:.4},{:.4}", scores[0], scores[1]), "-1.0000,1.0000");
+}
+
+#[test]
+fn test_svm_linear_support_vectors() {
+    let inputs = Matrix::from_str("[[0, 0], [1, 0], [0, 1], [-1, -1], [3, 3], [4, 3], [3, 4], [5, 5]]");
+    let labels = vec![0, 0, 0, 0, 1, 1, 1, 1];
+    let mut model = SVC::new(Kernel::Linear);
+    model.set_c(10.0);
+    model.fit(&inputs, &labels);
+
+    assert_eq!(model.predict(&inputs), labels);
+    // Only the points closest to the boundary hold up the margin
+    assert_eq!(model.get_support_indices(), &vec![1, 2, 4]);
+    assert_eq!(model.get_support_vectors().to_string(), "[[1,0],[0,1],[3,3]]");
+    assert_eq!(model.get_dual_coefficients().len(), 3);
+    let sum: f64 = model.get_dual_coefficients().iter().sum();
+    assert!(sum.abs() < 1e-9);
+}
+
+#[test]
+fn test_svm_nonlinear_kernels_solve_xor() {
+    let inputs = Matrix::from_str("[[0, 0], [0, 1], [1, 0], [1, 1]]");
+    let labels = vec![0, 1, 1, 0];
+
+    let mut rbf = SVC::new(Kernel::RBF { gamma: 2.0 });
+    rbf.set_c(10.0);
+    rbf.fit(&inputs, &labels);
+    assert_eq!(rbf.predict(&inputs), labels);
+    assert_eq!(rbf.get_support_indices().len(), 4);
+
+    let mut polynomial = SVC::new(Kernel::Polynomial { degree: 2, gamma: 1.0, coef0: 1.0 });
+    polynomial.set_c(10.0);
+    polynomial.fit(&inputs, &labels);
+    assert_eq!(polynomial.predict(&inputs), labels);
+
+    let mut linear = SVC::new(Kernel::Linear);
+    linear.fit(&inputs, &labels);
+    assert_ne!(linear.predict(&inputs), labels);
+}
+
+#[test]
+fn test_svm_soft_margin() {
+    // The last point is an outlier sitting among the other class
+    let inputs = Matrix::from_str("[[0, 0], [1, 0], [0, 1], [4, 4], [5, 4], [4, 5], [4.5, 4.5]]");
+    let labels = vec![0, 0, 0, 1, 1, 1, 0];
+
+    let mut soft = SVC::new(Kernel::Linear);
+    soft.set_c(0.1);
+    soft.fit(&inputs, &labels);
+    assert_eq!(soft.predict(&Matrix::from_str("[[0.5, 0.5], [4.5, 4.5]]")), vec![0, 1]);
+
+    // Every alpha is capped at C
+    for coefficient in soft.get_dual_coefficients() {
+        assert!(coefficient.abs() <= 0.1 + 1e-12);
+    }
+}
+
+#[should_panic]
+#[test]
+fn test_svm_coefficients_require_linear_kernel() {
+    let mut model = SVC::new(Kernel::RBF { gamma: 1.0 });
+    model.fit(&Matrix::from_str("[[0], [1]]"), &vec![0, 1]);
+    model.get_coefficients();
+}
+
+#[test]
+fn test_svm_selects_negative_class_violator() {
+    // After the first step the most violating sample is from class 0, and the
+    // curvature must not depend on its label for the pair choice to stay exact
+    let mut model = SVC::new(Kernel::Linear);
+    model.fit(&Matrix::from_str("[[-1, -3], [-3, -1], [-3, 1], [-3, 0]]"), &vec![0, 0, 0, 1]);
+
+    assert_eq!(model.get_num_iterations(), 2);
+    assert_eq!(model.get_support_indices(), &vec![1, 2, 3]);
+    let coefficients: Vec<String> = model.get_dual_coefficients().iter().map(|a| format!("{:.4}", a)).collect();
+    assert_eq!(coefficients, vec!["-0.5000", "-0.5000", "1.0000"]);
+}
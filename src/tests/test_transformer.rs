@@ -0,0 +1,160 @@
+extern crate rand;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use super::super::matrix::Matrix;
+use super::super::layers::Layer;
+use super::super::layers::test::{check_gradients, random_matrix};
+use super::super::transformer::{scaled_dot_product_attention, positional_encoding};
+use super::super::transformer::{MultiHeadAttention, TransformerBlock, Embedding};
+
+#[test]
+fn test_scaled_dot_product_attention() {
+    let queries = Matrix::from_str("[[1, 0], [0, 1]]");
+    let values = Matrix::from_str("[[1, 2], [3, 4]]");
+
+    // Each query matches its own key with a score of 1 / sqrt(2)
+    let (outputs, weights) = scaled_dot_product_attention(&queries, &queries, &values, false);
+    assert_eq!(weights.to_string_fmt(6), "[[0.669762,0.330238],[0.330238,0.669762]]");
+    assert_eq!(outputs.to_string_fmt(6), "[[1.660477,2.660477],[2.339523,3.339523]]");
+
+    // The first position can only see itself under a causal mask
+    let (outputs, weights) = scaled_dot_product_attention(&queries, &queries, &values, true);
+    assert_eq!(weights.to_string_fmt(6), "[[1.000000,0.000000],[0.330238,0.669762]]");
+    assert_eq!(outputs.to_string_fmt(6), "[[1.000000,2.000000],[2.339523,3.339523]]");
+}
+
+#[test]
+fn test_positional_encoding() {
+    let encoding = positional_encoding(2, 4);
+    assert_eq!(encoding.to_string_fmt(6), "[[0.000000,1.000000,0.000000,1.000000],[0.841471,0.540302,0.010000,0.999950]]");
+}
+
+#[test]
+fn test_multi_head_attention_gradients() {
+    let mut attention = MultiHeadAttention::new(4, 2);
+    assert_eq!(attention.parameters().len(), 8);
+    check_gradients(&mut attention, 5, 4);
+
+    let mut attention = MultiHeadAttention::new(4, 2);
+    attention.set_causal(true);
+    check_gradients(&mut attention, 5, 4);
+}
+
+#[test]
+fn test_transformer_block_gradients() {
+    let mut block = TransformerBlock::new(4, 2, 6);
+    block.set_causal(true);
+    assert_eq!(block.parameters().len(), 16);
+    check_gradients(&mut block, 4, 4);
+}
+
+#[test]
+fn test_causal_attention_ignores_later_positions() {
+    let mut attention = MultiHeadAttention::new(6, 3);
+    attention.set_causal(true);
+    let inputs = random_matrix(4, 6);
+    let outputs = attention.forward(&inputs);
+
+    let weights = attention.get_attention_weights();
+    assert_eq!(weights.len(), 3);
+    for head in weights {
+        for i in 0..4 {
+            assert_eq!(format!("{:.10}", head.get_row(i).iter().sum::<f64>()), "1.0000000000");
+            for j in (i + 1)..4 {
+                assert_eq!(head.get_at_index(i, j), 0.0);
+            }
+        }
+    }
+
+    // Changing the last position leaves every earlier output untouched
+    let mut changed = inputs.clone();
+    for c in 0..6 {
+        changed.set_at_index(3, c, 5.0);
+    }
+    let changed_outputs = attention.forward(&changed);
+    for r in 0..3 {
+        assert_eq!(format!("{:?}", outputs.get_row(r)), format!("{:?}", changed_outputs.get_row(r)));
+    }
+    assert_ne!(format!("{:?}", outputs.get_row(3)), format!("{:?}", changed_outputs.get_row(3)));
+}
+
+#[should_panic]
+#[test]
+fn test_multi_head_attention_uneven_heads() {
+    MultiHeadAttention::new(5, 2);
+}
+
+#[test]
+fn test_embedding() {
+    let mut embedding = Embedding::new(3, 2);
+    embedding.set_weights(Matrix::from_str("[[1, 2], [3, 4], [5, 6]]"));
+    let outputs = embedding.forward(&vec![2, 0, 2]);
+    assert_eq!(outputs.to_string(), "[[5,6],[1,2],[5,6]]");
+
+    // Repeated tokens accumulate gradient
+    embedding.backward(&Matrix::from_str("[[1, 1], [2, 2], [3, 3]]"));
+    assert_eq!(embedding.get_gradients().to_string(), "[[2,2],[0,0],[4,4]]");
+    embedding.update(0.5);
+    assert_eq!(embedding.get_weights().to_string(), "[[0,1],[3,4],[3,4]]");
+    assert_eq!(embedding.get_gradients().to_string(), "[[0,0],[0,0],[0,0]]");
+}
+
+#[should_panic]
+#[test]
+fn test_embedding_unknown_token() {
+    let mut embedding = Embedding::new(3, 2);
+    embedding.forward(&vec![3]);
+}
+
+#[test]
+fn test_embedding_initialize_with_seed() {
+    let mut first = Embedding::new(5, 4);
+    let mut second = Embedding::new(5, 4);
+    first.initialize(&mut StdRng::seed_from_u64(8));
+    second.initialize(&mut StdRng::seed_from_u64(8));
+    assert_eq!(first.get_weights().as_vec(), second.get_weights().as_vec());
+    assert!(first.get_weights().as_vec().iter().all(|w| w.abs() <= 0.5));
+}
+
+#[test]
+fn test_transformer_block_learns_to_copy_previous_token() {
+    // With a causal mask, predict the token one position back from
+    // one-hot inputs plus positional encodings
+    let (length, size) = (6, 8);
+    let mut block = TransformerBlock::new(size, 2, 16);
+    block.set_causal(true);
+    let encoding = positional_encoding(length, size);
+    let mut rng = rand::thread_rng();
+    let mut sample = || -> (Matrix, Matrix) {
+        let tokens: Vec<usize> = (0..length).map(|_| rng.gen_range(0, 4)).collect();
+        let mut inputs = Matrix::new();
+        inputs.zero_fill(length, size);
+        let mut targets = Matrix::new();
+        targets.zero_fill(length, size);
+        for (t, &token) in tokens.iter().enumerate() {
+            inputs.set_at_index(t, token, 1.0);
+            targets.set_at_index(t, if t == 0 { token } else { tokens[t - 1] }, 1.0);
+        }
+        return (inputs.add(&encoding), targets);
+    };
+
+    let mut loss = |block: &mut TransformerBlock, train: bool| -> f64 {
+        let (inputs, targets) = sample();
+        let outputs = block.forward(&inputs).get_columns(0, 4);
+        let errors = outputs.subtract(&targets.get_columns(0, 4));
+        if train {
+            let zeros = Matrix::from_vec(&vec![0.0; length * (size - 4)], length, size - 4);
+            block.backward(&Matrix::concat_columns(&vec![errors.clone(), zeros]));
+            block.update(0.02);
+        }
+        return errors.as_vec().iter().map(|e| e * e).sum::<f64>() / length as f64;
+    };
+    let before: f64 = (0..50).map(|_| loss(&mut block, false)).sum::<f64>() / 50.0;
+    for _ in 0..1500 {
+        loss(&mut block, true);
+    }
+    let after: f64 = (0..50).map(|_| loss(&mut block, false)).sum::<f64>() / 50.0;
+    assert!(after < 0.25 * before);
+}
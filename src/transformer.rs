@@ -0,0 +1,425 @@
+extern crate rand;
+
+use rand::{Rng, FromEntropy};
+use rand::rngs::StdRng;
+
+use super::math::softmax;
+use super::matrix::Matrix;
+use super::layers::{Layer, Dense, LayerNorm};
+
+// Every layer here works on one sequence at a time, stored as a
+// sequence_length x features Matrix with one row per position.
+
+fn zeros(rows: usize, cols: usize) -> Matrix {
+    let mut result = Matrix::new();
+    result.zero_fill(rows, cols);
+    return result;
+}
+
+fn uniform(rows: usize, cols: usize, bound: f64, rng: &mut StdRng) -> Matrix {
+    let data: Vec<f64> = (0..(rows * cols)).map(|_| rng.gen_range(-bound, bound)).collect();
+    return Matrix::from_vec(&data, rows, cols);
+}
+
+fn scaled(matrix: &Matrix, factor: f64) -> Matrix {
+    let mut result = matrix.clone();
+    result.map(|x| x * factor);
+    return result;
+}
+
+// Returns softmax(Q K^T / sqrt(d_k)) V and the attention weights, one row of
+// weights per query. With a causal mask each position only attends to itself
+// and earlier positions.
+pub fn scaled_dot_product_attention(queries: &Matrix, keys: &Matrix, values: &Matrix, causal: bool) -> (Matrix, Matrix) {
+    if queries.size().1 != keys.size().1 || keys.size().0 != values.size().0 {
+        panic!("Incompatible Matrix Dimensions!");
+    }
+    let (rows, cols) = (queries.size().0, keys.size().0);
+    let scores = scaled(&queries.dot_prod(&keys.transpose()), 1.0 / (queries.size().1 as f64).sqrt());
+    let mut data: Vec<f64> = Vec::with_capacity(rows * cols);
+    for i in 0..rows {
+        let mut row = scores.get_row(i);
+        if causal {
+            for score in row.iter_mut().skip(i + 1) {
+                *score = f64::NEG_INFINITY;
+            }
+        }
+        data.extend(softmax(&row));
+    }
+    let weights = Matrix::from_vec(&data, rows, cols);
+    return (weights.dot_prod(values), weights);
+}
+
+// Fixed sinusoidal encodings from "Attention Is All You Need":
+// PE(pos, 2i) = sin(pos / 10000^(2i / d)), PE(pos, 2i + 1) = cos(...)
+pub fn positional_encoding(length: usize, d_model: usize) -> Matrix {
+    let mut result = zeros(length, d_model);
+    for pos in 0..length {
+        for i in 0..d_model {
+            let angle = pos as f64 / 10000f64.powf((i - i % 2) as f64 / d_model as f64);
+            result.set_at_index(pos, i, if i % 2 == 0 { angle.sin() } else { angle.cos() });
+        }
+    }
+    return result;
+}
+
+// Projects the inputs to queries, keys and values, attends separately in each
+// head over a slice of d_model / num_heads features, and projects the
+// concatenated heads back to d_model
+pub struct MultiHeadAttention {
+    d_model: usize,
+    num_heads: usize,
+    causal: bool,
+    query: Dense,
+    key: Dense,
+    value: Dense,
+    output: Dense,
+    // Per head projections and attention weights from the last forward pass
+    queries: Vec<Matrix>,
+    keys: Vec<Matrix>,
+    values: Vec<Matrix>,
+    attention_weights: Vec<Matrix>,
+}
+
+impl MultiHeadAttention {
+    pub fn new(d_model: usize, num_heads: usize) -> MultiHeadAttention {
+        if num_heads == 0 || d_model == 0 || !d_model.is_multiple_of(num_heads) {
+            panic!("Model size must be a non-zero multiple of the number of heads!");
+        }
+        MultiHeadAttention {
+            d_model,
+            num_heads,
+            causal: false,
+            query: Dense::new(d_model, d_model),
+            key: Dense::new(d_model, d_model),
+            value: Dense::new(d_model, d_model),
+            output: Dense::new(d_model, d_model),
+            queries: Vec::new(),
+            keys: Vec::new(),
+            values: Vec::new(),
+            attention_weights: Vec::new(),
+        }
+    }
+
+    // Mask out attention to later positions, as needed for language models
+    pub fn set_causal(&mut self, causal: bool) {
+        self.causal = causal;
+    }
+
+    // One sequence_length x sequence_length Matrix per head
+    pub fn get_attention_weights(&self) -> &Vec<Matrix> {
+        return &self.attention_weights;
+    }
+
+    // Splits the projections into heads and attends in each, returning the
+    // concatenated heads along with the per head queries, keys, values and
+    // attention weights
+    fn attend(&self, queries: &Matrix, keys: &Matrix, values: &Matrix) -> (Matrix, [Vec<Matrix>; 4]) {
+        let head_size = self.d_model / self.num_heads;
+        let mut heads: Vec<Matrix> = Vec::new();
+        let mut cache: [Vec<Matrix>; 4] = [Vec::new(), Vec::new(), Vec::new(), Vec::new()];
+        for h in 0..self.num_heads {
+            let queries = queries.get_columns(h * head_size, head_size);
+            let keys = keys.get_columns(h * head_size, head_size);
+            let values = values.get_columns(h * head_size, head_size);
+            let (head, weights) = scaled_dot_product_attention(&queries, &keys, &values, self.causal);
+            heads.push(head);
+            cache[0].push(queries);
+            cache[1].push(keys);
+            cache[2].push(values);
+            cache[3].push(weights);
+        }
+        return (Matrix::concat_columns(&heads), cache);
+    }
+}
+
+impl Layer for MultiHeadAttention {
+    fn predict(&self, inputs: &Matrix) -> Matrix {
+        if inputs.size().1 != self.d_model {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        let (heads, _) = self.attend(&self.query.predict(inputs), &self.key.predict(inputs), &self.value.predict(inputs));
+        return self.output.predict(&heads);
+    }
+
+    fn forward(&mut self, inputs: &Matrix) -> Matrix {
+        if inputs.size().1 != self.d_model {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        let queries = self.query.forward(inputs);
+        let keys = self.key.forward(inputs);
+        let values = self.value.forward(inputs);
+        let (heads, [queries, keys, values, weights]) = self.attend(&queries, &keys, &values);
+        self.queries = queries;
+        self.keys = keys;
+        self.values = values;
+        self.attention_weights = weights;
+        return self.output.forward(&heads);
+    }
+
+    fn backward(&mut self, output_gradients: &Matrix) -> Matrix {
+        if self.attention_weights.is_empty() {
+            panic!("Forward must be called before backward!");
+        }
+        let head_size = self.d_model / self.num_heads;
+        let scale = 1.0 / (head_size as f64).sqrt();
+        let head_gradients = self.output.backward(output_gradients);
+        let mut query_gradients: Vec<Matrix> = Vec::new();
+        let mut key_gradients: Vec<Matrix> = Vec::new();
+        let mut value_gradients: Vec<Matrix> = Vec::new();
+        for h in 0..self.num_heads {
+            let gradients = head_gradients.get_columns(h * head_size, head_size);
+            let weights = &self.attention_weights[h];
+            let weight_gradients = gradients.dot_prod(&self.values[h].transpose());
+            value_gradients.push(weights.transpose().dot_prod(&gradients));
+
+            // Softmax backward per row: dS = A * (dA - sum(dA * A)); masked
+            // positions have zero weight and so get no gradient
+            let (rows, cols) = weights.size();
+            let mut score_gradients = zeros(rows, cols);
+            for i in 0..rows {
+                let dot: f64 = (0..cols).map(|j| weights.get_at_index(i, j) * weight_gradients.get_at_index(i, j)).sum();
+                for j in 0..cols {
+                    let value = weights.get_at_index(i, j) * (weight_gradients.get_at_index(i, j) - dot) * scale;
+                    score_gradients.set_at_index(i, j, value);
+                }
+            }
+            query_gradients.push(score_gradients.dot_prod(&self.keys[h]));
+            key_gradients.push(score_gradients.transpose().dot_prod(&self.queries[h]));
+        }
+        let input_gradients = self.query.backward(&Matrix::concat_columns(&query_gradients));
+        let input_gradients = input_gradients.add(&self.key.backward(&Matrix::concat_columns(&key_gradients)));
+        return input_gradients.add(&self.value.backward(&Matrix::concat_columns(&value_gradients)));
+    }
+
+    // Weights and biases of the query, key, value and output projections
+    fn parameters(&self) -> Vec<&Matrix> {
+        let mut result = self.query.parameters();
+        result.extend(self.key.parameters());
+        result.extend(self.value.parameters());
+        result.extend(self.output.parameters());
+        return result;
+    }
+
+    fn gradients(&self) -> Vec<&Matrix> {
+        let mut result = self.query.gradients();
+        result.extend(self.key.gradients());
+        result.extend(self.value.gradients());
+        result.extend(self.output.gradients());
+        return result;
+    }
+
+    fn initialize(&mut self, rng: &mut StdRng) {
+        self.query.initialize(rng);
+        self.key.initialize(rng);
+        self.value.initialize(rng);
+        self.output.initialize(rng);
+    }
+
+    fn regularized(&self) -> Vec<bool> {
+        let mut result = self.query.regularized();
+        result.extend(self.key.regularized());
+        result.extend(self.value.regularized());
+        result.extend(self.output.regularized());
+        return result;
+    }
+
+    fn parameters_and_gradients(&mut self) -> Vec<(&mut Matrix, &mut Matrix)> {
+        let mut result = self.query.parameters_and_gradients();
+        result.extend(self.key.parameters_and_gradients());
+        result.extend(self.value.parameters_and_gradients());
+        result.extend(self.output.parameters_and_gradients());
+        return result;
+    }
+}
+
+// Pre-norm transformer encoder block:
+//   x = x + attention(norm(x))
+//   x = x + feed_forward(norm(x))
+// where the feed forward network is two dense layers with a ReLU between
+pub struct TransformerBlock {
+    attention_norm: LayerNorm,
+    attention: MultiHeadAttention,
+    feed_forward_norm: LayerNorm,
+    hidden: Dense,
+    output: Dense,
+    hidden_outputs: Matrix,
+}
+
+impl TransformerBlock {
+    pub fn new(d_model: usize, num_heads: usize, feed_forward_size: usize) -> TransformerBlock {
+        if feed_forward_size == 0 {
+            panic!("Feed forward size must be at least 1!");
+        }
+        TransformerBlock {
+            attention_norm: LayerNorm::new(d_model),
+            attention: MultiHeadAttention::new(d_model, num_heads),
+            feed_forward_norm: LayerNorm::new(d_model),
+            hidden: Dense::new(d_model, feed_forward_size),
+            output: Dense::new(feed_forward_size, d_model),
+            hidden_outputs: Matrix::new(),
+        }
+    }
+
+    pub fn set_causal(&mut self, causal: bool) {
+        self.attention.set_causal(causal);
+    }
+
+    pub fn get_attention(&self) -> &MultiHeadAttention {
+        return &self.attention;
+    }
+
+}
+
+impl Layer for TransformerBlock {
+    fn predict(&self, inputs: &Matrix) -> Matrix {
+        let attended = inputs.add(&self.attention.predict(&self.attention_norm.predict(inputs)));
+        let mut hidden = self.hidden.predict(&self.feed_forward_norm.predict(&attended));
+        hidden.map(|x| x.max(0.0));
+        return attended.add(&self.output.predict(&hidden));
+    }
+
+    fn forward(&mut self, inputs: &Matrix) -> Matrix {
+        let attended = inputs.add(&self.attention.forward(&self.attention_norm.forward(inputs)));
+        let mut hidden = self.hidden.forward(&self.feed_forward_norm.forward(&attended));
+        hidden.map(|x| x.max(0.0));
+        let outputs = attended.add(&self.output.forward(&hidden));
+        self.hidden_outputs = hidden;
+        return outputs;
+    }
+
+    fn backward(&mut self, output_gradients: &Matrix) -> Matrix {
+        let mut hidden_gradients = self.output.backward(output_gradients);
+        let mask = self.hidden_outputs.as_vec();
+        hidden_gradients.map_with_index(|g, i| if mask[i] > 0.0 { g } else { 0.0 });
+        let norm_gradients = self.hidden.backward(&hidden_gradients);
+        let attended_gradients = output_gradients.add(&self.feed_forward_norm.backward(&norm_gradients));
+        let attention_gradients = self.attention.backward(&attended_gradients);
+        return attended_gradients.add(&self.attention_norm.backward(&attention_gradients));
+    }
+
+    // Attention norm, attention, feed forward norm, then the feed forward
+    // weights and biases
+    fn parameters(&self) -> Vec<&Matrix> {
+        let mut result = self.attention_norm.parameters();
+        result.extend(self.attention.parameters());
+        result.extend(self.feed_forward_norm.parameters());
+        result.extend(self.hidden.parameters());
+        result.extend(self.output.parameters());
+        return result;
+    }
+
+    fn gradients(&self) -> Vec<&Matrix> {
+        let mut result = self.attention_norm.gradients();
+        result.extend(self.attention.gradients());
+        result.extend(self.feed_forward_norm.gradients());
+        result.extend(self.hidden.gradients());
+        result.extend(self.output.gradients());
+        return result;
+    }
+
+    fn initialize(&mut self, rng: &mut StdRng) {
+        self.attention_norm.initialize(rng);
+        self.attention.initialize(rng);
+        self.feed_forward_norm.initialize(rng);
+        self.hidden.initialize(rng);
+        self.output.initialize(rng);
+    }
+
+    fn regularized(&self) -> Vec<bool> {
+        let mut result = self.attention_norm.regularized();
+        result.extend(self.attention.regularized());
+        result.extend(self.feed_forward_norm.regularized());
+        result.extend(self.hidden.regularized());
+        result.extend(self.output.regularized());
+        return result;
+    }
+
+    fn parameters_and_gradients(&mut self) -> Vec<(&mut Matrix, &mut Matrix)> {
+        let mut result = self.attention_norm.parameters_and_gradients();
+        result.extend(self.attention.parameters_and_gradients());
+        result.extend(self.feed_forward_norm.parameters_and_gradients());
+        result.extend(self.hidden.parameters_and_gradients());
+        result.extend(self.output.parameters_and_gradients());
+        return result;
+    }
+}
+
+// Lookup table mapping token ids to learned vectors
+pub struct Embedding {
+    weights: Matrix,
+    gradients: Matrix,
+    tokens: Vec<usize>,
+}
+
+impl Embedding {
+    pub fn new(vocab_size: usize, size: usize) -> Embedding {
+        if vocab_size == 0 || size == 0 {
+            panic!("Vocabulary and embedding sizes must be at least 1!");
+        }
+        let mut embedding = Embedding {
+            weights: zeros(vocab_size, size),
+            gradients: zeros(vocab_size, size),
+            tokens: Vec::new(),
+        };
+        embedding.initialize(&mut StdRng::from_entropy());
+        return embedding;
+    }
+
+    // Redraws every vector uniformly in +-1 / sqrt(size) from `rng`
+    pub fn initialize(&mut self, rng: &mut StdRng) {
+        let (vocab_size, size) = self.weights.size();
+        self.weights = uniform(vocab_size, size, 1.0 / (size as f64).sqrt(), rng);
+    }
+
+    // One row per token id
+    pub fn get_weights(&self) -> &Matrix {
+        return &self.weights;
+    }
+
+    pub fn set_weights(&mut self, weights: Matrix) {
+        if weights.size() != self.weights.size() {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        self.weights = weights;
+    }
+
+    pub fn get_gradients(&self) -> &Matrix {
+        return &self.gradients;
+    }
+
+    pub fn forward(&mut self, tokens: &Vec<usize>) -> Matrix {
+        let (vocab_size, size) = self.weights.size();
+        let mut data: Vec<f64> = Vec::with_capacity(tokens.len() * size);
+        for &token in tokens {
+            if token >= vocab_size {
+                panic!("Token is outside the vocabulary!");
+            }
+            data.extend(self.weights.get_row(token));
+        }
+        self.tokens = tokens.clone();
+        return Matrix::from_vec(&data, tokens.len(), size);
+    }
+
+    // Adds each row of the gradients to the row of the token it came from
+    pub fn backward(&mut self, output_gradients: &Matrix) {
+        if output_gradients.size() != (self.tokens.len(), self.weights.size().1) {
+            panic!("Incompatible Matrix Dimensions!");
+        }
+        for (r, &token) in self.tokens.iter().enumerate() {
+            for c in 0..self.weights.size().1 {
+                let value = self.gradients.get_at_index(token, c) + output_gradients.get_at_index(r, c);
+                self.gradients.set_at_index(token, c, value);
+            }
+        }
+    }
+
+    pub fn update(&mut self, learning_rate: f64) {
+        self.weights = self.weights.subtract(&scaled(&self.gradients, learning_rate));
+        self.gradients.map(|_| 0.0);
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/test_transformer.rs"]
+mod test;